@@ -0,0 +1,37 @@
+//! Configuration JSON Schema Example
+//!
+//! Prints the JSON Schema of `Config<AppConfig>` so editors and CI can validate
+//! `config/*.toml` before deploy. The application's own section is included by
+//! deriving `schemars::JsonSchema` next to `Deserialize`.
+//!
+//! Run with:
+//! ```bash
+//! cargo run --example config_schema --features json-schema > config.schema.json
+//! ```
+//!
+//! Then point a schema-aware editor at it, e.g. with a comment on the first
+//! line of `config/dev.toml` (Taplo / Even Better TOML):
+//! ```toml
+//! #:schema ./config.schema.json
+//! ```
+
+use axum_conf::{Config, Result};
+use serde::{Deserialize, Serialize};
+
+/// Application settings, flattened next to `[http]` and `[logging]`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+struct AppConfig {
+    /// Greeting returned by the index route.
+    #[serde(default = "default_greeting")]
+    greeting: String,
+}
+
+fn default_greeting() -> String {
+    "Hello, World!".to_string()
+}
+
+fn main() -> Result<()> {
+    let schema = Config::<AppConfig>::json_schema();
+    println!("{schema:#}");
+    Ok(())
+}
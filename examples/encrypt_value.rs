@@ -0,0 +1,37 @@
+//! Encrypted Configuration Values Example
+//!
+//! Generates a key, or encrypts a value for a `Sensitive<String>` field so that
+//! the config file can be committed with its secrets.
+//!
+//! Run with:
+//! ```bash
+//! # Print a new base64 key; keep it in a secret store, not in git
+//! cargo run --example encrypt_value --features encryption -- generate-key
+//!
+//! # Encrypt a value with the key the service will use
+//! AXUM_CONF_ENCRYPTION_KEY=... \
+//!     cargo run --example encrypt_value --features encryption -- 'my-client-secret'
+//! ```
+//!
+//! Then paste the output into the config file:
+//! ```toml
+//! [http.oidc]
+//! client_secret = "enc:v1:..."
+//! ```
+
+use axum_conf::{EncryptionKey, Error, Result};
+
+fn main() -> Result<()> {
+    let Some(arg) = std::env::args().nth(1) else {
+        return Err(Error::invalid_input(
+            "usage: encrypt_value generate-key | encrypt_value <plaintext>",
+        ));
+    };
+
+    if arg == "generate-key" {
+        println!("{}", EncryptionKey::generate().to_base64().expose_secret());
+    } else {
+        println!("{}", EncryptionKey::from_env()?.encrypt(&arg)?);
+    }
+    Ok(())
+}
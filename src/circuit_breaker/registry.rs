@@ -63,6 +63,33 @@ impl CircuitBreakerRegistry {
             .clone()
     }
 
+    /// Applies a new configuration to the registry.
+    ///
+    /// Targets whose settings changed are replaced by a fresh (closed) circuit
+    /// breaker, new targets are added, and targets removed from the
+    /// configuration fall back to the default settings. Unchanged targets keep
+    /// their state.
+    pub fn reconfigure(&self, config: &CircuitBreakerConfig) {
+        for (name, target_config) in &config.targets {
+            let unchanged = self
+                .breakers
+                .get(name)
+                .is_some_and(|b| b.value().config() == target_config);
+            if !unchanged {
+                let state = Arc::new(CircuitBreakerState::new(target_config.clone()));
+                self.breakers.insert(name.clone(), state);
+            }
+        }
+        for mut entry in self.breakers.iter_mut() {
+            if !config.targets.contains_key(entry.key())
+                && entry.value().config() != &self.default_config
+            {
+                *entry.value_mut() =
+                    Arc::new(CircuitBreakerState::new(self.default_config.clone()));
+            }
+        }
+    }
+
     /// List all registered target names.
     pub fn targets(&self) -> Vec<String> {
         self.breakers.iter().map(|r| r.key().clone()).collect()
@@ -121,6 +148,42 @@ mod tests {
         assert!(registry.get("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_reconfigure_replaces_only_changed_targets() {
+        let strict = CircuitBreakerTargetConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        };
+        let mut targets = HashMap::new();
+        targets.insert("db".to_string(), strict.clone());
+        targets.insert("api".to_string(), strict.clone());
+        let registry = CircuitBreakerRegistry::new(&CircuitBreakerConfig { targets });
+        let dynamic = registry.get_or_default("dynamic");
+        let db = registry.get("db").unwrap();
+
+        let mut targets = HashMap::new();
+        targets.insert("db".to_string(), strict);
+        targets.insert(
+            "payments".to_string(),
+            CircuitBreakerTargetConfig {
+                failure_threshold: 7,
+                ..Default::default()
+            },
+        );
+        registry.reconfigure(&CircuitBreakerConfig { targets });
+
+        assert!(Arc::ptr_eq(&registry.get("db").unwrap(), &db));
+        assert!(Arc::ptr_eq(&registry.get("dynamic").unwrap(), &dynamic));
+        assert_eq!(
+            registry.get("payments").unwrap().config().failure_threshold,
+            7
+        );
+        assert_eq!(
+            registry.get("api").unwrap().config(),
+            &CircuitBreakerTargetConfig::default()
+        );
+    }
+
     #[test]
     fn test_get_or_default_creates_new() {
         let registry = CircuitBreakerRegistry::default();
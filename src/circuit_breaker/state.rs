@@ -70,6 +70,11 @@ impl CircuitBreakerState {
         }
     }
 
+    /// The configuration this circuit breaker was created with.
+    pub(crate) fn config(&self) -> &CircuitBreakerTargetConfig {
+        &self.config
+    }
+
     /// Acquire the inner lock, recovering from poisoning.
     ///
     /// A panic in another thread while holding the lock must not cascade into a
@@ -0,0 +1,242 @@
+//!
+//! Encrypted values inside configuration files.
+//!
+//! A [`Sensitive<String>`](crate::Sensitive) field may hold an encrypted value
+//! such as `client_secret = "enc:v1:..."`, so that config files with secrets can
+//! be committed. The value is decrypted while the configuration is deserialized,
+//! with the key read from:
+//!
+//! 1. `AXUM_CONF_ENCRYPTION_KEY` — the base64-encoded 32-byte key, or
+//! 2. `AXUM_CONF_ENCRYPTION_KEY_FILE` — the path of a file holding it.
+//!
+//! `v1` values are ChaCha20-Poly1305 ciphertexts: `enc:v1:` followed by the
+//! base64 encoding of a random 96-bit nonce and the ciphertext with its tag.
+//! Use [`EncryptionKey`] (or `examples/encrypt_value.rs`) to create keys and
+//! encrypt values.
+//!
+use {
+    crate::{Error, Result, Sensitive},
+    base64::{Engine, engine::general_purpose::STANDARD as BASE64},
+    chacha20poly1305::{
+        ChaCha20Poly1305, Key, Nonce,
+        aead::{Aead, AeadCore, KeyInit, OsRng},
+    },
+    std::fmt,
+    zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing},
+};
+
+/// Environment variable holding the base64-encoded decryption key.
+pub const ENCRYPTION_KEY_VAR: &str = "AXUM_CONF_ENCRYPTION_KEY";
+
+/// Environment variable naming a file that holds the base64-encoded key.
+pub const ENCRYPTION_KEY_FILE_VAR: &str = "AXUM_CONF_ENCRYPTION_KEY_FILE";
+
+/// Prefix of the values encrypted with the current format.
+const V1_PREFIX: &str = super::secrets::ENCRYPTED_PREFIX;
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// A 256-bit key for encrypting and decrypting configuration values.
+///
+/// The key bytes are zeroized on drop and hidden from `Debug` output.
+///
+/// # Example
+///
+/// ```rust
+/// use axum_conf::EncryptionKey;
+///
+/// let key = EncryptionKey::generate();
+/// let encrypted = key.encrypt("s3cr3t").unwrap();
+/// assert!(encrypted.starts_with("enc:v1:"));
+///
+/// // Store `key.to_base64()` in AXUM_CONF_ENCRYPTION_KEY (or a key file) and
+/// // write `encrypted` into the config file.
+/// assert_eq!(key.decrypt(&encrypted).unwrap().expose_secret(), "s3cr3t");
+/// ```
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct EncryptionKey([u8; KEY_LEN]);
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptionKey(****)")
+    }
+}
+
+impl EncryptionKey {
+    /// Generates a new random key.
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    /// Decodes a base64-encoded key, as produced by [`Self::to_base64`].
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let decoded = Zeroizing::new(
+            BASE64
+                .decode(encoded.trim())
+                .map_err(|_| Error::config("The encryption key is not valid base64"))?,
+        );
+        let bytes: [u8; KEY_LEN] = decoded.as_slice().try_into().map_err(|_| {
+            Error::config(format!(
+                "The encryption key must be {KEY_LEN} bytes, got {}",
+                decoded.len()
+            ))
+        })?;
+        Ok(Self(bytes))
+    }
+
+    /// Encodes the key as base64, for `AXUM_CONF_ENCRYPTION_KEY` or a key file.
+    pub fn to_base64(&self) -> Sensitive<String> {
+        Sensitive(BASE64.encode(self.0))
+    }
+
+    /// Reads the key from `AXUM_CONF_ENCRYPTION_KEY`, or else from the file
+    /// named by `AXUM_CONF_ENCRYPTION_KEY_FILE`.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Same as [`Self::from_env`], looking variables up with `var`.
+    pub(crate) fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if let Some(encoded) = var(ENCRYPTION_KEY_VAR).map(Sensitive) {
+            return Self::from_base64(encoded.expose_secret());
+        }
+        if let Some(path) = var(ENCRYPTION_KEY_FILE_VAR) {
+            let encoded = Sensitive(std::fs::read_to_string(&path).map_err(|e| {
+                Error::config(format!(
+                    "Failed to read the encryption key file `{path}`: {e}"
+                ))
+            })?);
+            return Self::from_base64(encoded.expose_secret());
+        }
+        Err(Error::config(format!(
+            "Encrypted configuration values need a key: set {ENCRYPTION_KEY_VAR} or \
+             {ENCRYPTION_KEY_FILE_VAR}"
+        )))
+    }
+
+    /// Encrypts `plaintext` into an `enc:v1:...` value for a config file.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| Error::internal("Failed to encrypt the configuration value"))?;
+        let mut payload = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        payload.extend_from_slice(&nonce);
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{V1_PREFIX}{}", BASE64.encode(payload)))
+    }
+
+    /// Decrypts an `enc:v1:...` value.
+    pub fn decrypt(&self, value: &str) -> Result<Sensitive<String>> {
+        let Some(encoded) = value.strip_prefix(V1_PREFIX) else {
+            return Err(Error::config(
+                "Unsupported encrypted value: expected the `enc:v1:` format",
+            ));
+        };
+        let payload = BASE64
+            .decode(encoded.trim())
+            .map_err(|_| Error::config("Encrypted value is not valid base64"))?;
+        if payload.len() < NONCE_LEN + TAG_LEN {
+            return Err(Error::config("Encrypted value is truncated"));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce
+            .try_into()
+            .map_err(|_| Error::config("Encrypted value is truncated"))?;
+        let plaintext = Zeroizing::new(
+            self.cipher()
+                .decrypt(&Nonce::from(nonce), ciphertext)
+                .map_err(|_| {
+                    Error::config(
+                        "Failed to decrypt configuration value: wrong key or corrupted value",
+                    )
+                })?,
+        );
+        let text = std::str::from_utf8(&plaintext)
+            .map_err(|_| Error::config("Decrypted configuration value is not valid UTF-8"))?;
+        Ok(Sensitive(text.to_string()))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&Key::from(self.0))
+    }
+}
+
+/// Decrypts an `enc:v1:` configuration value with the key from the environment.
+pub(crate) fn decrypt_config_value(value: &str) -> Result<Sensitive<String>> {
+    EncryptionKey::from_env()?.decrypt(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_round_trip_and_are_randomized() {
+        let key = EncryptionKey::generate();
+        let a = key.encrypt("hunter2").unwrap();
+        let b = key.encrypt("hunter2").unwrap();
+        assert!(a.starts_with(V1_PREFIX));
+        assert_ne!(a, b, "each value gets a fresh nonce");
+        assert_eq!(key.decrypt(&a).unwrap().expose_secret(), "hunter2");
+        assert_eq!(key.decrypt(&b).unwrap().expose_secret(), "hunter2");
+    }
+
+    #[test]
+    fn wrong_key_or_tampering_is_rejected_without_leaking() {
+        let key = EncryptionKey::generate();
+        let encrypted = key.encrypt("hunter2").unwrap();
+
+        let err = EncryptionKey::generate().decrypt(&encrypted).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Configuration);
+        assert!(!err.to_string().contains("hunter2"));
+
+        let mut payload = BASE64.decode(&encrypted[V1_PREFIX.len()..]).unwrap();
+        let last = payload.len() - 1;
+        payload[last] ^= 1;
+        let tampered = format!("{V1_PREFIX}{}", BASE64.encode(payload));
+        assert!(key.decrypt(&tampered).is_err());
+
+        for bad in ["enc:v2:AAAA", "enc:v1:!!!", "enc:v1:AAAA"] {
+            assert!(key.decrypt(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn key_is_read_from_variable_or_file() {
+        let key = EncryptionKey::generate();
+        let encoded = key.to_base64().expose_secret().clone();
+        let encrypted = key.encrypt("s3cr3t").unwrap();
+
+        let from_var =
+            EncryptionKey::from_vars(|name| (name == ENCRYPTION_KEY_VAR).then(|| encoded.clone()))
+                .unwrap();
+        assert_eq!(
+            from_var.decrypt(&encrypted).unwrap().expose_secret(),
+            "s3cr3t"
+        );
+
+        let path = std::env::temp_dir().join(format!("axum-conf-key-{}", uuid::Uuid::now_v7()));
+        std::fs::write(&path, format!("{encoded}\n")).unwrap();
+        let from_file = EncryptionKey::from_vars(|name| {
+            (name == ENCRYPTION_KEY_FILE_VAR).then(|| path.display().to_string())
+        });
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            from_file
+                .unwrap()
+                .decrypt(&encrypted)
+                .unwrap()
+                .expose_secret(),
+            "s3cr3t"
+        );
+
+        let err = EncryptionKey::from_vars(|_| None).unwrap_err();
+        assert!(err.to_string().contains(ENCRYPTION_KEY_VAR), "{err}");
+        assert!(EncryptionKey::from_base64("c2hvcnQ=").is_err());
+        assert_eq!(format!("{key:?}"), "EncryptionKey(****)");
+    }
+}
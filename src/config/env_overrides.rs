@@ -0,0 +1,336 @@
+//!
+//! Environment-variable overrides for individual configuration keys.
+//!
+//! Any key of `Config<T>` — including fields of the flattened application
+//! type `T` — can be overridden with an environment variable named after its
+//! TOML path: the [`ENV_OVERRIDE_PREFIX`], then each path segment in upper
+//! case, joined by [`ENV_OVERRIDE_SEPARATOR`]:
+//!
+//! | Variable | TOML key |
+//! |----------|----------|
+//! | `AXUM_CONF__HTTP__BIND_PORT=8080` | `http.bind_port = 8080` |
+//! | `AXUM_CONF__LOGGING__FORMAT=compact` | `logging.format = "compact"` |
+//! | `AXUM_CONF__HTTP__DIRECTORIES__0__CACHE_MAX_AGE=60` | `http.directories[0].cache_max_age = 60` |
+//! | `AXUM_CONF__CACHE_TTL_SECS=300` | `cache_ttl_secs = 300` (app field) |
+//!
+//! Overrides are applied to the parsed TOML tree, after layering and before
+//! deserialization, so they go through exactly the same type checks as the file.
+//! Values are coerced to the type of the key they replace. New keys, of the
+//! crate's sections and of the application alike, are read as TOML literals
+//! (`50`, `true`, `["a", "b"]`) and fall back to plain strings, so the
+//! `cache_ttl_secs` row above works for a `#[serde(default)]` field missing
+//! from the file. Quote the value (`'"1234"'`) to keep a new key a string.
+//!
+use {
+    crate::{Error, Result},
+    toml::{Table, Value},
+};
+
+/// Prefix shared by every override variable.
+pub(crate) const ENV_OVERRIDE_PREFIX: &str = "AXUM_CONF";
+
+/// Separator between the prefix and each TOML path segment.
+pub(crate) const ENV_OVERRIDE_SEPARATOR: &str = "__";
+
+/// Applies every `AXUM_CONF__…` variable in `vars` to `table` and returns the
+/// names of the variables that were applied, sorted.
+pub(crate) fn apply_env_overrides<I>(table: &mut Table, vars: I) -> Result<Vec<String>>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let prefix = format!("{ENV_OVERRIDE_PREFIX}{ENV_OVERRIDE_SEPARATOR}");
+    let mut overrides: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(&prefix))
+        .collect();
+    // Apply in a stable order so `A__B` (a table) and `A__B__C` (a key inside
+    // it) combine deterministically: the shorter path is written first.
+    overrides.sort();
+
+    let mut applied = Vec::with_capacity(overrides.len());
+    for (name, raw) in overrides {
+        let path: Vec<String> = name[prefix.len()..]
+            .split(ENV_OVERRIDE_SEPARATOR)
+            .map(str::to_lowercase)
+            .collect();
+        if path.iter().any(String::is_empty) {
+            return Err(Error::config(format!(
+                "Invalid configuration override {name}: expected \
+                 {ENV_OVERRIDE_PREFIX}{ENV_OVERRIDE_SEPARATOR}SECTION{ENV_OVERRIDE_SEPARATOR}KEY \
+                 with non-empty segments"
+            )));
+        }
+        set_path(table, &path, &name, &raw)?;
+        applied.push(name);
+    }
+    Ok(applied)
+}
+
+/// Walks `path` from `table`, creating intermediate tables as needed, and
+/// stores the coerced value at the final segment.
+fn set_path(table: &mut Table, path: &[String], name: &str, raw: &str) -> Result<()> {
+    let mut root = Value::Table(std::mem::take(table));
+    let result = set_in(&mut root, path, name, raw);
+    if let Value::Table(t) = root {
+        *table = t;
+    }
+    result
+}
+
+fn set_in(root: &mut Value, path: &[String], name: &str, raw: &str) -> Result<()> {
+    let Some((last, parents)) = path.split_last() else {
+        return Ok(());
+    };
+    let mut node = root;
+    for segment in parents {
+        node = child(node, segment, name)?;
+    }
+    match node {
+        Value::Table(t) => {
+            let value = coerce(t.get(last.as_str()), name, raw)?;
+            t.insert(last.clone(), value);
+        }
+        Value::Array(items) => {
+            let index = array_index(items, last, name)?;
+            items[index] = coerce(Some(&items[index]), name, raw)?;
+        }
+        other => return Err(not_a_table(name, other)),
+    }
+    Ok(())
+}
+
+/// Descends one segment into a table (creating it if missing) or an array.
+fn child<'a>(node: &'a mut Value, segment: &str, name: &str) -> Result<&'a mut Value> {
+    match node {
+        Value::Table(t) => Ok(t
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Table(Table::new()))),
+        Value::Array(items) => {
+            let index = array_index(items, segment, name)?;
+            Ok(&mut items[index])
+        }
+        other => Err(not_a_table(name, other)),
+    }
+}
+
+fn array_index(items: &[Value], segment: &str, name: &str) -> Result<usize> {
+    segment
+        .parse::<usize>()
+        .ok()
+        .filter(|i| *i < items.len())
+        .ok_or_else(|| {
+            Error::config(format!(
+                "Invalid configuration override {name}: `{segment}` is not an index \
+                 into an array of {} element(s)",
+                items.len()
+            ))
+        })
+}
+
+fn not_a_table(name: &str, found: &Value) -> Error {
+    Error::config(format!(
+        "Invalid configuration override {name}: the path crosses a {} value, \
+         which has no nested keys",
+        found.type_str()
+    ))
+}
+
+/// Converts the raw variable value into a TOML value. When the key already
+/// has a value, the override must have the same type; otherwise the raw text
+/// is read as a TOML literal, falling back to a plain string.
+fn coerce(existing: Option<&Value>, name: &str, raw: &str) -> Result<Value> {
+    let literal = parse_literal(raw);
+    let Some(existing) = existing else {
+        return Ok(literal.unwrap_or_else(|| Value::String(raw.to_string())));
+    };
+
+    let coerced = match (existing, literal) {
+        // Strings are taken verbatim (quotes optional), so `0.0.0.0` or `123`
+        // remain strings where the file has a string.
+        (Value::String(_), Some(Value::String(s))) => Some(Value::String(s)),
+        (Value::String(_), _) => Some(Value::String(raw.to_string())),
+        (Value::Float(_), Some(Value::Integer(i))) => Some(Value::Float(i as f64)),
+        (existing, Some(value)) if existing.same_type(&value) => Some(value),
+        _ => None,
+    };
+    coerced.ok_or_else(|| {
+        Error::config(format!(
+            "Invalid configuration override {name}: expected {} {}",
+            article(existing.type_str()),
+            existing.type_str()
+        ))
+    })
+}
+
+/// Parses `raw` as a TOML value (`8080`, `true`, `"x"`, `["a"]`, `{ k = 1 }`).
+fn parse_literal(raw: &str) -> Option<Value> {
+    format!("v = {raw}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+}
+
+fn article(type_name: &str) -> &'static str {
+    if type_name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn table(s: &str) -> Table {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn overrides_existing_keys_with_type_coercion() {
+        let mut t = table(
+            r#"
+            [http]
+            bind_addr = "127.0.0.1"
+            bind_port = 3000
+            support_compression = false
+            "#,
+        );
+        let applied = apply_env_overrides(
+            &mut t,
+            vars(&[
+                ("AXUM_CONF__HTTP__BIND_PORT", "8080"),
+                ("AXUM_CONF__HTTP__BIND_ADDR", "0.0.0.0"),
+                ("AXUM_CONF__HTTP__SUPPORT_COMPRESSION", "true"),
+                ("UNRELATED", "x"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(applied.len(), 3);
+        assert_eq!(t["http"]["bind_port"].as_integer(), Some(8080));
+        assert_eq!(t["http"]["bind_addr"].as_str(), Some("0.0.0.0"));
+        assert_eq!(t["http"]["support_compression"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn numeric_text_stays_a_string_where_the_file_has_a_string() {
+        let mut t = table("api_key = \"abc\"");
+        apply_env_overrides(&mut t, vars(&[("AXUM_CONF__API_KEY", "12345")])).unwrap();
+        assert_eq!(t["api_key"].as_str(), Some("12345"));
+    }
+
+    #[test]
+    fn missing_keys_and_sections_are_created_from_literals() {
+        let mut t = Table::new();
+        apply_env_overrides(
+            &mut t,
+            vars(&[
+                ("AXUM_CONF__HTTP__MAX_REQUESTS_PER_SEC", "50"),
+                ("AXUM_CONF__HTTP__MAX_PAYLOAD_SIZE_BYTES", "1KiB"),
+                (
+                    "AXUM_CONF__HTTP__CORS__ALLOWED_ORIGINS",
+                    "[\"https://a.example\"]",
+                ),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(t["http"]["max_requests_per_sec"].as_integer(), Some(50));
+        assert_eq!(t["http"]["max_payload_size_bytes"].as_str(), Some("1KiB"));
+        assert_eq!(
+            t["http"]["cors"]["allowed_origins"]
+                .as_array()
+                .map(Vec::len),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn missing_application_keys_are_literals_unless_quoted() {
+        let mut t = table("[app]\nname = \"x\"");
+        apply_env_overrides(
+            &mut t,
+            vars(&[
+                ("AXUM_CONF__CACHE_TTL_SECS", "300"),
+                ("AXUM_CONF__FEATURE_FLAGS", "true"),
+                ("AXUM_CONF__APP__PIN", "\"1234\""),
+                ("AXUM_CONF__APP__REGION", "eu-west-1"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(t["cache_ttl_secs"].as_integer(), Some(300));
+        assert_eq!(t["feature_flags"].as_bool(), Some(true));
+        assert_eq!(t["app"]["pin"].as_str(), Some("1234"));
+        assert_eq!(t["app"]["region"].as_str(), Some("eu-west-1"));
+    }
+
+    #[test]
+    fn array_elements_are_addressed_by_index() {
+        let mut t = table(
+            r#"
+            [[http.directories]]
+            directory = "a"
+            [[http.directories]]
+            directory = "b"
+            "#,
+        );
+        apply_env_overrides(
+            &mut t,
+            vars(&[("AXUM_CONF__HTTP__DIRECTORIES__1__CACHE_MAX_AGE", "60")]),
+        )
+        .unwrap();
+        assert_eq!(
+            t["http"]["directories"][1]["cache_max_age"].as_integer(),
+            Some(60)
+        );
+
+        let err = apply_env_overrides(
+            &mut t,
+            vars(&[("AXUM_CONF__HTTP__DIRECTORIES__5__CACHE_MAX_AGE", "60")]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not an index"), "{err}");
+    }
+
+    #[test]
+    fn type_mismatch_is_reported_with_variable_name() {
+        let mut t = table("[http]\nbind_port = 3000");
+        let err = apply_env_overrides(&mut t, vars(&[("AXUM_CONF__HTTP__BIND_PORT", "abc")]))
+            .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("AXUM_CONF__HTTP__BIND_PORT"), "{msg}");
+        assert!(msg.contains("expected an integer"), "{msg}");
+        // The value may be a secret, so it is not echoed.
+        assert!(!msg.contains("abc"), "{msg}");
+    }
+
+    #[test]
+    fn integer_is_accepted_for_float_key() {
+        let mut t = table("ratio = 0.5");
+        apply_env_overrides(&mut t, vars(&[("AXUM_CONF__RATIO", "2")])).unwrap();
+        assert_eq!(t["ratio"].as_float(), Some(2.0));
+    }
+
+    #[test]
+    fn empty_segments_are_rejected() {
+        let mut t = Table::new();
+        let err =
+            apply_env_overrides(&mut t, vars(&[("AXUM_CONF__HTTP____PORT", "1")])).unwrap_err();
+        assert!(err.to_string().contains("non-empty segments"), "{err}");
+    }
+
+    #[test]
+    fn descending_into_a_scalar_is_rejected() {
+        let mut t = table("[http]\nbind_port = 3000");
+        let err = apply_env_overrides(&mut t, vars(&[("AXUM_CONF__HTTP__BIND_PORT__X", "1")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("integer value"), "{err}");
+    }
+}
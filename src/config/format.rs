@@ -0,0 +1,233 @@
+//!
+//! Configuration file formats.
+//!
+//! TOML is the native format: layering, environment overrides and unknown-key
+//! detection all operate on a TOML table. YAML (with the `yaml` feature) and
+//! JSON documents are converted to that table right after parsing, so every
+//! format behaves the same from there on.
+//!
+//! TOML has no null, so `null` (or `~`) mapping values are treated as absent
+//! keys; a null inside an array is an error.
+//!
+use {
+    crate::{Error, Result},
+    serde_json::Value as Json,
+    std::path::Path,
+    toml::{Table, Value},
+};
+
+/// A supported configuration file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Recognized file extensions, in the order they are tried when a file is
+    /// looked up by name (e.g. `config/dev.toml` before `config/dev.yaml`).
+    pub(crate) const EXTENSIONS: &[(&str, ConfigFormat)] = &[
+        ("toml", ConfigFormat::Toml),
+        ("yaml", ConfigFormat::Yaml),
+        ("yml", ConfigFormat::Yaml),
+        ("json", ConfigFormat::Json),
+    ];
+
+    /// Detects the format from the extension of `path`.
+    pub(crate) fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        Self::EXTENSIONS
+            .iter()
+            .find(|(ext, _)| ext.eq_ignore_ascii_case(extension))
+            .map(|&(_, format)| format)
+            .ok_or_else(|| {
+                Error::config(format!(
+                    "Cannot detect the format of configuration file {}: expected a \
+                     .toml, .yaml, .yml or .json extension",
+                    path.display()
+                ))
+            })
+    }
+
+    /// Parses `text` (with placeholders already substituted) into a TOML table.
+    pub(crate) fn parse_table(self, text: &str) -> Result<Table> {
+        match self {
+            ConfigFormat::Toml => Ok(text.parse()?),
+            ConfigFormat::Yaml => parse_yaml(text),
+            ConfigFormat::Json => {
+                let json: Json = serde_json::from_str(text)
+                    .map_err(|e| Error::config(format!("Invalid JSON configuration: {e}")))?;
+                json_to_table(json)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(text: &str) -> Result<Table> {
+    // An empty document is an empty configuration, as with an empty TOML file.
+    if text.trim().is_empty() {
+        return Ok(Table::new());
+    }
+    let json: Json = serde_yaml_ng::from_str(text)
+        .map_err(|e| Error::config(format!("Invalid YAML configuration: {e}")))?;
+    json_to_table(json)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml(_text: &str) -> Result<Table> {
+    Err(Error::config(
+        "YAML configuration files require the `yaml` crate feature",
+    ))
+}
+
+/// Converts a parsed JSON (or YAML) document into a TOML table.
+fn json_to_table(json: Json) -> Result<Table> {
+    match json {
+        Json::Object(map) => object_to_table(map, ""),
+        other => Err(Error::config(format!(
+            "The configuration document must be a mapping of keys to values, got {}",
+            json_type(&other)
+        ))),
+    }
+}
+
+fn object_to_table(map: serde_json::Map<String, Json>, prefix: &str) -> Result<Table> {
+    let mut table = Table::new();
+    for (key, value) in map {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        if let Some(value) = json_to_value(value, &path)? {
+            table.insert(key, value);
+        }
+    }
+    Ok(table)
+}
+
+/// Converts one value; `None` for a null, which TOML cannot represent.
+fn json_to_value(json: Json, path: &str) -> Result<Option<Value>> {
+    let value = match json {
+        Json::Null => return Ok(None),
+        Json::Bool(b) => Value::Boolean(b),
+        Json::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => Value::Integer(i),
+            (None, Some(f)) if n.is_f64() => Value::Float(f),
+            _ => {
+                return Err(Error::config(format!(
+                    "`{path}`: the number {n} is out of range"
+                )));
+            }
+        },
+        Json::String(s) => Value::String(s),
+        Json::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    json_to_value(item, &format!("{path}[{i}]"))?.ok_or_else(|| {
+                        Error::config(format!("`{path}[{i}]`: null is not allowed in arrays"))
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Json::Object(map) => Value::Table(object_to_table(map, path)?),
+    };
+    Ok(Some(value))
+}
+
+fn json_type(json: &Json) -> &'static str {
+    match json {
+        Json::Null => "null",
+        Json::Bool(_) => "a boolean",
+        Json::Number(_) => "a number",
+        Json::String(_) => "a string",
+        Json::Array(_) => "an array",
+        Json::Object(_) => "a mapping",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_is_detected_from_the_extension() {
+        let cases = [
+            ("config/dev.toml", ConfigFormat::Toml),
+            ("config/dev.yaml", ConfigFormat::Yaml),
+            ("config/dev.YML", ConfigFormat::Yaml),
+            ("/etc/app/config.json", ConfigFormat::Json),
+        ];
+        for (path, format) in cases {
+            assert_eq!(ConfigFormat::from_path(Path::new(path)).unwrap(), format);
+        }
+        let err = ConfigFormat::from_path(Path::new("config/dev.ini")).unwrap_err();
+        assert!(err.to_string().contains(".yaml"), "{err}");
+    }
+
+    #[test]
+    fn json_is_converted_to_a_table() {
+        let table = ConfigFormat::Json
+            .parse_table(
+                r#"{
+                    "http": {"bind_port": 8080, "max_payload_size_bytes": "1KiB", "oidc": null},
+                    "logging": {"format": "json"},
+                    "ratio": 0.5,
+                    "tags": ["a", "b"]
+                }"#,
+            )
+            .unwrap();
+        assert_eq!(table["http"]["bind_port"].as_integer(), Some(8080));
+        assert!(!table["http"].as_table().unwrap().contains_key("oidc"));
+        assert_eq!(table["ratio"].as_float(), Some(0.5));
+        assert_eq!(table["tags"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn json_must_be_an_object_without_nulls_in_arrays() {
+        let err = ConfigFormat::Json.parse_table("[1, 2]").unwrap_err();
+        assert!(err.to_string().contains("got an array"), "{err}");
+
+        let err = ConfigFormat::Json
+            .parse_table(r#"{"tags": ["a", null]}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("`tags[1]`"), "{err}");
+
+        let err = ConfigFormat::Json
+            .parse_table(r#"{"big": 18446744073709551615}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("`big`"), "{err}");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_is_converted_to_a_table() {
+        let table = ConfigFormat::Yaml
+            .parse_table(
+                "http:\n  bind_port: 8080\n  max_payload_size_bytes: 1KiB\n  oidc: ~\n\
+                 logging:\n  format: json\n",
+            )
+            .unwrap();
+        assert_eq!(table["http"]["bind_port"].as_integer(), Some(8080));
+        assert_eq!(
+            table["http"]["max_payload_size_bytes"].as_str(),
+            Some("1KiB")
+        );
+        assert!(!table["http"].as_table().unwrap().contains_key("oidc"));
+        assert!(ConfigFormat::Yaml.parse_table("").unwrap().is_empty());
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn yaml_requires_the_feature() {
+        let err = ConfigFormat::Yaml.parse_table("a: 1").unwrap_err();
+        assert!(err.to_string().contains("`yaml` crate feature"), "{err}");
+    }
+}
@@ -0,0 +1,81 @@
+//! Admin (management) listener configuration.
+//!
+//! When `[http.admin]` is present, [`FluentRouter::start`](crate::FluentRouter::start)
+//! serves the liveness, readiness and metrics routes on a second listener
+//! instead of the public one. They run behind a minimal middleware stack (panic
+//! recovery and the request timeout), so rate limiting, authentication and the
+//! other public middleware do not apply to them. The effective-config route
+//! needs an authenticated caller, so it stays on the public listener.
+//!
+//! # Example
+//!
+//! ```toml
+//! [http.admin]
+//! bind_addr = "127.0.0.1" # default: the same as [http] bind_addr
+//! bind_port = 9090
+//! ```
+
+use {
+    crate::{Error, ValidationReport},
+    serde::{Deserialize, Serialize},
+};
+
+/// Configuration of the admin listener.
+///
+/// Keep the admin port out of the public ingress: the routes it serves are
+/// not authenticated.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct HttpAdminConfig {
+    /// IP address to bind the admin listener to.
+    /// Unset (the default) uses `[http] bind_addr`.
+    #[serde(default)]
+    pub bind_addr: Option<String>,
+
+    /// Port to bind the admin listener to. Must differ from `[http] bind_port`.
+    pub bind_port: u16,
+}
+
+impl HttpAdminConfig {
+    /// Creates a configuration serving the admin routes on `bind_port`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum_conf::HttpAdminConfig;
+    ///
+    /// let admin = HttpAdminConfig::new(9090);
+    /// assert_eq!(admin.full_bind_addr("0.0.0.0"), "0.0.0.0:9090");
+    /// ```
+    pub fn new(bind_port: u16) -> Self {
+        Self {
+            bind_addr: None,
+            bind_port,
+        }
+    }
+
+    /// Returns the admin address as `"IP:PORT"`, using `default_addr` when
+    /// `bind_addr` is unset.
+    pub fn full_bind_addr(&self, default_addr: &str) -> String {
+        let addr = self.bind_addr.as_deref().unwrap_or(default_addr);
+        format!("{addr}:{}", self.bind_port)
+    }
+
+    /// Checks the admin configuration, with key paths relative to `[http.admin]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        if self
+            .bind_addr
+            .as_ref()
+            .is_some_and(|addr| addr.trim().is_empty())
+        {
+            report.error(
+                "bind_addr",
+                Error::invalid_input(
+                    "[http.admin] bind_addr is empty. Set it to an IP address or remove it.",
+                ),
+            );
+        }
+        report
+    }
+}
@@ -29,13 +29,14 @@
 //! Basic Auth cannot be used together with OIDC authentication (`keycloak` feature).
 //! Choose one authentication method per application.
 
-use crate::{Error, Result, utils::Sensitive};
-use serde::Deserialize;
+use crate::{Error, Result, ValidationReport, utils::Sensitive};
+use serde::{Deserialize, Serialize};
 
 /// Authentication mode for basic authentication.
 ///
 /// Determines which authentication methods are accepted by the middleware.
-#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum BasicAuthMode {
     /// Only HTTP Basic Auth (RFC 7617) is accepted.
@@ -56,7 +57,8 @@ pub enum BasicAuthMode {
 /// username = "admin"
 /// password = "{{ ADMIN_PASSWORD }}"
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct BasicAuthUser {
     /// Username for authentication.
     pub username: String,
@@ -85,7 +87,8 @@ pub struct BasicAuthUser {
 /// key = "{{ API_KEY_1 }}"
 /// name = "frontend-app"
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct BasicAuthApiKey {
     /// The API key value wrapped in Sensitive for secure handling.
     pub key: Sensitive<String>,
@@ -127,7 +130,8 @@ pub struct BasicAuthApiKey {
 /// key = "{{ API_KEY_1 }}"
 /// name = "frontend-app"
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct HttpBasicAuthConfig {
     /// Authentication mode determining which methods are accepted.
     #[serde(default)]
@@ -171,56 +175,80 @@ impl HttpBasicAuthConfig {
     /// - Passwords are not empty
     /// - API keys are not empty
     /// - API key header name is not empty
+    ///
+    /// The error lists every problem found (see [`Self::validation_report`]).
     pub fn validate(&self) -> Result<()> {
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
+
+    /// Checks the basic auth configuration, collecting every problem with its
+    /// key path relative to `[http.basic_auth]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
         let has_users = !self.users.is_empty();
         let has_api_keys = !self.api_keys.is_empty();
 
         match self.mode {
-            BasicAuthMode::Basic if !has_users => {
-                return Err(Error::invalid_input(
+            BasicAuthMode::Basic if !has_users => report.error(
+                "users",
+                Error::invalid_input(
                     "Basic auth mode 'basic' requires at least one user. Add [[http.basic_auth.users]] to config.",
-                ));
-            }
-            BasicAuthMode::ApiKey if !has_api_keys => {
-                return Err(Error::invalid_input(
+                ),
+            ),
+            BasicAuthMode::ApiKey if !has_api_keys => report.error(
+                "api_keys",
+                Error::invalid_input(
                     "Basic auth mode 'api_key' requires at least one API key. Add [[http.basic_auth.api_keys]] to config.",
-                ));
-            }
-            BasicAuthMode::Either if !has_users && !has_api_keys => {
-                return Err(Error::invalid_input(
+                ),
+            ),
+            BasicAuthMode::Either if !has_users && !has_api_keys => report.error(
+                "mode",
+                Error::invalid_input(
                     "Basic auth requires at least one user or API key. Add credentials to config.",
-                ));
-            }
+                ),
+            ),
             _ => {}
         }
 
         // Validate usernames and passwords are not empty
-        for user in &self.users {
+        for (index, user) in self.users.iter().enumerate() {
             if user.username.trim().is_empty() {
-                return Err(Error::invalid_input("Basic auth username cannot be empty."));
+                report.error(
+                    format!("users[{index}].username"),
+                    Error::invalid_input("Basic auth username cannot be empty."),
+                );
             }
             if user.password.0.is_empty() {
-                return Err(Error::invalid_input(
-                    "Basic auth password cannot be empty. Use {{ ENV_VAR }} for secrets.",
-                ));
+                report.error(
+                    format!("users[{index}].password"),
+                    Error::invalid_input(
+                        "Basic auth password cannot be empty. Use {{ ENV_VAR }} for secrets.",
+                    ),
+                );
             }
         }
 
         // Validate API keys are not empty
-        for api_key in &self.api_keys {
+        for (index, api_key) in self.api_keys.iter().enumerate() {
             if api_key.key.0.is_empty() {
-                return Err(Error::invalid_input(
-                    "API key cannot be empty. Use {{ ENV_VAR }} for secrets.",
-                ));
+                report.error(
+                    format!("api_keys[{index}].key"),
+                    Error::invalid_input("API key cannot be empty. Use {{ ENV_VAR }} for secrets."),
+                );
             }
         }
 
         // Validate API key header name
         if self.api_key_header.trim().is_empty() {
-            return Err(Error::invalid_input("API key header name cannot be empty."));
+            report.error(
+                "api_key_header",
+                Error::invalid_input("API key header name cannot be empty."),
+            );
         }
 
-        Ok(())
+        report
     }
 }
 
@@ -339,6 +367,31 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_validation_report_lists_every_bad_credential() {
+        let user = |username: &str, password: &str| BasicAuthUser {
+            username: username.to_string(),
+            password: Sensitive::from(password),
+            email: None,
+            groups: vec![],
+            roles: vec![],
+            preferred_username: None,
+        };
+        let config = HttpBasicAuthConfig {
+            mode: BasicAuthMode::Basic,
+            users: vec![user("admin", "secret"), user(" ", "")],
+            api_key_header: String::new(),
+            ..Default::default()
+        };
+
+        let report = config.validation_report();
+        let paths: Vec<_> = report.errors().map(|issue| issue.path()).collect();
+        assert_eq!(
+            paths,
+            ["users[1].username", "users[1].password", "api_key_header"]
+        );
+    }
+
     #[test]
     fn test_config_validation_empty_api_key() {
         let config = HttpBasicAuthConfig {
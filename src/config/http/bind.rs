@@ -0,0 +1,100 @@
+//! Listen targets other than `bind_addr:bind_port`.
+//!
+//! `[http] bind` selects where [`FluentRouter::start`](crate::FluentRouter::start)
+//! listens when TCP on `bind_addr:bind_port` is not wanted.
+//!
+//! # Example
+//!
+//! ```toml
+//! [http]
+//! bind = "unix:/run/app/http.sock"   # a Unix domain socket
+//! unix_socket_mode = "0660"          # permissions of the socket file
+//!
+//! # or: bind = "systemd"             # the socket passed by systemd (LISTEN_FDS)
+//! # or: bind = "systemd:http"        # the one named `FileDescriptorName=http`
+//! ```
+
+use {
+    crate::{Error, Result},
+    std::path::PathBuf,
+};
+
+/// Where the HTTP server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindTarget {
+    /// TCP on this `address:port` (`bind` unset).
+    Tcp(String),
+    /// A Unix domain socket at this path (`bind = "unix:<path>"`).
+    Unix(PathBuf),
+    /// A listening socket inherited through systemd socket activation
+    /// (`bind = "systemd"`), optionally selected by its
+    /// `FileDescriptorName=` (`bind = "systemd:<name>"`).
+    SocketActivation(Option<String>),
+}
+
+impl BindTarget {
+    /// Parses a `bind` value: `"unix:<path>"`, `"systemd"` or `"systemd:<name>"`.
+    pub fn parse(bind: &str) -> Result<Self> {
+        if let Some(path) = bind.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(Error::invalid_input(
+                    "HTTP bind = \"unix:\" needs a socket path, e.g. \"unix:/run/app/http.sock\".",
+                ));
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        match bind.strip_prefix("systemd") {
+            Some("") => Ok(Self::SocketActivation(None)),
+            Some(name) if name.len() > 1 && name.starts_with(':') => {
+                Ok(Self::SocketActivation(Some(name[1..].to_string())))
+            }
+            _ => Err(Error::invalid_input(format!(
+                "Unsupported HTTP bind {bind:?}. Use \"unix:<path>\", \"systemd\" or \
+                 \"systemd:<name>\", or remove it to listen on bind_addr:bind_port."
+            ))),
+        }
+    }
+}
+
+/// Parses octal socket permissions such as `"0660"` or `"660"`.
+pub(crate) fn parse_socket_mode(mode: &str) -> Result<u32> {
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|bits| *bits <= 0o777 && !digits.is_empty())
+        .ok_or_else(|| {
+            Error::invalid_input(format!(
+                "HTTP unix_socket_mode {mode:?} is not an octal permission such as \"0660\"."
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_values_parse() {
+        assert_eq!(
+            BindTarget::parse("unix:/run/app.sock").unwrap(),
+            BindTarget::Unix("/run/app.sock".into())
+        );
+        assert_eq!(
+            BindTarget::parse("systemd").unwrap(),
+            BindTarget::SocketActivation(None)
+        );
+        assert_eq!(
+            BindTarget::parse("systemd:http").unwrap(),
+            BindTarget::SocketActivation(Some("http".into()))
+        );
+        for invalid in ["unix:", "systemd:", "tcp:0.0.0.0:80", "/run/app.sock"] {
+            assert!(BindTarget::parse(invalid).is_err(), "{invalid}");
+        }
+
+        assert_eq!(parse_socket_mode("0660").unwrap(), 0o660);
+        assert_eq!(parse_socket_mode("0o600").unwrap(), 0o600);
+        for invalid in ["", "0o", "0999", "rw-rw----", "1777"] {
+            assert!(parse_socket_mode(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -1,13 +1,14 @@
 //! Circuit breaker configuration for external service resilience.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, time::Duration};
 
 /// Configuration for a single circuit breaker target.
 ///
 /// Each target (e.g., "database", "payment-api") has its own circuit breaker
 /// with independent state and thresholds.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct CircuitBreakerTargetConfig {
     /// Number of consecutive failures before the circuit opens.
     /// Default: 5
@@ -25,6 +26,10 @@ pub struct CircuitBreakerTargetConfig {
         default = "CircuitBreakerTargetConfig::default_reset_timeout",
         with = "humantime_serde"
     )]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub reset_timeout: Duration,
 
     /// Maximum concurrent requests allowed in half-open state for probing.
@@ -35,6 +40,10 @@ pub struct CircuitBreakerTargetConfig {
     /// Optional per-call timeout. If set, calls exceeding this duration
     /// are considered failures.
     #[serde(default, with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub call_timeout: Option<Duration>,
 }
 
@@ -82,7 +91,8 @@ impl Default for CircuitBreakerTargetConfig {
 /// reset_timeout = "60s"
 /// call_timeout = "10s"
 /// ```
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct CircuitBreakerConfig {
     /// Named circuit breaker configurations.
     /// Keys are target names like "database", "payment-api", "auth-service".
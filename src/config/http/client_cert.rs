@@ -0,0 +1,163 @@
+//! Client-certificate (mutual TLS) authentication configuration.
+//!
+//! With `[http.tls] client_ca_file` set, every client presents a certificate
+//! signed by one of the configured CAs. `[http.client_cert_auth]` turns that
+//! certificate into an [`AuthenticatedIdentity`](crate::AuthenticatedIdentity)
+//! with [`AuthMethod::ClientCertificate`](crate::AuthMethod::ClientCertificate),
+//! so the role extractors work as with any other authentication method.
+//!
+//! # Example
+//!
+//! ```toml
+//! [http.tls]
+//! cert_file = "/etc/tls/tls.crt"
+//! key_file = "/etc/tls/tls.key"
+//! client_ca_file = "/etc/tls/mesh-ca.crt"
+//!
+//! [http.client_cert_auth]
+//! user = "cn"                  # identity.user: "cn", "san_dns", "san_uri" or "san_email"
+//! groups = ["ou"]              # every OU becomes a group
+//! roles = []                   # attributes copied verbatim into identity.roles
+//!
+//! [[http.client_cert_auth.role_mappings]]
+//! attribute = "san_uri"
+//! value = "spiffe://cluster.local/ns/shop/sa/orders"
+//! roles = ["orders-writer"]
+//! ```
+
+use {
+    crate::{Error, ValidationReport},
+    serde::{Deserialize, Serialize},
+};
+
+/// A client-certificate attribute identity fields can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CertAttribute {
+    /// The subject's common name (CN).
+    Cn,
+    /// The subject's organizational units (OU).
+    Ou,
+    /// DNS names of the subject alternative name extension.
+    SanDns,
+    /// URIs of the subject alternative name extension (e.g. SPIFFE IDs).
+    SanUri,
+    /// Email addresses of the subject alternative name extension.
+    SanEmail,
+}
+
+/// Grants `roles` to clients whose certificate has `value` in `attribute`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct ClientCertRoleMapping {
+    /// The attribute to match.
+    pub attribute: CertAttribute,
+    /// The value the attribute must have (compared exactly).
+    pub value: String,
+    /// Roles granted on a match.
+    pub roles: Vec<String>,
+}
+
+/// Configuration for authenticating clients by their TLS certificate.
+///
+/// The certificate has already been verified against `[http.tls]
+/// client_ca_file` during the handshake; this section only decides how it maps
+/// to an identity. A certificate without the [`user`](Self::user) attribute
+/// yields no identity, so routes guarded by role extractors answer 401.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct HttpClientCertAuthConfig {
+    /// Attribute used as `identity.user`; the first value wins.
+    /// By default `user` is `"cn"`.
+    #[serde(default = "HttpClientCertAuthConfig::default_user")]
+    pub user: CertAttribute,
+
+    /// Attributes whose values become `identity.groups`.
+    /// By default `groups` is `["ou"]`.
+    #[serde(default = "HttpClientCertAuthConfig::default_groups")]
+    pub groups: Vec<CertAttribute>,
+
+    /// Attributes whose values become `identity.roles` as they are.
+    /// Empty by default.
+    #[serde(default)]
+    pub roles: Vec<CertAttribute>,
+
+    /// Roles granted when an attribute has a specific value.
+    #[serde(default)]
+    pub role_mappings: Vec<ClientCertRoleMapping>,
+}
+
+impl Default for HttpClientCertAuthConfig {
+    fn default() -> Self {
+        Self {
+            user: Self::default_user(),
+            groups: Self::default_groups(),
+            roles: Vec::new(),
+            role_mappings: Vec::new(),
+        }
+    }
+}
+
+impl HttpClientCertAuthConfig {
+    fn default_user() -> CertAttribute {
+        CertAttribute::Cn
+    }
+
+    fn default_groups() -> Vec<CertAttribute> {
+        vec![CertAttribute::Ou]
+    }
+
+    /// Checks the mapping, with key paths relative to `[http.client_cert_auth]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        for (index, mapping) in self.role_mappings.iter().enumerate() {
+            if mapping.value.is_empty() {
+                report.error(
+                    format!("role_mappings[{index}].value"),
+                    Error::invalid_input(
+                        "Client certificate role mapping value must not be empty.",
+                    ),
+                );
+            }
+            if mapping.roles.is_empty() {
+                report.warning(
+                    format!("role_mappings[{index}].roles"),
+                    "Client certificate role mapping grants no roles.",
+                );
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_deserializes_with_defaults() {
+        let config: HttpClientCertAuthConfig = toml::from_str(
+            r#"
+            roles = ["san_uri"]
+
+            [[role_mappings]]
+            attribute = "ou"
+            value = "payments"
+            roles = ["payments-writer"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.user, CertAttribute::Cn);
+        assert_eq!(config.groups, [CertAttribute::Ou]);
+        assert_eq!(config.roles, [CertAttribute::SanUri]);
+        assert_eq!(config.role_mappings[0].attribute, CertAttribute::Ou);
+        assert!(config.validation_report().is_empty());
+
+        let mut config = config;
+        config.role_mappings[0].value.clear();
+        let report = config.validation_report();
+        let paths: Vec<_> = report.errors().map(|issue| issue.path()).collect();
+        assert_eq!(paths, ["role_mappings[0].value"]);
+    }
+}
@@ -0,0 +1,339 @@
+//! HTTP/1 and HTTP/2 connection settings.
+//!
+//! `[http.connection]` tunes how [`FluentRouter::start`](crate::FluentRouter::start)
+//! serves each connection: how many connections it accepts, how long a client
+//! may take to send its headers, how long an idle keep-alive connection stays
+//! open, how large request headers may be, and the HTTP/2 flow-control and
+//! ping settings. Every key is optional; the defaults match the server's
+//! behavior without the section.
+//!
+//! # Example
+//!
+//! ```toml
+//! [http.connection]
+//! max_connections = 10000
+//! max_connections_per_ip = 100
+//! per_ip_ipv6_prefix = 64         # IPv6 clients are counted per /64
+//! header_read_timeout = "10s"     # slowloris protection; "0s" (the default) disables
+//! keep_alive_timeout = "55s"      # close idle connections before the load balancer does
+//! max_headers = 64
+//! max_header_size = "16 KiB"
+//! http2_max_concurrent_streams = 100
+//! http2_keep_alive_interval = "20s"
+//! h2c = false                     # plaintext listeners speak HTTP/1 only
+//! ```
+
+use {
+    crate::{Error, ValidationReport},
+    byte_unit::Byte,
+    serde::{Deserialize, Serialize},
+    std::time::Duration,
+};
+
+/// Smallest header buffer hyper accepts for HTTP/1 connections.
+const MIN_HEADER_SIZE: u64 = 8 * 1024;
+
+/// Largest HTTP/2 flow-control window (RFC 9113, section 6.9.1).
+const MAX_WINDOW_SIZE: u64 = (1 << 31) - 1;
+
+/// Connection-level settings shared by every listener of the server.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct HttpConnectionConfig {
+    /// Maximum number of connections open at once on the public listener.
+    /// Connections beyond it are closed as soon as they are accepted, before
+    /// any TLS handshake. Unset (the default) sets no limit.
+    pub max_connections: Option<u32>,
+
+    /// Maximum number of connections open at once from one client IP address
+    /// on the public listener. Unset (the default) sets no limit. Connections
+    /// over a Unix socket are only counted against `max_connections`.
+    pub max_connections_per_ip: Option<u32>,
+
+    /// Prefix length IPv6 clients are grouped by for `max_connections_per_ip`:
+    /// addresses in the same network share one count, since a single host
+    /// usually owns a whole /64. By default 64; 128 counts each address.
+    pub per_ip_ipv6_prefix: u8,
+
+    /// Maximum time a client has to send the headers of a request, after
+    /// which the connection is closed. `"0s"` (the default) sets no limit.
+    ///
+    /// On HTTP/1 the timer also runs while a kept-alive connection waits for
+    /// its next request, so it caps the idle time of such connections too.
+    #[serde(with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub header_read_timeout: Duration,
+
+    /// Whether HTTP/1 connections are kept open between requests.
+    /// By default `keep_alive` is true.
+    pub keep_alive: bool,
+
+    /// How long a connection with no request in flight stays open before the
+    /// server closes it (HTTP/2 connections receive a GOAWAY). Keep it below
+    /// the idle timeout of the load balancer in front of the server, so the
+    /// load balancer never reuses a connection the server is closing.
+    /// `"0s"` (the default) keeps idle connections open.
+    #[serde(with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub keep_alive_timeout: Duration,
+
+    /// Maximum number of headers in an HTTP/1 request; requests with more are
+    /// rejected with 431 Request Header Fields Too Large. By default 100.
+    pub max_headers: Option<usize>,
+
+    /// Maximum size of the request headers. On HTTP/1 this is the read buffer
+    /// size, which must be at least 8 KiB; on HTTP/2 it is the advertised
+    /// header list size. By default ~400 KiB (HTTP/1) and 16 KiB (HTTP/2).
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::byte_size")
+    )]
+    pub max_header_size: Option<Byte>,
+
+    /// Maximum number of concurrent streams a client may open on one HTTP/2
+    /// connection. By default 200.
+    pub http2_max_concurrent_streams: Option<u32>,
+
+    /// Initial HTTP/2 flow-control window of each stream. By default 64 KiB
+    /// less one byte, the protocol default.
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::byte_size")
+    )]
+    pub http2_initial_stream_window_size: Option<Byte>,
+
+    /// Initial HTTP/2 flow-control window of each connection. By default
+    /// 64 KiB less one byte, the protocol default.
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::byte_size")
+    )]
+    pub http2_initial_connection_window_size: Option<Byte>,
+
+    /// Interval between HTTP/2 PING frames sent to keep connections alive and
+    /// detect dead peers. `"0s"` (the default) sends no pings.
+    #[serde(with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub http2_keep_alive_interval: Duration,
+
+    /// How long to wait for the acknowledgement of a keep-alive PING before
+    /// closing the connection. By default 20 seconds.
+    #[serde(with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub http2_keep_alive_timeout: Duration,
+
+    /// Whether plaintext listeners accept HTTP/2 with prior knowledge (h2c).
+    /// When false, they speak HTTP/1 only. HTTPS negotiates HTTP/2 through
+    /// `[http.tls] alpn` instead. By default `h2c` is true.
+    pub h2c: bool,
+}
+
+impl Default for HttpConnectionConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: None,
+            max_connections_per_ip: None,
+            per_ip_ipv6_prefix: 64,
+            header_read_timeout: Duration::ZERO,
+            keep_alive: true,
+            keep_alive_timeout: Duration::ZERO,
+            max_headers: None,
+            max_header_size: None,
+            http2_max_concurrent_streams: None,
+            http2_initial_stream_window_size: None,
+            http2_initial_connection_window_size: None,
+            http2_keep_alive_interval: Duration::ZERO,
+            http2_keep_alive_timeout: Duration::from_secs(20),
+            h2c: true,
+        }
+    }
+}
+
+impl HttpConnectionConfig {
+    /// Checks the connection settings, with key paths relative to `[http.connection]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+
+        for (key, max) in [
+            ("max_connections", self.max_connections),
+            ("max_connections_per_ip", self.max_connections_per_ip),
+        ] {
+            if max == Some(0) {
+                report.error(
+                    key,
+                    Error::invalid_input(format!(
+                        "[http.connection] {key} must be greater than zero. Remove it to accept \
+                         any number of connections."
+                    )),
+                );
+            }
+        }
+        if let (Some(total), Some(per_ip)) = (self.max_connections, self.max_connections_per_ip)
+            && per_ip > total
+        {
+            report.warning(
+                "max_connections_per_ip",
+                format!(
+                    "[http.connection] max_connections_per_ip ({per_ip}) is above \
+                     max_connections ({total}) and has no effect."
+                ),
+            );
+        }
+        if !(1..=128).contains(&self.per_ip_ipv6_prefix) {
+            report.error(
+                "per_ip_ipv6_prefix",
+                Error::invalid_input(format!(
+                    "[http.connection] per_ip_ipv6_prefix must be between 1 and 128, got {}.",
+                    self.per_ip_ipv6_prefix
+                )),
+            );
+        }
+        if self.max_headers == Some(0) {
+            report.error(
+                "max_headers",
+                Error::invalid_input("[http.connection] max_headers must be greater than zero."),
+            );
+        }
+        if let Some(size) = self.max_header_size
+            && size.as_u64() < MIN_HEADER_SIZE
+        {
+            report.error(
+                "max_header_size",
+                Error::invalid_input(format!(
+                    "[http.connection] max_header_size must be at least 8 KiB, got {} bytes.",
+                    size.as_u64()
+                )),
+            );
+        }
+        if self.http2_max_concurrent_streams == Some(0) {
+            report.error(
+                "http2_max_concurrent_streams",
+                Error::invalid_input(
+                    "[http.connection] http2_max_concurrent_streams must be greater than zero.",
+                ),
+            );
+        }
+        for (key, window) in [
+            (
+                "http2_initial_stream_window_size",
+                self.http2_initial_stream_window_size,
+            ),
+            (
+                "http2_initial_connection_window_size",
+                self.http2_initial_connection_window_size,
+            ),
+        ] {
+            if let Some(window) = window
+                && !(1..=MAX_WINDOW_SIZE).contains(&window.as_u64())
+            {
+                report.error(
+                    key,
+                    Error::invalid_input(format!(
+                        "[http.connection] {key} must be between 1 byte and 2 GiB - 1 byte."
+                    )),
+                );
+            }
+        }
+        if !self.http2_keep_alive_interval.is_zero() && self.http2_keep_alive_timeout.is_zero() {
+            report.error(
+                "http2_keep_alive_timeout",
+                Error::invalid_input(
+                    "[http.connection] http2_keep_alive_timeout must be greater than zero \
+                     when http2_keep_alive_interval is set.",
+                ),
+            );
+        }
+
+        // hyper's header timer also runs between HTTP/1 requests, so a shorter
+        // header timeout closes idle connections before keep_alive_timeout does.
+        if self.keep_alive
+            && !self.header_read_timeout.is_zero()
+            && self.header_read_timeout < self.keep_alive_timeout
+        {
+            report.warning(
+                "header_read_timeout",
+                format!(
+                    "[http.connection] header_read_timeout ({:?}) also closes idle HTTP/1 \
+                     connections, before keep_alive_timeout does. Set keep_alive_timeout to \
+                     at most header_read_timeout.",
+                    self.header_read_timeout
+                ),
+            );
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> HttpConnectionConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn missing_keys_keep_the_defaults() {
+        let config = parse("keep_alive_timeout = \"55s\"\nmax_header_size = \"16 KiB\"");
+        assert_eq!(config.keep_alive_timeout, Duration::from_secs(55));
+        assert_eq!(config.max_header_size, Some(Byte::from_u64(16 * 1024)));
+        assert!(config.keep_alive && config.h2c);
+        assert!(config.header_read_timeout.is_zero());
+        assert_eq!(config.per_ip_ipv6_prefix, 64);
+    }
+
+    #[test]
+    fn out_of_range_settings_are_rejected() {
+        let config = parse(
+            r#"
+            max_connections = 0
+            per_ip_ipv6_prefix = 129
+            max_headers = 0
+            max_header_size = "1 KiB"
+            http2_max_concurrent_streams = 0
+            http2_initial_stream_window_size = "4 GiB"
+            http2_keep_alive_interval = "10s"
+            http2_keep_alive_timeout = "0s"
+            "#,
+        );
+        let report = config.validation_report();
+        let paths: Vec<_> = report.errors().map(|issue| issue.path()).collect();
+        assert_eq!(
+            paths,
+            [
+                "max_connections",
+                "per_ip_ipv6_prefix",
+                "max_headers",
+                "max_header_size",
+                "http2_max_concurrent_streams",
+                "http2_initial_stream_window_size",
+                "http2_keep_alive_timeout",
+            ]
+        );
+    }
+
+    #[test]
+    fn header_timeout_shorter_than_idle_timeout_is_warned() {
+        let config = parse("header_read_timeout = \"10s\"\nkeep_alive_timeout = \"60s\"");
+        let report = config.validation_report();
+        assert!(!report.has_errors());
+        assert_eq!(report.warnings().count(), 1);
+
+        let config = parse("header_read_timeout = \"60s\"\nkeep_alive_timeout = \"55s\"");
+        assert_eq!(config.validation_report().warnings().count(), 0);
+    }
+}
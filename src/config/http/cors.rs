@@ -1,5 +1,5 @@
 use http::{HeaderName, Method};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{str::FromStr, time::Duration};
 
 /// Configuration for Cross-Origin Resource Sharing (CORS).
@@ -26,7 +26,8 @@ use std::{str::FromStr, time::Duration};
 /// exposed_headers = ["x-request-id"]
 /// max_age = "1h"
 /// ```
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct HttpCorsConfig {
     /// Whether to allow credentials (cookies, authorization headers) in CORS requests.
     /// When true, `allowed_origins`, `allowed_methods`, and `allowed_headers` must
@@ -57,6 +58,10 @@ pub struct HttpCorsConfig {
     /// For example: `"1h"` for 1 hour, `"30m"` for 30 minutes.
     /// This reduces the number of preflight requests made by browsers.
     #[serde(default, with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub max_age: Option<Duration>,
 }
 
@@ -183,6 +188,30 @@ impl<'de> Deserialize<'de> for CorsMethod {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for CorsMethod {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "CorsMethod".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        crate::config::schema::string(generator)
+    }
+}
+
+impl Serialize for CorsMethod {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
 /// Wrapper type for HTTP header names in CORS configuration.
 ///
 /// This type enables deserialization of HTTP header names from strings in TOML.
@@ -209,6 +238,30 @@ impl<'de> Deserialize<'de> for CorsHeader {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for CorsHeader {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "CorsHeader".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        crate::config::schema::string(generator)
+    }
+}
+
+impl Serialize for CorsHeader {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Config;
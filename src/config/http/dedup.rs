@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 /// Configuration for request deduplication using a custom middleware.
@@ -20,7 +20,8 @@ use std::time::Duration;
 /// ttl = "5m"              # Keep cached responses for 5 minutes
 /// max_entries = 10000     # Not used by axum-idempotent (for future compatibility)
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct HttpDeduplicationConfig {
     /// Time-to-live for completed request IDs in the cache.
     /// After a request completes, its ID remains in the cache for this duration
@@ -30,6 +31,10 @@ pub struct HttpDeduplicationConfig {
         default = "HttpDeduplicationConfig::default_ttl",
         with = "humantime_serde"
     )]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub ttl: Duration,
 
     /// Maximum number of request IDs to keep in the cache.
@@ -17,6 +17,8 @@ pub enum AuthMethod {
     Oidc,
     /// Proxy-based OIDC authentication (e.g., oauth2-proxy).
     ProxyOidc,
+    /// Mutual TLS: a client certificate verified during the TLS handshake.
+    ClientCertificate,
 }
 
 /// Identity of an authenticated user or service.
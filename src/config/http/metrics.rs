@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// How a [`MetricBucketsConfig`] entry matches recorded metric names.
 ///
@@ -8,7 +8,8 @@ use serde::Deserialize;
 /// In TOML the value is lowercase (`full`, `prefix`, `suffix`).
 ///
 /// [`Full`]: MetricMatch::Full
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum MetricMatch {
     /// Match a metric by its exact, full name.
@@ -46,7 +47,8 @@ pub enum MetricMatch {
 /// match   = "full"   # optional; defaults to "full"
 /// buckets = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct MetricBucketsConfig {
     /// Raw recorded metric name (or the prefix/suffix when `match` is set
     /// accordingly). See the type-level note: facade metrics are not prefixed.
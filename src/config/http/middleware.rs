@@ -1,8 +1,8 @@
-use crate::Result;
+use crate::{Result, ValidationReport};
 
 #[cfg(any(feature = "deduplication", feature = "keycloak"))]
 use crate::Error;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Configuration for which middleware to enable or disable.
 ///
@@ -26,7 +26,8 @@ use serde::Deserialize;
 /// [http.middleware]
 /// include = ["logging", "cors", "liveness", "readiness"]
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum HttpMiddlewareConfig {
     /// Only enable the middleware in this list. All others will be disabled.
     Include(Vec<HttpMiddleware>),
@@ -61,29 +62,54 @@ impl HttpMiddlewareConfig {
     /// - `RequestDeduplication` requires `RequestId` (uses x-request-id header as idempotency key)
     /// - `Oidc` requires `Session` (when keycloak feature is enabled)
     pub fn validate(&self) -> Result<()> {
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
+
+    /// Checks middleware dependencies, collecting every problem with its key
+    /// path (`Include` or `Exclude`) relative to `[http]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        #[cfg_attr(
+            not(any(feature = "deduplication", feature = "keycloak")),
+            allow(unused_mut)
+        )]
+        let mut report = ValidationReport::new();
+        #[cfg(any(feature = "deduplication", feature = "keycloak"))]
+        let key = match self {
+            Self::Include(_) => "Include",
+            Self::Exclude(_) => "Exclude",
+        };
+
         // RequestDeduplication depends on RequestId (only when deduplication feature is enabled)
         #[cfg(feature = "deduplication")]
         if self.is_enabled(HttpMiddleware::RequestDeduplication)
             && !self.is_enabled(HttpMiddleware::RequestId)
         {
-            return Err(Error::invalid_input(
-                "RequestDeduplication requires RequestId. Remove 'request-id' from Exclude list or add both to Include list.",
-            ));
+            report.error(
+                key,
+                Error::invalid_input(
+                    "RequestDeduplication requires RequestId. Remove 'request-id' from Exclude list or add both to Include list.",
+                ),
+            );
         }
 
         // Oidc depends on Session (when keycloak feature is enabled)
         #[cfg(feature = "keycloak")]
         if self.is_enabled(HttpMiddleware::Oidc) && !self.is_enabled(HttpMiddleware::Session) {
-            return Err(Error::invalid_input(
-                "Oidc requires Session middleware. Remove 'session' from Exclude list or add both to Include list.",
-            ));
+            report.error(
+                key,
+                Error::invalid_input(
+                    "Oidc requires Session middleware. Remove 'session' from Exclude list or add both to Include list.",
+                ),
+            );
         }
 
         // Note: BasicAuth and Oidc mutual exclusion is enforced at config level
         // (in HttpConfig::validate()) rather than middleware level, since
         // middleware can be "enabled" by default even without config.
 
-        Ok(())
+        report
     }
 }
 
@@ -95,7 +121,8 @@ impl HttpMiddlewareConfig {
 /// # TOML Names
 ///
 /// In configuration files, use kebab-case names (e.g., `rate-limiting`, `catch-panic`).
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum HttpMiddleware {
     /// OIDC/Keycloak authentication middleware.
@@ -114,6 +141,12 @@ pub enum HttpMiddleware {
     /// No feature flag required.
     ProxyOidc,
 
+    /// Client-certificate (mutual TLS) authentication middleware.
+    /// Maps the verified client certificate to an authenticated identity.
+    /// Requires the `tls` feature.
+    #[cfg(feature = "tls")]
+    ClientCertAuth,
+
     /// Request deduplication middleware.
     /// Prevents duplicate processing of requests with the same request ID.
     /// Requires `RequestId` middleware to be enabled.
@@ -1,7 +1,12 @@
+mod admin;
 #[cfg(feature = "basic-auth")]
 mod basic_auth;
+mod bind;
 #[cfg(feature = "circuit-breaker")]
 mod circuit_breaker;
+#[cfg(feature = "tls")]
+mod client_cert;
+mod connection;
 mod cors;
 mod dedup;
 mod identity;
@@ -10,13 +15,22 @@ mod middleware;
 #[cfg(feature = "keycloak")]
 mod oidc;
 mod proxy_oidc;
+mod proxy_protocol;
 mod role_extractors;
 mod staticdir;
+#[cfg(feature = "tls")]
+mod tls;
 
+pub use admin::HttpAdminConfig;
 #[cfg(feature = "basic-auth")]
 pub use basic_auth::{BasicAuthApiKey, BasicAuthMode, BasicAuthUser, HttpBasicAuthConfig};
+pub use bind::BindTarget;
+pub(crate) use bind::parse_socket_mode;
 #[cfg(feature = "circuit-breaker")]
 pub use circuit_breaker::{CircuitBreakerConfig, CircuitBreakerTargetConfig};
+#[cfg(feature = "tls")]
+pub use client_cert::{CertAttribute, ClientCertRoleMapping, HttpClientCertAuthConfig};
+pub use connection::HttpConnectionConfig;
 pub use cors::{CorsHeader, CorsMethod, HttpCorsConfig};
 pub use dedup::HttpDeduplicationConfig;
 pub use identity::{AuthMethod, AuthenticatedIdentity, SharedIdentity};
@@ -25,10 +39,19 @@ pub use middleware::{HttpMiddleware, HttpMiddlewareConfig};
 #[cfg(feature = "keycloak")]
 pub use oidc::HttpOidcConfig;
 pub use proxy_oidc::HttpProxyOidcConfig;
+pub use proxy_protocol::ProxyProtocol;
 pub use role_extractors::{AllRoles, AnyRole, ApplicationRole, ApplicationRoles, WithRole};
 pub use staticdir::{StaticDirConfig, StaticDirRoute};
+#[cfg(feature = "tls")]
+pub use tls::{HttpTlsConfig, TlsVersion};
 
-use {crate::Result, serde::Deserialize, std::fmt, std::time::Duration};
+use {
+    crate::{Result, ValidationReport},
+    ipnet::IpNet,
+    serde::{Deserialize, Serialize},
+    std::fmt,
+    std::time::Duration,
+};
 
 #[cfg(feature = "session")]
 use crate::utils::Sensitive;
@@ -37,7 +60,8 @@ use crate::utils::Sensitive;
 ///
 /// This enum is used for configuration parsing. When the `security-headers` feature
 /// is enabled, it will be converted to the actual `axum_helmet::XFrameOptions` value.
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum XFrameOptions {
     /// Prevents the page from being displayed in a frame
@@ -71,7 +95,8 @@ impl fmt::Display for XFrameOptions {
 /// session_same_site = "strict"  # or "lax" / "none"
 /// ```
 #[cfg(feature = "session")]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum SameSiteConfig {
     /// Cookie sent only for same-site requests (most CSRF-resistant).
@@ -99,7 +124,8 @@ pub enum SameSiteConfig {
 /// # url  = "redis://127.0.0.1:6379"
 /// ```
 #[cfg(feature = "session")]
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum SessionStoreConfig {
@@ -114,6 +140,7 @@ pub enum SessionStoreConfig {
     #[cfg(feature = "session-redis")]
     Redis {
         /// Redis connection URL, e.g. `redis://127.0.0.1:6379`.
+        #[serde(serialize_with = "crate::utils::serialize_url_redacted")]
         url: String,
     },
 }
@@ -142,7 +169,8 @@ impl SessionStoreConfig {
 /// of the HTTP server, including binding address and port, request limits,
 /// timeouts, and specific route paths.
 ///
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct HttpConfig {
     /// IP address to bind the HTTP server to.
     /// The default `bind_addr` is "0.0.0.0" (all interfaces), so the service is
@@ -156,6 +184,53 @@ pub struct HttpConfig {
     #[serde(default = "HttpConfig::default_bind_port")]
     pub bind_port: u16,
 
+    /// Listen somewhere other than `bind_addr:bind_port` over TCP:
+    /// `"unix:<path>"` for a Unix domain socket, `"systemd"` for the socket
+    /// passed through systemd socket activation (`LISTEN_FDS`), or
+    /// `"systemd:<name>"` for the one with that `FileDescriptorName=`.
+    /// Unset (the default) binds `bind_addr:bind_port`.
+    #[serde(default)]
+    pub bind: Option<String>,
+
+    /// Permissions of the socket file created for `bind = "unix:<path>"`, in
+    /// octal (e.g. `"0660"`). The socket only appears at its path once it has
+    /// this mode. Unset keeps the permissions the umask gives.
+    #[serde(default)]
+    pub unix_socket_mode: Option<String>,
+
+    /// PROXY protocol header to read at the start of each TCP connection:
+    /// `"v1"`, `"v2"` or `"optional"` (either, or none). The client address
+    /// it carries becomes the request's `ConnectInfo<SocketAddr>`. Only set it
+    /// behind a load balancer that sends the header; a Unix socket `bind` is
+    /// rejected. Unset (the default) expects no header.
+    #[serde(default)]
+    pub proxy_protocol: Option<ProxyProtocol>,
+
+    /// CIDR ranges of the load balancers allowed to send a PROXY header.
+    /// Connections from any other peer are not parsed and keep their own
+    /// address. Empty (the default) reads the header from every peer, which
+    /// is rejected together with `[http.proxy_oidc] trusted_proxies`.
+    #[serde(default)]
+    #[cfg_attr(feature = "json-schema", schemars(with = "Vec<String>"))]
+    pub proxy_protocol_trusted: Vec<IpNet>,
+
+    /// Separate listener for the liveness, readiness and metrics routes.
+    /// When None (the default), they are served on
+    /// the public listener with the full middleware stack.
+    #[serde(default)]
+    pub admin: Option<HttpAdminConfig>,
+
+    /// HTTP/1 and HTTP/2 connection settings: header read and keep-alive
+    /// timeouts, header limits and HTTP/2 flow control.
+    #[serde(default)]
+    pub connection: HttpConnectionConfig,
+
+    /// HTTPS configuration. Only included if the "tls" feature is enabled.
+    /// When None (the default), the server speaks plain HTTP.
+    #[cfg(feature = "tls")]
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
+
     /// Maximum number of concurrent requests to handle.
     /// If the number of concurrent requests exceeds this number, new requests
     /// will be rejected with a 503 Service Unavailable response.
@@ -179,12 +254,20 @@ pub struct HttpConfig {
         default = "HttpConfig::default_request_timeout",
         with = "humantime_serde"
     )]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub request_timeout: Option<Duration>,
 
     /// Maximum payload size in bytes for incoming HTTP requests.
     /// Requests with payloads larger than this will be rejected with
     /// a 413 Payload Too Large response.
     /// By default `max_payload_size_bytes` is set to 32KiB.
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::byte_size")
+    )]
     pub max_payload_size_bytes: byte_unit::Byte,
 
     /// Whether or not to support gzip/brotli/deflate/zstd request and
@@ -219,6 +302,22 @@ pub struct HttpConfig {
     #[serde(default = "HttpConfig::default_metrics_route")]
     pub metrics_route: String,
 
+    /// Route serving the effective configuration, with secrets masked (see
+    /// [`Config::to_redacted_toml`](crate::Config::to_redacted_toml)). JSON by
+    /// default, TOML with `?format=toml`. Unset (the default) disables the route.
+    /// It is served on the public listener behind authentication, so
+    /// `[http.basic_auth]`, `[http.oidc]`, `[http.proxy_oidc]` or
+    /// `[http.client_cert_auth]` must be configured, together with
+    /// [`debug_config_role`](Self::debug_config_role).
+    #[serde(default)]
+    pub debug_config_route: Option<String>,
+
+    /// Role or group the caller of [`debug_config_route`](Self::debug_config_route)
+    /// must have (in `identity.roles` or `identity.groups`); others get
+    /// `403 Forbidden`. Required when the route is set.
+    #[serde(default)]
+    pub debug_config_role: Option<String>,
+
     /// Whether to set the X-Content-Type-Options header to "nosniff".
     /// By default `x_content_type_nosniff` is set to true.
     #[serde(default = "HttpConfig::default_x_content_type_nosniff")]
@@ -300,6 +399,13 @@ pub struct HttpConfig {
     #[serde(default)]
     pub proxy_oidc: Option<HttpProxyOidcConfig>,
 
+    /// Client-certificate (mutual TLS) authentication configuration.
+    /// Only included if the "tls" feature is enabled. Requires
+    /// `[http.tls] client_ca_file` so that clients present verified certificates.
+    #[cfg(feature = "tls")]
+    #[serde(default)]
+    pub client_cert_auth: Option<HttpClientCertAuthConfig>,
+
     /// CORS configuration. If not present defaults to permissive CORS.
     pub cors: Option<HttpCorsConfig>,
 
@@ -320,12 +426,20 @@ pub struct HttpConfig {
     /// metric kinds). `None` (the default) disables idle eviction, matching the
     /// built-in behavior. Accepts human-readable durations (e.g. `"5m"`).
     #[serde(default, with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub metrics_idle_timeout: Option<Duration>,
 
     /// Interval of the background upkeep loop that drives idle eviction / state
     /// maintenance for the Prometheus recorder. `None` (the default) uses the
     /// built-in 5-second interval. Accepts human-readable durations (e.g. `"10s"`).
     #[serde(default, with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub metrics_upkeep_timeout: Option<Duration>,
 
     /// Default API version to use when clients don't specify one.
@@ -340,14 +454,33 @@ pub struct HttpConfig {
     pub deduplication: Option<HttpDeduplicationConfig>,
 
     /// Maximum time to wait for graceful shutdown to complete.
-    /// After this timeout, the server will force shutdown.
+    /// After this timeout, the server will force shutdown. With
+    /// [`FluentRouter::on_shutdown`](crate::FluentRouter::on_shutdown) hooks,
+    /// up to a quarter of it is reserved for them after the drain.
     /// By default `shutdown_timeout` is set to 30 seconds.
     #[serde(
         default = "HttpConfig::default_shutdown_timeout",
         with = "humantime_serde"
     )]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
     pub shutdown_timeout: Duration,
 
+    /// How long the server keeps serving after a shutdown signal, with the
+    /// readiness route already answering 503, before it closes the listener
+    /// and starts draining. This gives load balancers and Kubernetes time to
+    /// stop routing new traffic to the instance. The `shutdown_timeout` for
+    /// draining starts after the delay.
+    /// By default `shutdown_pre_stop_delay` is `"0s"`.
+    #[serde(default, with = "humantime_serde")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub shutdown_pre_stop_delay: Duration,
+
     /// Middleware include/exclude configuration (flattened from `[http.middleware]`).
     #[serde(flatten)]
     pub middleware: Option<HttpMiddlewareConfig>,
@@ -361,6 +494,15 @@ impl HttpConfig {
         format!("{}:{}", self.bind_addr, self.bind_port)
     }
 
+    /// Returns where the server listens: `bind` when set, otherwise
+    /// [`full_bind_addr`](Self::full_bind_addr) over TCP.
+    pub fn bind_target(&self) -> Result<BindTarget> {
+        match &self.bind {
+            Some(bind) => BindTarget::parse(bind),
+            None => Ok(BindTarget::Tcp(self.full_bind_addr())),
+        }
+    }
+
     /// Returns true if `bind_addr` is a loopback address (e.g. `127.0.0.1`, `::1`).
     pub fn bind_addr_is_loopback(&self) -> bool {
         self.bind_addr
@@ -439,49 +581,162 @@ impl HttpConfig {
         true
     }
 
+    /// Returns true if an authentication method (Basic Auth / API keys, OIDC or
+    /// proxy headers) is configured.
+    fn has_authentication(&self) -> bool {
+        #[cfg(feature = "basic-auth")]
+        if self.basic_auth.is_some() {
+            return true;
+        }
+        #[cfg(feature = "keycloak")]
+        if self.oidc.is_some() {
+            return true;
+        }
+        #[cfg(feature = "tls")]
+        if self.client_cert_auth.is_some() {
+            return true;
+        }
+        self.proxy_oidc.is_some()
+    }
+
     /// Validates the HTTP configuration (bind address, limits, auth mutual
-    /// exclusion, static directories, middleware dependencies), returning an
-    /// error with actionable guidance on the first problem found.
+    /// exclusion, static directories, middleware dependencies), logging the
+    /// warnings and returning an error with actionable guidance that lists
+    /// every problem found.
     pub fn validate(&self) -> Result<()> {
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
+
+    /// Checks the HTTP configuration, collecting every error and warning with
+    /// its key path relative to `[http]` (e.g. `directories[1].cache_max_age`).
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+
         // Validate bind address
         if self.bind_addr.trim().is_empty() {
-            return Err(crate::Error::invalid_input(
-                "HTTP bind_addr is required. Set [http] bind_addr = \"0.0.0.0\" or \"127.0.0.1\" in config.",
-            ));
+            report.error(
+                "bind_addr",
+                crate::Error::invalid_input(
+                    "HTTP bind_addr is required. Set [http] bind_addr = \"0.0.0.0\" or \"127.0.0.1\" in config.",
+                ),
+            );
         }
-
         // Validate bind address format (basic IP address validation)
-        if self.bind_addr.parse::<std::net::IpAddr>().is_err() {
-            return Err(crate::Error::invalid_input(
-                "HTTP bind_addr must be a valid IP address. Examples: \"127.0.0.1\", \"0.0.0.0\", \"::1\"",
-            ));
+        else if self.bind_addr.parse::<std::net::IpAddr>().is_err() {
+            report.error(
+                "bind_addr",
+                crate::Error::invalid_input(
+                    "HTTP bind_addr must be a valid IP address. Examples: \"127.0.0.1\", \"0.0.0.0\", \"::1\"",
+                ),
+            );
         }
-
         // Warn when bound to loopback: in a Kubernetes pod this is unreachable by
         // the kubelet's health probes and by other pods. Fine for local dev.
-        if self.bind_addr_is_loopback() {
-            tracing::warn!(
-                bind_addr = %self.bind_addr,
-                "HTTP server bound to a loopback address; it will be unreachable from \
-                 outside the host (e.g. Kubernetes probes). Use \"0.0.0.0\" for deployments."
+        else if self.bind_addr_is_loopback() {
+            report.warning(
+                "bind_addr",
+                format!(
+                    "HTTP server bound to loopback address {}; it will be unreachable from \
+                     outside the host (e.g. Kubernetes probes). Use \"0.0.0.0\" for deployments.",
+                    self.bind_addr
+                ),
+            );
+        }
+
+        // Validate the alternative listen target
+        match self.bind_target() {
+            Err(e) => report.error("bind", e),
+            Ok(BindTarget::Tcp(_)) => {}
+            Ok(_) if !cfg!(unix) => report.error(
+                "bind",
+                crate::Error::invalid_input(
+                    "HTTP bind = \"unix:...\" and \"systemd\" are only supported on Unix platforms.",
+                ),
+            ),
+            #[cfg(feature = "tls")]
+            Ok(BindTarget::Unix(_)) if self.tls.is_some() => report.error(
+                "bind",
+                crate::Error::invalid_input(
+                    "[http.tls] is not supported on a Unix socket. Terminate TLS in front of \
+                     the socket or listen on TCP.",
+                ),
+            ),
+            Ok(_) => {}
+        }
+        if let Some(mode) = &self.unix_socket_mode {
+            if let Err(e) = parse_socket_mode(mode) {
+                report.error("unix_socket_mode", e);
+            } else if !matches!(self.bind_target(), Ok(BindTarget::Unix(_))) {
+                report.warning(
+                    "unix_socket_mode",
+                    "HTTP unix_socket_mode only applies to bind = \"unix:<path>\" and is ignored.",
+                );
+            }
+        }
+
+        report.merge("connection", self.connection.validation_report());
+
+        match (self.proxy_protocol, self.bind_target()) {
+            (Some(_), Ok(BindTarget::Unix(_))) => report.error(
+                "proxy_protocol",
+                crate::Error::invalid_input(
+                    "HTTP proxy_protocol only applies to TCP listeners. Remove it or bind to a \
+                     TCP address instead of a Unix socket.",
+                ),
+            ),
+            (Some(ProxyProtocol::Optional), _) => report.warning(
+                "proxy_protocol",
+                "HTTP proxy_protocol = \"optional\" lets clients that connect directly claim \
+                 any address. Use \"v1\" or \"v2\" once every connection comes through the \
+                 load balancer.",
+            ),
+            _ => {}
+        }
+        // A client that can send its own PROXY header could claim the address
+        // of a trusted proxy and pass the proxy-OIDC peer check.
+        if self.proxy_protocol.is_some()
+            && self.proxy_protocol_trusted.is_empty()
+            && self
+                .proxy_oidc
+                .as_ref()
+                .is_some_and(|proxy_oidc| !proxy_oidc.trusted_proxies.is_empty())
+        {
+            report.error(
+                "proxy_protocol_trusted",
+                crate::Error::invalid_input(
+                    "HTTP proxy_protocol with [http.proxy_oidc] trusted_proxies requires \
+                     proxy_protocol_trusted, so only the load balancer can set the client \
+                     address. List the load balancer's CIDR ranges in \
+                     [http] proxy_protocol_trusted.",
+                ),
             );
         }
 
         // Validate max_concurrent_requests is not zero
         if self.max_concurrent_requests == 0 {
-            return Err(crate::Error::invalid_input(
-                "HTTP max_concurrent_requests must be > 0. Set [http] max_concurrent_requests = 4096 in config.",
-            ));
+            report.error(
+                "max_concurrent_requests",
+                crate::Error::invalid_input(
+                    "HTTP max_concurrent_requests must be > 0. Set [http] max_concurrent_requests = 4096 in config.",
+                ),
+            );
+        }
+
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            report.merge("tls", tls.validation_report());
         }
 
         #[cfg(feature = "keycloak")]
         if let Some(oidc_config) = &self.oidc {
-            oidc_config.validate()?;
+            report.merge("oidc", oidc_config.validation_report());
         }
 
         #[cfg(feature = "basic-auth")]
         if let Some(basic_auth_config) = &self.basic_auth {
-            basic_auth_config.validate()?;
+            report.merge("basic_auth", basic_auth_config.validation_report());
         }
 
         // Mutual exclusion: basic_auth and oidc cannot both be configured in bearer-only mode.
@@ -491,44 +746,161 @@ impl HttpConfig {
         if let (Some(_basic_auth), Some(oidc)) = (&self.basic_auth, &self.oidc)
             && !oidc.auth_code_flow_enabled()
         {
-            return Err(crate::Error::invalid_input(
-                "Cannot configure both [http.basic_auth] and [http.oidc] in bearer-only mode. \
-                 Either enable auth code flow by setting redirect_uri, or choose one authentication method.",
-            ));
+            report.error(
+                "oidc",
+                crate::Error::invalid_input(
+                    "Cannot configure both [http.basic_auth] and [http.oidc] in bearer-only mode. \
+                     Either enable auth code flow by setting redirect_uri, or choose one authentication method.",
+                ),
+            );
         }
 
         // Mutual exclusion: proxy_oidc cannot be used with basic_auth
         #[cfg(feature = "basic-auth")]
         if self.basic_auth.is_some() && self.proxy_oidc.is_some() {
-            return Err(crate::Error::invalid_input(
-                "Cannot configure both [http.basic_auth] and [http.proxy_oidc]. Choose one authentication method.",
-            ));
+            report.error(
+                "proxy_oidc",
+                crate::Error::invalid_input(
+                    "Cannot configure both [http.basic_auth] and [http.proxy_oidc]. Choose one authentication method.",
+                ),
+            );
         }
 
         // Mutual exclusion: proxy_oidc cannot be used with oidc
         #[cfg(feature = "keycloak")]
         if self.oidc.is_some() && self.proxy_oidc.is_some() {
-            return Err(crate::Error::invalid_input(
-                "Cannot configure both [http.oidc] and [http.proxy_oidc]. Choose one authentication method.",
-            ));
+            report.error(
+                "proxy_oidc",
+                crate::Error::invalid_input(
+                    "Cannot configure both [http.oidc] and [http.proxy_oidc]. Choose one authentication method.",
+                ),
+            );
+        }
+
+        // Client-certificate auth needs the handshake to verify certificates,
+        // and replaces rather than complements the other methods.
+        #[cfg(feature = "tls")]
+        if let Some(client_cert_auth) = &self.client_cert_auth {
+            report.merge("client_cert_auth", client_cert_auth.validation_report());
+
+            if self
+                .tls
+                .as_ref()
+                .is_none_or(|tls| tls.client_ca_file.is_none())
+            {
+                report.error(
+                    "client_cert_auth",
+                    crate::Error::invalid_input(
+                        "[http.client_cert_auth] requires client certificates to be verified. \
+                         Set [http.tls] client_ca_file to the CA bundle that signs them.",
+                    ),
+                );
+            }
+
+            #[cfg(feature = "basic-auth")]
+            let other_auth = self.basic_auth.is_some();
+            #[cfg(not(feature = "basic-auth"))]
+            let other_auth = false;
+            #[cfg(feature = "keycloak")]
+            let other_auth = other_auth || self.oidc.is_some();
+            if other_auth || self.proxy_oidc.is_some() {
+                report.error(
+                    "client_cert_auth",
+                    crate::Error::invalid_input(
+                        "Cannot configure [http.client_cert_auth] together with [http.basic_auth], \
+                         [http.oidc] or [http.proxy_oidc]. Choose one authentication method.",
+                    ),
+                );
+            }
+        }
+
+        // The admin listener needs a port of its own.
+        if let Some(admin) = &self.admin {
+            report.merge("admin", admin.validation_report());
+            if self.bind.is_none() && admin.bind_port == self.bind_port && admin.bind_port != 0 {
+                report.error(
+                    "admin.bind_port",
+                    crate::Error::invalid_input(format!(
+                        "[http.admin] bind_port {} is already the public bind_port. \
+                         Choose another port, e.g. bind_port = 9090.",
+                        admin.bind_port
+                    )),
+                );
+            }
+        }
+
+        // The effective-config dump is only ever served to authenticated callers
+        // holding a dedicated role.
+        if let Some(route) = &self.debug_config_route {
+            if !route.starts_with('/') {
+                report.error(
+                    "debug_config_route",
+                    crate::Error::invalid_input(format!(
+                        "HTTP debug_config_route must start with '/', got {route:?}. \
+                         Example: debug_config_route = \"/debug/config\"",
+                    )),
+                );
+            }
+            if !self.has_authentication() {
+                report.error(
+                    "debug_config_route",
+                    crate::Error::invalid_input(
+                        "HTTP debug_config_route requires authentication. Configure \
+                         [http.basic_auth], [http.oidc], [http.proxy_oidc] or \
+                         [http.client_cert_auth], or remove debug_config_route.",
+                    ),
+                );
+            }
+            if self
+                .debug_config_role
+                .as_deref()
+                .is_none_or(|role| role.trim().is_empty())
+            {
+                report.error(
+                    "debug_config_role",
+                    crate::Error::invalid_input(
+                        "HTTP debug_config_route requires debug_config_role, the role or \
+                         group allowed to read the configuration. \
+                         Example: debug_config_role = \"config-reader\"",
+                    ),
+                );
+            }
         }
 
         // Validate individual static directories
-        for dir in &self.directories {
-            dir.validate()?;
+        for (index, dir) in self.directories.iter().enumerate() {
+            report.merge(&format!("directories[{index}]"), dir.validation_report());
         }
 
         // Validate that there's at most one fallback directory
         let fallback_count = self.directories.iter().filter(|d| d.is_fallback()).count();
         if fallback_count > 1 {
-            return Err(crate::Error::invalid_input(
-                "Only one static directory can be configured as fallback",
-            ));
+            report.error(
+                "directories",
+                crate::Error::invalid_input(
+                    "Only one static directory can be configured as fallback",
+                ),
+            );
         }
 
         // Validate middleware dependencies
         if let Some(middleware_config) = &self.middleware {
-            middleware_config.validate()?;
+            report.merge("", middleware_config.validation_report());
+        }
+
+        // Without the readiness route, nothing tells the load balancer to stop
+        // routing traffic during the delay; shutdown is only slower.
+        if !self.shutdown_pre_stop_delay.is_zero()
+            && self
+                .middleware
+                .as_ref()
+                .is_some_and(|middleware| !middleware.is_enabled(HttpMiddleware::Readiness))
+        {
+            report.warning(
+                "shutdown_pre_stop_delay",
+                "shutdown_pre_stop_delay is set but the readiness middleware is disabled, so \
+                 load balancers cannot see the instance is shutting down during the delay.",
+            );
         }
 
         // Warn when the per-process in-memory session store is used on a
@@ -536,11 +908,12 @@ impl HttpConfig {
         #[cfg(feature = "session")]
         if matches!(self.session_store, SessionStoreConfig::Memory) && !self.bind_addr_is_loopback()
         {
-            tracing::warn!(
+            report.warning(
+                "session_store",
                 "Using the in-memory session store while not bound to loopback. Sessions are \
                  per-process and will not be shared across replicas; configure \
                  [http.session_store] with type = \"postgres\" or \"redis\" for multi-replica \
-                 deployments."
+                 deployments.",
             );
         }
 
@@ -553,10 +926,13 @@ impl HttpConfig {
                 .as_ref()
                 .is_some_and(|s| s.0.trim().is_empty())
         {
-            return Err(crate::Error::config(
-                "[http.proxy_oidc] shared_secret is configured but empty (e.g. an unset \
-                 environment variable). Remove it or provide a non-empty secret.",
-            ));
+            report.error(
+                "proxy_oidc.shared_secret",
+                crate::Error::config(
+                    "[http.proxy_oidc] shared_secret is configured but empty (e.g. an unset \
+                     environment variable). Remove it or provide a non-empty secret.",
+                ),
+            );
         }
 
         // An external session store persists records outside the process, so they
@@ -564,51 +940,60 @@ impl HttpConfig {
         #[cfg(feature = "session")]
         if self.session_store.is_external() {
             match &self.session_signing_key {
-                None => {
-                    return Err(crate::Error::config(
+                None => report.error(
+                    "session_signing_key",
+                    crate::Error::config(
                         "session_store is external (postgres/redis) but no session_signing_key \
                          is configured. Set [http] session_signing_key (>= 32 bytes, stable \
                          across replicas) so session records can be HMAC-tagged against tampering.",
-                    ));
-                }
-                Some(key) if key.0.len() < 32 => {
-                    return Err(crate::Error::config(
+                    ),
+                ),
+                Some(key) if key.0.len() < 32 => report.error(
+                    "session_signing_key",
+                    crate::Error::config(
                         "session_signing_key is too short; it must be at least 32 bytes.",
-                    ));
-                }
+                    ),
+                ),
                 Some(_) => {}
             }
         }
 
         // Warn if CORS is not explicitly configured (will use permissive defaults)
         if self.cors.is_none() {
-            tracing::warn!(
+            report.warning(
+                "cors",
                 "No CORS configuration found. Permissive defaults will be used, \
                  which allows all origins. Consider configuring explicit CORS rules \
-                 for production environments."
+                 for production environments.",
             );
         }
 
         // Warn (non-fatal) about metrics bucket lists that the recorder will
         // ignore or that would render incorrectly. An empty list is skipped at
         // setup time; non-ascending `le` bounds produce a malformed histogram.
-        for entry in &self.metrics_buckets {
+        for (index, entry) in self.metrics_buckets.iter().enumerate() {
             if entry.buckets.is_empty() {
-                tracing::warn!(
-                    metric = %entry.metric,
-                    "[[http.metrics_buckets]] entry has an empty bucket list; it will be \
-                     ignored and the metric keeps its default rendering."
+                report.warning(
+                    format!("metrics_buckets[{index}].buckets"),
+                    format!(
+                        "[[http.metrics_buckets]] entry for {} has an empty bucket list; it \
+                         will be ignored and the metric keeps its default rendering.",
+                        entry.metric
+                    ),
                 );
             } else if entry.buckets.windows(2).any(|w| w[1] <= w[0]) {
-                tracing::warn!(
-                    metric = %entry.metric,
-                    "[[http.metrics_buckets]] buckets are not strictly ascending; Prometheus \
-                     histogram `le` upper bounds must increase monotonically."
+                report.warning(
+                    format!("metrics_buckets[{index}].buckets"),
+                    format!(
+                        "[[http.metrics_buckets]] buckets for {} are not strictly ascending; \
+                         Prometheus histogram `le` upper bounds must increase monotonically.",
+                        entry.metric
+                    ),
                 );
             }
         }
 
-        Ok(())
+        report
     }
 }
 
@@ -617,6 +1002,14 @@ impl Default for HttpConfig {
         HttpConfig {
             bind_addr: Self::default_bind_addr(),
             bind_port: Self::default_bind_port(),
+            bind: None,
+            unix_socket_mode: None,
+            proxy_protocol: None,
+            proxy_protocol_trusted: Vec::new(),
+            admin: None,
+            connection: HttpConnectionConfig::default(),
+            #[cfg(feature = "tls")]
+            tls: None,
             max_payload_size_bytes: Self::default_max_payload_size_bytes(),
             max_concurrent_requests: Self::default_max_concurrent_requests(),
             max_requests_per_sec: Self::default_max_requests_per_sec(),
@@ -627,6 +1020,8 @@ impl Default for HttpConfig {
             liveness_route: Self::default_liveness_route(),
             readiness_route: Self::default_readiness_route(),
             metrics_route: Self::default_metrics_route(),
+            debug_config_route: None,
+            debug_config_role: None,
             x_content_type_nosniff: Self::default_x_content_type_nosniff(),
             x_frame_options: Self::default_x_frame_options(),
             hsts_max_age: None,
@@ -647,6 +1042,8 @@ impl Default for HttpConfig {
             #[cfg(feature = "basic-auth")]
             basic_auth: None,
             proxy_oidc: None,
+            #[cfg(feature = "tls")]
+            client_cert_auth: None,
             cors: None,
             metrics_buckets: Vec::new(),
             metrics_global_labels: std::collections::BTreeMap::new(),
@@ -654,6 +1051,7 @@ impl Default for HttpConfig {
             metrics_upkeep_timeout: None,
             deduplication: None,
             shutdown_timeout: Self::default_shutdown_timeout(),
+            shutdown_pre_stop_delay: Duration::ZERO,
             middleware: None,
         }
     }
@@ -721,6 +1119,38 @@ impl<'de> Deserialize<'de> for HttpXFrameConfig {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for HttpXFrameConfig {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "HttpXFrameConfig".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "\"DENY\", \"SAMEORIGIN\" or an origin allowed to frame the page",
+            "examples": ["DENY", "SAMEORIGIN", "https://example.com"]
+        })
+    }
+}
+
+impl Serialize for HttpXFrameConfig {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // The inverse of `Deserialize`: an allowed origin is written bare.
+        match &self.0 {
+            XFrameOptions::AllowFrom(url) => serializer.serialize_str(url),
+            other => serializer.collect_str(other),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -761,6 +1191,126 @@ mod tests {
         );
     }
 
+    #[test]
+    fn debug_config_route_requires_authentication() {
+        let config = Config::<()>::from_toml("").unwrap();
+        let mut http = config.http;
+
+        http.debug_config_route = Some("/debug/config".into());
+        http.debug_config_role = Some("config-reader".into());
+        let err = http.validate().unwrap_err();
+        assert!(err.to_string().contains("requires authentication"), "{err}");
+
+        http.proxy_oidc = Some(HttpProxyOidcConfig::default());
+        assert!(http.validate().is_ok());
+
+        http.debug_config_role = None;
+        let err = http.validate().unwrap_err();
+        assert!(err.to_string().contains("debug_config_role"), "{err}");
+        http.debug_config_role = Some("config-reader".into());
+
+        http.debug_config_route = Some("debug/config".into());
+        assert!(http.validate().is_err(), "route must start with '/'");
+    }
+
+    #[test]
+    fn admin_listener_needs_its_own_port() {
+        let mut http = Config::<()>::from_toml("").unwrap().http;
+        http.admin = Some(HttpAdminConfig::new(http.bind_port));
+        let report = http.validation_report();
+        assert!(
+            report
+                .errors()
+                .any(|issue| issue.path() == "admin.bind_port")
+        );
+
+        http.admin = Some(HttpAdminConfig::new(9090));
+        assert_eq!(
+            http.admin.as_ref().unwrap().full_bind_addr(&http.bind_addr),
+            "0.0.0.0:9090"
+        );
+    }
+
+    #[test]
+    fn bind_and_unix_socket_mode_are_validated() {
+        let config = Config::<()>::from_toml("").unwrap();
+        let mut http = config.http;
+        let error_paths = |http: &HttpConfig| {
+            let report = http.validation_report();
+            report
+                .errors()
+                .map(|issue| issue.path().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        http.bind = Some("tcp:0.0.0.0:80".into());
+        assert_eq!(error_paths(&http), ["bind"]);
+
+        http.bind = Some("unix:/run/app/http.sock".into());
+        http.unix_socket_mode = Some("0999".into());
+        assert_eq!(error_paths(&http), ["unix_socket_mode"]);
+
+        http.unix_socket_mode = Some("0660".into());
+        assert!(error_paths(&http).is_empty());
+
+        http.proxy_protocol = Some(ProxyProtocol::V2);
+        assert_eq!(error_paths(&http), ["proxy_protocol"]);
+        http.proxy_protocol = None;
+
+        http.bind = None;
+        let report = http.validation_report();
+        assert!(!report.has_errors());
+        assert!(
+            report
+                .warnings()
+                .any(|issue| issue.path() == "unix_socket_mode")
+        );
+    }
+
+    #[test]
+    fn proxy_protocol_with_trusted_proxies_requires_trusted_peers() {
+        let config = Config::<()>::from_toml("").unwrap();
+        let mut http = config.http;
+        let has_error = |http: &HttpConfig| {
+            http.validation_report()
+                .errors()
+                .any(|issue| issue.path() == "proxy_protocol_trusted")
+        };
+
+        http.proxy_protocol = Some(ProxyProtocol::V2);
+        assert!(!has_error(&http));
+
+        http.proxy_oidc = Some(HttpProxyOidcConfig {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        });
+        assert!(has_error(&http));
+        http.proxy_protocol = Some(ProxyProtocol::Optional);
+        assert!(has_error(&http));
+
+        http.proxy_protocol_trusted = vec!["10.0.1.0/24".parse().unwrap()];
+        assert!(!has_error(&http));
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn client_cert_auth_requires_client_ca_and_no_other_auth() {
+        let config = Config::<()>::from_toml("").unwrap();
+        let mut http = config.http;
+        http.client_cert_auth = Some(HttpClientCertAuthConfig::default());
+
+        http.tls = Some(HttpTlsConfig::new("tls.crt", "tls.key"));
+        let err = http.validate().unwrap_err();
+        assert!(err.to_string().contains("client_ca_file"), "{err}");
+
+        http.tls.as_mut().unwrap().client_ca_file = Some("ca.crt".into());
+        assert!(http.validate().is_ok());
+
+        http.proxy_oidc = Some(HttpProxyOidcConfig::default());
+        let err = http.validate().unwrap_err();
+        assert!(err.to_string().contains("Choose one"), "{err}");
+    }
+
     #[test]
     fn test_x_content_type_nosniff_default() {
         let config_str = r#"
@@ -60,8 +60,8 @@
 //! In bearer-only mode (no `redirect_uri`), they are mutually exclusive since both
 //! compete for the `Authorization` header.
 
-use crate::{Error, Result, utils::Sensitive};
-use serde::Deserialize;
+use crate::{Error, Result, ValidationReport, utils::Sensitive};
+use serde::{Deserialize, Serialize};
 
 /// Configuration for OIDC (OpenID Connect) authentication.
 ///
@@ -97,7 +97,8 @@ use serde::Deserialize;
 /// - `callback_route` - Callback endpoint path (default: `"/auth/callback"`)
 /// - `logout_route` - Logout endpoint path (default: `"/auth/logout"`)
 #[allow(unused)]
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct HttpOidcConfig {
     /// Base issuer URL of the OIDC provider (e.g. `https://keycloak.example.com`).
     #[serde(default)]
@@ -211,52 +212,73 @@ impl HttpOidcConfig {
     }
 
     /// Validates the OIDC configuration, returning an error with actionable
-    /// guidance when a required field is missing or inconsistent.
+    /// guidance listing every required field that is missing or inconsistent.
     pub fn validate(&self) -> Result<()> {
-        if self.issuer_url.trim().is_empty() {
-            return Err(Error::invalid_input(
-                "OIDC issuer_url is required. Set [http.oidc] issuer_url = \"https://your-keycloak-server\" in config.",
-            ));
-        }
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
 
-        if !self.issuer_url.starts_with("http://") && !self.issuer_url.starts_with("https://") {
-            return Err(Error::invalid_input(
-                "OIDC issuer_url must start with http:// or https://. Example: \"https://keycloak.example.com\"",
-            ));
+    /// Checks the OIDC configuration, collecting every problem with its key
+    /// path relative to `[http.oidc]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        let is_http_url = |url: &str| url.starts_with("http://") || url.starts_with("https://");
+
+        if self.issuer_url.trim().is_empty() {
+            report.error(
+                "issuer_url",
+                Error::invalid_input(
+                    "OIDC issuer_url is required. Set [http.oidc] issuer_url = \"https://your-keycloak-server\" in config.",
+                ),
+            );
+        } else if !is_http_url(&self.issuer_url) {
+            report.error(
+                "issuer_url",
+                Error::invalid_input(
+                    "OIDC issuer_url must start with http:// or https://. Example: \"https://keycloak.example.com\"",
+                ),
+            );
         }
 
         if self.client_id.trim().is_empty() {
-            return Err(Error::invalid_input(
-                "OIDC client_id is required. Set [http.oidc] client_id = \"your-client-id\" in config.",
-            ));
+            report.error(
+                "client_id",
+                Error::invalid_input(
+                    "OIDC client_id is required. Set [http.oidc] client_id = \"your-client-id\" in config.",
+                ),
+            );
         }
 
         if self.client_secret.0.is_empty() {
-            return Err(Error::invalid_input(
-                "OIDC client_secret is required. Set [http.oidc] client_secret = \"{{ OIDC_CLIENT_SECRET }}\" to use env var.",
-            ));
+            report.error(
+                "client_secret",
+                Error::invalid_input(
+                    "OIDC client_secret is required. Set [http.oidc] client_secret = \"{{ OIDC_CLIENT_SECRET }}\" to use env var.",
+                ),
+            );
         }
 
         // Validate redirect_uri format when present
         if let Some(redirect_uri) = &self.redirect_uri
-            && !redirect_uri.starts_with("http://")
-            && !redirect_uri.starts_with("https://")
+            && !is_http_url(redirect_uri)
         {
-            return Err(Error::invalid_input(
-                "OIDC redirect_uri must start with http:// or https://.",
-            ));
+            report.error(
+                "redirect_uri",
+                Error::invalid_input("OIDC redirect_uri must start with http:// or https://."),
+            );
         }
 
         if let Some(jwks_url) = &self.jwks_url
-            && !jwks_url.starts_with("http://")
-            && !jwks_url.starts_with("https://")
+            && !is_http_url(jwks_url)
         {
-            return Err(Error::invalid_input(
-                "OIDC jwks_url must start with http:// or https://.",
-            ));
+            report.error(
+                "jwks_url",
+                Error::invalid_input("OIDC jwks_url must start with http:// or https://."),
+            );
         }
 
-        Ok(())
+        report
     }
 }
 
@@ -18,7 +18,7 @@
 
 use crate::utils::Sensitive;
 use ipnet::IpNet;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Configuration for proxy-based OIDC authentication.
 ///
@@ -44,7 +44,8 @@ use serde::Deserialize;
 /// When **neither** is configured, the headers are honored only outside production
 /// (`RUST_ENV` unset / `prod` / `production` / `release` ⇒ production) and ignored
 /// in production — i.e. **fail-closed by default**.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct HttpProxyOidcConfig {
     /// Header containing the authenticated user identifier.
     #[serde(default = "HttpProxyOidcConfig::default_user_header")]
@@ -84,7 +85,11 @@ pub struct HttpProxyOidcConfig {
     /// workload routing through that hop spoof identity. **For load-balanced
     /// topologies use [`shared_secret`](Self::shared_secret) instead**, which does
     /// not depend on the peer IP.
+    ///
+    /// A peer connecting over a Unix socket (`bind = "unix:..."`) has no IP
+    /// address and never matches; a proxy on the socket needs `shared_secret`.
     #[serde(default)]
+    #[cfg_attr(feature = "json-schema", schemars(with = "Vec<String>"))]
     pub trusted_proxies: Vec<IpNet>,
 
     /// Shared secret the trusted proxy must echo in
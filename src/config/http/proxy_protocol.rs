@@ -0,0 +1,45 @@
+//! PROXY protocol configuration.
+//!
+//! Load balancers such as AWS NLB and HAProxy can prepend a PROXY protocol
+//! header to each TCP connection, carrying the address of the client that
+//! connected to them. With `[http] proxy_protocol` set,
+//! [`FluentRouter::start`](crate::FluentRouter::start) reads that header and
+//! reports the client's address as `ConnectInfo<SocketAddr>`, so rate limiting
+//! and the proxy-OIDC `trusted_proxies` check see the real client.
+//!
+//! # Example
+//!
+//! ```toml
+//! [http]
+//! proxy_protocol = "v2"   # or "v1", or "optional" to accept either or none
+//! proxy_protocol_trusted = ["10.0.0.0/16"]   # only read headers from these peers
+//! ```
+
+use {
+    serde::{Deserialize, Serialize},
+    std::fmt,
+};
+
+/// PROXY protocol header expected at the start of each TCP connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyProtocol {
+    /// Every connection starts with a version 1 (text) header.
+    V1,
+    /// Every connection starts with a version 2 (binary) header.
+    V2,
+    /// Connections may start with a version 1 or 2 header, or with none. As
+    /// any client can then send a header, only use it while migrating.
+    Optional,
+}
+
+impl fmt::Display for ProxyProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyProtocol::V1 => write!(f, "v1"),
+            ProxyProtocol::V2 => write!(f, "v2"),
+            ProxyProtocol::Optional => write!(f, "optional"),
+        }
+    }
+}
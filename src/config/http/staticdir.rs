@@ -1,6 +1,6 @@
 use {
-    crate::{Error, Result},
-    serde::Deserialize,
+    crate::{Error, Result, ValidationReport},
+    serde::{Deserialize, Serialize},
 };
 
 /// Configuration for serving static files from a directory.
@@ -78,7 +78,8 @@ use {
 /// client_id = "my-client"
 /// client_secret = "{{ OIDC_CLIENT_SECRET }}"
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct StaticDirConfig {
     /// Path to the directory containing static files to serve.
     pub directory: String,
@@ -111,40 +112,62 @@ impl StaticDirConfig {
         matches!(self.route, StaticDirRoute::Fallback(_))
     }
     /// Validates the static directory configuration (non-empty path, route
-    /// constraints), returning an error on the first problem found.
+    /// constraints), returning an error listing every problem found.
     pub fn validate(&self) -> Result<()> {
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
+
+    /// Checks the static directory configuration, collecting every problem
+    /// with its key path relative to the `[[http.directories]]` entry.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+
         // Validate directory path is not empty
         if self.directory.trim().is_empty() {
-            return Err(Error::invalid_input(
-                "Static directory path is required. Set [[http.directories]] directory = \"./public\" in config.",
-            ));
+            report.error(
+                "directory",
+                Error::invalid_input(
+                    "Static directory path is required. Set [[http.directories]] directory = \"./public\" in config.",
+                ),
+            );
         }
 
         // Validate route path is not empty (for Route variant)
         if let StaticDirRoute::Route(route_path) = &self.route
             && route_path.trim().is_empty()
         {
-            return Err(Error::invalid_input(
-                "Static directory route is required. Set route = \"/static\" or use fallback = true.",
-            ));
+            report.error(
+                "route",
+                Error::invalid_input(
+                    "Static directory route is required. Set route = \"/static\" or use fallback = true.",
+                ),
+            );
         }
 
         if self.is_fallback() && self.protected {
-            return Err(Error::invalid_input(
-                "Fallback directories cannot be protected. Remove protected = true or use route = \"/path\" instead.",
-            ));
+            report.error(
+                "protected",
+                Error::invalid_input(
+                    "Fallback directories cannot be protected. Remove protected = true or use route = \"/path\" instead.",
+                ),
+            );
         }
 
         // Validate cache_max_age is within reasonable bounds
         if let Some(max_age) = self.cache_max_age
             && max_age > MAX_CACHE_AGE_SECONDS
         {
-            return Err(Error::invalid_input(
-                "cache_max_age exceeds 31536000 (1 year). Use values like 86400 (1 day) or 604800 (1 week).",
-            ));
+            report.error(
+                "cache_max_age",
+                Error::invalid_input(
+                    "cache_max_age exceeds 31536000 (1 year). Use values like 86400 (1 day) or 604800 (1 week).",
+                ),
+            );
         }
 
-        Ok(())
+        report
     }
 }
 
@@ -176,7 +199,8 @@ impl StaticDirConfig {
 /// directory = "./dist"
 /// fallback = true
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum StaticDirRoute {
     /// Serve static files at the specified route prefix.
@@ -0,0 +1,239 @@
+//! HTTPS (TLS termination) configuration.
+//!
+//! When `[http.tls]` is present, [`FluentRouter::start`](crate::FluentRouter::start)
+//! serves HTTPS on the configured port instead of plain HTTP, using rustls.
+//!
+//! # Example
+//!
+//! ```toml
+//! [http.tls]
+//! cert_file = "/etc/tls/tls.crt"       # PEM certificate chain, leaf first
+//! key_file = "/etc/tls/tls.key"        # PEM private key (PKCS#8, PKCS#1 or SEC1)
+//! client_ca_file = "/etc/tls/ca.crt"   # optional: require client certificates
+//! min_version = "1.2"                  # or "1.3"
+//! alpn = ["h2", "http/1.1"]
+//! reload_interval = "10s"              # how often to check the files; "0s" disables
+//! ```
+
+use {
+    crate::{Error, ValidationReport},
+    serde::{Deserialize, Serialize},
+    std::{fmt, time::Duration},
+};
+
+/// ALPN protocols the server can speak.
+const SUPPORTED_ALPN: &[&str] = &["h2", "http/1.1"];
+
+/// Oldest TLS protocol version accepted from clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum TlsVersion {
+    /// TLS 1.2 and 1.3 are accepted.
+    #[default]
+    #[serde(rename = "1.2")]
+    Tls12,
+    /// Only TLS 1.3 is accepted.
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsVersion::Tls12 => write!(f, "TLS 1.2"),
+            TlsVersion::Tls13 => write!(f, "TLS 1.3"),
+        }
+    }
+}
+
+/// Configuration for serving HTTPS directly.
+///
+/// The certificate and key are read when the server starts, then checked for
+/// changes every [`reload_interval`](Self::reload_interval). A changed pair is
+/// swapped in for new handshakes; established connections are not affected. A
+/// pair that fails to load (e.g. caught halfway through a rotation) is logged
+/// and the previous certificate stays in use until the next check.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct HttpTlsConfig {
+    /// Path of the PEM-encoded certificate chain, leaf certificate first.
+    pub cert_file: String,
+
+    /// Path of the PEM-encoded private key of the leaf certificate.
+    pub key_file: String,
+
+    /// Path of a PEM bundle of CA certificates used to verify client
+    /// certificates. When set, clients must present a certificate signed by
+    /// one of them (mutual TLS). Unset (the default) does not ask clients for
+    /// a certificate.
+    #[serde(default)]
+    pub client_ca_file: Option<String>,
+
+    /// Oldest TLS version accepted: `"1.2"` (the default) or `"1.3"`.
+    #[serde(default)]
+    pub min_version: TlsVersion,
+
+    /// ALPN protocols offered to clients, in order of preference.
+    /// By default `alpn` is `["h2", "http/1.1"]`.
+    #[serde(default = "HttpTlsConfig::default_alpn")]
+    pub alpn: Vec<String>,
+
+    /// How often the certificate and key files are checked for changes.
+    /// By default `reload_interval` is 10 seconds; `"0s"` disables reloading.
+    #[serde(
+        default = "HttpTlsConfig::default_reload_interval",
+        with = "humantime_serde"
+    )]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub reload_interval: Duration,
+
+    /// Maximum time a client has to complete the TLS handshake.
+    /// By default `handshake_timeout` is 10 seconds.
+    #[serde(
+        default = "HttpTlsConfig::default_handshake_timeout",
+        with = "humantime_serde"
+    )]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(schema_with = "crate::config::schema::duration")
+    )]
+    pub handshake_timeout: Duration,
+}
+
+impl HttpTlsConfig {
+    /// Creates a configuration serving the given certificate chain and key,
+    /// with default settings otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum_conf::config::HttpTlsConfig;
+    ///
+    /// let tls = HttpTlsConfig::new("/etc/tls/tls.crt", "/etc/tls/tls.key");
+    /// assert_eq!(tls.alpn, ["h2", "http/1.1"]);
+    /// ```
+    pub fn new(cert_file: impl Into<String>, key_file: impl Into<String>) -> Self {
+        Self {
+            cert_file: cert_file.into(),
+            key_file: key_file.into(),
+            client_ca_file: None,
+            min_version: TlsVersion::default(),
+            alpn: Self::default_alpn(),
+            reload_interval: Self::default_reload_interval(),
+            handshake_timeout: Self::default_handshake_timeout(),
+        }
+    }
+
+    fn default_alpn() -> Vec<String> {
+        SUPPORTED_ALPN.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn default_reload_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_handshake_timeout() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    /// Checks the TLS configuration, with key paths relative to `[http.tls]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+
+        for (key, path) in [("cert_file", &self.cert_file), ("key_file", &self.key_file)] {
+            if path.trim().is_empty() {
+                report.error(
+                    key,
+                    Error::invalid_input(format!(
+                        "[http.tls] {key} is required. Set it to the path of a PEM file."
+                    )),
+                );
+            }
+        }
+        if self
+            .client_ca_file
+            .as_ref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            report.error(
+                "client_ca_file",
+                Error::invalid_input(
+                    "[http.tls] client_ca_file is empty. Set it to a PEM CA bundle or remove it.",
+                ),
+            );
+        }
+
+        if self.alpn.is_empty() {
+            report.error(
+                "alpn",
+                Error::invalid_input(
+                    "[http.tls] alpn must list at least one protocol: \"h2\" and/or \"http/1.1\".",
+                ),
+            );
+        }
+        for (index, protocol) in self.alpn.iter().enumerate() {
+            if !SUPPORTED_ALPN.contains(&protocol.as_str()) {
+                report.error(
+                    format!("alpn[{index}]"),
+                    Error::invalid_input(format!(
+                        "Unsupported ALPN protocol {protocol:?}; expected \"h2\" or \"http/1.1\"."
+                    )),
+                );
+            }
+        }
+
+        if self.handshake_timeout.is_zero() {
+            report.error(
+                "handshake_timeout",
+                Error::invalid_input("[http.tls] handshake_timeout must be greater than zero."),
+            );
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_versions_deserialize() {
+        let tls: HttpTlsConfig =
+            toml::from_str("cert_file = \"a.pem\"\nkey_file = \"a.key\"\nmin_version = \"1.3\"")
+                .unwrap();
+        assert_eq!(tls.min_version, TlsVersion::Tls13);
+        assert_eq!(tls.alpn, ["h2", "http/1.1"]);
+        assert_eq!(tls.reload_interval, Duration::from_secs(10));
+        assert!(tls.validation_report().is_empty());
+
+        let err = toml::from_str::<HttpTlsConfig>(
+            "cert_file = \"a.pem\"\nkey_file = \"a.key\"\nmin_version = \"1.1\"",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("1.1"), "{err}");
+    }
+
+    #[test]
+    fn invalid_settings_are_reported_by_key() {
+        let mut tls = HttpTlsConfig::new(" ", "a.key");
+        tls.alpn = vec!["h2".into(), "spdy/3".into()];
+        tls.client_ca_file = Some(String::new());
+        tls.handshake_timeout = Duration::ZERO;
+
+        let report = tls.validation_report();
+        let paths: Vec<_> = report.errors().map(|issue| issue.path()).collect();
+        assert_eq!(
+            paths,
+            [
+                "cert_file",
+                "client_ca_file",
+                "alpn[1]",
+                "handshake_timeout"
+            ]
+        );
+    }
+}
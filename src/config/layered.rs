@@ -0,0 +1,374 @@
+//!
+//! Layered configuration files.
+//!
+//! An environment file such as `config/prod.toml` is deep-merged on top of a
+//! parent file before deserialization, so settings shared by every environment
+//! live in one place:
+//!
+//! - If the file has a top-level `extends = "<name>"` key, the parent is
+//!   `config/<name>.toml` (which may itself `extends` another file).
+//! - Otherwise, if `config/base.toml` exists, it is used as the parent.
+//!
+//! A file named `<name>` may be written in any supported format: the first of
+//! `<name>.toml`, `<name>.yaml`, `<name>.yml` and `<name>.json` that exists is
+//! used, so a YAML environment file can extend a TOML base and vice versa.
+//!
+//! Tables are merged key-by-key (the overlay wins on conflicts); every other
+//! value — including arrays and arrays of tables such as `[[http.directories]]` —
+//! is replaced wholesale by the overlay.
+//!
+use {
+    super::{format::ConfigFormat, secrets::SecretsFingerprint},
+    crate::{Error, Result, utils::replace_handlebars_with_env},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+    toml::{Table, Value},
+};
+
+/// Name of the implicit parent file (`config/base.toml`).
+pub(crate) const BASE_CONFIG_NAME: &str = "base";
+
+/// Top-level key naming an explicit parent file.
+pub(crate) const EXTENDS_KEY: &str = "extends";
+
+/// A merged configuration table together with the files it was read from.
+#[derive(Debug)]
+pub(crate) struct LayeredTable {
+    /// The deep-merged TOML table.
+    pub(crate) table: Table,
+    /// Every file that contributed to `table`, the requested file first.
+    pub(crate) files: Vec<PathBuf>,
+    /// The secrets resolved from `table`, set once it is deserialized.
+    pub(crate) secrets: SecretsFingerprint,
+}
+
+/// Loads the file named `name` in `dir` together with its parents and returns
+/// the merged TOML table. Environment variables are substituted in each file
+/// before it is parsed.
+pub(crate) fn load_layered_table(dir: &Path, name: &str) -> Result<LayeredTable> {
+    let path = resolve(dir, name);
+    load_chain(dir, name, path)
+}
+
+/// Loads the file at `path` (in any supported format) together with its
+/// parents, which are looked up in the same directory.
+pub(crate) fn load_layered_file(path: &Path) -> Result<LayeredTable> {
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| {
+            Error::config(format!(
+                "Invalid configuration file name: {}",
+                path.display()
+            ))
+        })?;
+    load_chain(dir, name, path.to_path_buf())
+}
+
+/// Every path a file named `name` in `dir` may have, in lookup order.
+pub(crate) fn candidate_paths(dir: &Path, name: &str) -> impl Iterator<Item = PathBuf> {
+    ConfigFormat::EXTENSIONS
+        .iter()
+        .map(move |(ext, _)| dir.join(format!("{name}.{ext}")))
+}
+
+/// Returns the first existing file named `name` in `dir`, or `{dir}/{name}.toml`
+/// when there is none (so the error names the native format).
+fn resolve(dir: &Path, name: &str) -> PathBuf {
+    candidate_paths(dir, name)
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| dir.join(format!("{name}.toml")))
+}
+
+fn load_chain(dir: &Path, name: &str, path: PathBuf) -> Result<LayeredTable> {
+    let mut chain = Vec::new();
+    let mut files = Vec::new();
+    let table = load_layer(dir, name, path, &mut chain, &mut files)?;
+    Ok(LayeredTable {
+        table,
+        files,
+        secrets: SecretsFingerprint::default(),
+    })
+}
+
+fn load_layer(
+    dir: &Path,
+    name: &str,
+    path: PathBuf,
+    chain: &mut Vec<String>,
+    files: &mut Vec<PathBuf>,
+) -> Result<Table> {
+    if chain.iter().any(|n| n == name) {
+        return Err(Error::config(format!(
+            "Configuration files form an `extends` cycle: {} -> {name}",
+            chain.join(" -> ")
+        )));
+    }
+    chain.push(name.to_string());
+
+    let format = ConfigFormat::from_path(&path)?;
+    let text = fs::read_to_string(&path).map_err(|e| {
+        Error::io(format!(
+            "Cannot read configuration file {}: {e}",
+            path.display()
+        ))
+    })?;
+    let mut table = format.parse_table(&replace_handlebars_with_env(&text)?)?;
+
+    let parent = match table.remove(EXTENDS_KEY) {
+        Some(Value::String(parent)) => Some(parent),
+        Some(other) => {
+            return Err(Error::config(format!(
+                "`{EXTENDS_KEY}` in {} must be a string naming another file in {} \
+                 (e.g. `{EXTENDS_KEY} = \"{BASE_CONFIG_NAME}\"`), got {}",
+                path.display(),
+                dir.display(),
+                other.type_str()
+            )));
+        }
+        // Only the file that was asked for picks up `base.toml` implicitly;
+        // files reached through `extends` are taken as-is.
+        None if chain.len() == 1
+            && name != BASE_CONFIG_NAME
+            && candidate_paths(dir, BASE_CONFIG_NAME).any(|path| path.is_file()) =>
+        {
+            Some(BASE_CONFIG_NAME.to_string())
+        }
+        None => None,
+    };
+    files.push(path);
+
+    match parent {
+        Some(parent) => {
+            let parent_path = resolve(dir, &parent);
+            let mut merged = load_layer(dir, &parent, parent_path, chain, files)?;
+            deep_merge(&mut merged, table);
+            Ok(merged)
+        }
+        None => Ok(table),
+    }
+}
+
+/// Merges `overlay` into `base`: nested tables are merged recursively, any
+/// other value in `overlay` replaces the one in `base`.
+pub(crate) fn deep_merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                deep_merge(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch `config/` directory removed on drop.
+    struct ConfigDir(PathBuf);
+
+    impl ConfigDir {
+        fn new(files: &[(&str, &str)]) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("axum-conf-layered-{}", uuid::Uuid::now_v7()));
+            fs::create_dir_all(&dir).unwrap();
+            for (name, contents) in files {
+                // Names without an extension are TOML files.
+                let file = if name.contains('.') {
+                    (*name).to_string()
+                } else {
+                    format!("{name}.toml")
+                };
+                fs::write(dir.join(file), contents).unwrap();
+            }
+            Self(dir)
+        }
+    }
+
+    impl Drop for ConfigDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn table(s: &str) -> Table {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn deep_merge_merges_tables_and_replaces_arrays() {
+        let mut base = table(
+            r#"
+            [http]
+            bind_port = 3000
+            max_payload_size_bytes = "1KiB"
+            [[http.directories]]
+            directory = "a"
+            route = "/a"
+            [[http.directories]]
+            directory = "b"
+            route = "/b"
+            [logging]
+            format = "json"
+            "#,
+        );
+        let overlay = table(
+            r#"
+            [http]
+            bind_port = 8080
+            [[http.directories]]
+            directory = "c"
+            route = "/c"
+            "#,
+        );
+
+        deep_merge(&mut base, overlay);
+
+        let http = base["http"].as_table().unwrap();
+        assert_eq!(http["bind_port"].as_integer(), Some(8080));
+        assert_eq!(http["max_payload_size_bytes"].as_str(), Some("1KiB"));
+        let dirs = http["directories"].as_array().unwrap();
+        assert_eq!(dirs.len(), 1, "arrays are replaced, not concatenated");
+        assert_eq!(dirs[0]["directory"].as_str(), Some("c"));
+        assert_eq!(base["logging"]["format"].as_str(), Some("json"));
+    }
+
+    #[test]
+    fn deep_merge_replaces_table_with_scalar_and_vice_versa() {
+        let mut base = table("a = { x = 1 }\nb = 2");
+        deep_merge(&mut base, table("a = 3\nb = { y = 4 }"));
+        assert_eq!(base["a"].as_integer(), Some(3));
+        assert_eq!(base["b"]["y"].as_integer(), Some(4));
+    }
+
+    #[test]
+    fn implicit_base_file_is_merged_under_env_file() {
+        let dir = ConfigDir::new(&[
+            (
+                "base",
+                "[http]\nbind_port = 3000\nmax_payload_size_bytes = \"1KiB\"",
+            ),
+            ("dev", "[http]\nbind_port = 4000"),
+        ]);
+        let merged = load_layered_table(&dir.0, "dev").unwrap().table;
+        assert_eq!(merged["http"]["bind_port"].as_integer(), Some(4000));
+        assert_eq!(
+            merged["http"]["max_payload_size_bytes"].as_str(),
+            Some("1KiB")
+        );
+    }
+
+    #[test]
+    fn env_file_without_base_is_used_as_is() {
+        let dir = ConfigDir::new(&[("dev", "[http]\nbind_port = 4000")]);
+        let merged = load_layered_table(&dir.0, "dev").unwrap().table;
+        assert_eq!(merged["http"]["bind_port"].as_integer(), Some(4000));
+    }
+
+    #[test]
+    fn loading_base_itself_does_not_recurse() {
+        let dir = ConfigDir::new(&[("base", "[http]\nbind_port = 3000")]);
+        let merged = load_layered_table(&dir.0, "base").unwrap().table;
+        assert_eq!(merged["http"]["bind_port"].as_integer(), Some(3000));
+    }
+
+    #[test]
+    fn explicit_extends_chain_is_followed_and_key_removed() {
+        let dir = ConfigDir::new(&[
+            (
+                "base",
+                "[http]\nbind_port = 1\nmax_concurrent_requests = 10",
+            ),
+            (
+                "shared",
+                "extends = \"base\"\n[http]\nbind_port = 2\nmax_requests_per_sec = 5",
+            ),
+            ("prod", "extends = \"shared\"\n[http]\nbind_port = 3"),
+        ]);
+        let merged = load_layered_table(&dir.0, "prod").unwrap().table;
+        assert!(!merged.contains_key(EXTENDS_KEY));
+        assert_eq!(merged["http"]["bind_port"].as_integer(), Some(3));
+        assert_eq!(merged["http"]["max_requests_per_sec"].as_integer(), Some(5));
+        assert_eq!(
+            merged["http"]["max_concurrent_requests"].as_integer(),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn extends_cycle_is_reported() {
+        let dir = ConfigDir::new(&[("a", "extends = \"b\""), ("b", "extends = \"a\"")]);
+        let err = load_layered_table(&dir.0, "a").unwrap_err();
+        assert!(err.to_string().contains("a -> b -> a"), "{err}");
+    }
+
+    #[test]
+    fn extends_must_be_a_string() {
+        let dir = ConfigDir::new(&[("dev", "extends = 3")]);
+        let err = load_layered_table(&dir.0, "dev").unwrap_err();
+        assert!(err.to_string().contains("must be a string"), "{err}");
+    }
+
+    #[test]
+    fn missing_parent_is_an_error() {
+        let dir = ConfigDir::new(&[("dev", "extends = \"nope\"")]);
+        assert!(load_layered_table(&dir.0, "dev").is_err());
+    }
+
+    #[test]
+    fn files_in_other_formats_are_layered_by_name() {
+        let dir = ConfigDir::new(&[
+            (
+                "base",
+                "[http]\nbind_port = 3000\nmax_payload_size_bytes = \"1KiB\"",
+            ),
+            (
+                "dev.json",
+                r#"{"extends": "base", "http": {"bind_port": 4000}}"#,
+            ),
+        ]);
+        let layered = load_layered_table(&dir.0, "dev").unwrap();
+        assert_eq!(layered.table["http"]["bind_port"].as_integer(), Some(4000));
+        assert_eq!(
+            layered.table["http"]["max_payload_size_bytes"].as_str(),
+            Some("1KiB")
+        );
+        assert_eq!(
+            layered.files,
+            [dir.0.join("dev.json"), dir.0.join("base.toml")]
+        );
+    }
+
+    #[test]
+    fn toml_file_wins_over_other_formats_with_the_same_name() {
+        let dir = ConfigDir::new(&[
+            ("dev", "[http]\nbind_port = 1"),
+            ("dev.json", r#"{"http": {"bind_port": 2}}"#),
+        ]);
+        let merged = load_layered_table(&dir.0, "dev").unwrap().table;
+        assert_eq!(merged["http"]["bind_port"].as_integer(), Some(1));
+    }
+
+    #[test]
+    fn explicit_file_picks_up_base_from_its_directory() {
+        let dir = ConfigDir::new(&[
+            ("base", "[http]\nmax_payload_size_bytes = \"1KiB\""),
+            ("service.json", r#"{"http": {"bind_port": 4000}}"#),
+        ]);
+        let merged = load_layered_file(&dir.0.join("service.json"))
+            .unwrap()
+            .table;
+        assert_eq!(merged["http"]["bind_port"].as_integer(), Some(4000));
+        assert_eq!(
+            merged["http"]["max_payload_size_bytes"].as_str(),
+            Some("1KiB")
+        );
+    }
+}
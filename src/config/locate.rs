@@ -0,0 +1,127 @@
+//!
+//! Locating the configuration directory.
+//!
+//! [`Config::from_toml_file`](super::Config::from_toml_file) and the functions
+//! built on it look for the environment's file (`{env}.toml`, `{env}.yaml`, ...)
+//! in these directories, using the first one that has it:
+//!
+//! 1. `$AXUM_CONF_DIR` when it is set — and then only there;
+//! 2. otherwise `config/` in the working directory, `config/` next to the
+//!    executable, and `/etc/<executable name>`, in that order.
+//!
+//! When no directory has the file, the error lists every location tried.
+//!
+use {
+    super::layered,
+    crate::{Error, Result},
+    std::path::{Path, PathBuf},
+};
+
+/// Directory holding the environment-specific configuration files, relative to
+/// the working directory or the executable.
+pub(crate) const CONFIG_DIR: &str = "config";
+
+/// Environment variable naming the configuration directory explicitly.
+pub(crate) const CONFIG_DIR_VAR: &str = "AXUM_CONF_DIR";
+
+/// Directory under which the executable's system-wide configuration lives.
+const SYSTEM_CONFIG_ROOT: &str = "/etc";
+
+/// Returns the directories to search, in order. `conf_dir` is the value of
+/// `AXUM_CONF_DIR` and `exe` the path of the running executable.
+pub(crate) fn search_dirs(conf_dir: Option<&str>, exe: Option<&Path>) -> Vec<PathBuf> {
+    if let Some(dir) = conf_dir.filter(|dir| !dir.trim().is_empty()) {
+        return vec![PathBuf::from(dir)];
+    }
+
+    let mut dirs = vec![PathBuf::from(CONFIG_DIR)];
+    if let Some(exe) = exe {
+        if let Some(exe_dir) = exe.parent() {
+            dirs.push(exe_dir.join(CONFIG_DIR));
+        }
+        if let Some(name) = exe.file_stem() {
+            dirs.push(Path::new(SYSTEM_CONFIG_ROOT).join(name));
+        }
+    }
+    dirs.dedup();
+    dirs
+}
+
+/// The search directories for the running process.
+pub(crate) fn process_search_dirs() -> Vec<PathBuf> {
+    let conf_dir = std::env::var(CONFIG_DIR_VAR).ok();
+    let exe = std::env::current_exe().ok();
+    search_dirs(conf_dir.as_deref(), exe.as_deref())
+}
+
+/// Returns the first of `dirs` holding a configuration file named `env`.
+pub(crate) fn find_config_dir(dirs: &[PathBuf], env: &str) -> Result<PathBuf> {
+    if let Some(dir) = dirs
+        .iter()
+        .find(|dir| layered::candidate_paths(dir, env).any(|path| path.is_file()))
+    {
+        return Ok(dir.clone());
+    }
+
+    let tried: Vec<String> = dirs
+        .iter()
+        .flat_map(|dir| layered::candidate_paths(dir, env))
+        .map(|path| format!("\n  - {}", path.display()))
+        .collect();
+    Err(Error::io(format!(
+        "No configuration file for environment `{env}` was found (set {CONFIG_DIR_VAR} to \
+         choose the directory); tried:{}",
+        tried.concat()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conf_dir_variable_replaces_the_search_list() {
+        let dirs = search_dirs(Some("/etc/myapp"), Some(Path::new("/opt/app/bin/server")));
+        assert_eq!(dirs, [PathBuf::from("/etc/myapp")]);
+    }
+
+    #[test]
+    fn default_search_list_is_cwd_then_executable_then_etc() {
+        let dirs = search_dirs(Some(" "), Some(Path::new("/opt/app/bin/server")));
+        assert_eq!(
+            dirs,
+            [
+                PathBuf::from("config"),
+                PathBuf::from("/opt/app/bin/config"),
+                PathBuf::from("/etc/server"),
+            ]
+        );
+        assert_eq!(search_dirs(None, None), [PathBuf::from("config")]);
+    }
+
+    #[test]
+    fn first_directory_with_the_file_wins_and_misses_list_every_path() {
+        let root = std::env::temp_dir().join(format!("axum-conf-locate-{}", uuid::Uuid::now_v7()));
+        let (empty, found) = (root.join("empty"), root.join("found"));
+        std::fs::create_dir_all(&empty).unwrap();
+        std::fs::create_dir_all(&found).unwrap();
+        std::fs::write(found.join("dev.yaml"), "").unwrap();
+
+        let dirs = [empty.clone(), found.clone()];
+        let hit = find_config_dir(&dirs, "dev");
+        let miss = find_config_dir(&dirs, "prod");
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(hit.unwrap(), found);
+        let err = miss.unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Io);
+        let message = err.to_string();
+        for dir in &dirs {
+            for ext in ["toml", "yaml", "yml", "json"] {
+                let path = dir.join(format!("prod.{ext}"));
+                assert!(message.contains(&path.display().to_string()), "{message}");
+            }
+        }
+        assert!(message.contains(CONFIG_DIR_VAR), "{message}");
+    }
+}
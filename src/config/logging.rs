@@ -1,4 +1,9 @@
-use {crate::Result, serde::Deserialize};
+use {
+    crate::{Error, Result, ValidationReport},
+    serde::{Deserialize, Serialize},
+    std::sync::OnceLock,
+    tracing_subscriber::{EnvFilter, Layer, Registry, layer::Layered, reload},
+};
 
 #[cfg(feature = "opentelemetry")]
 use crate::config::opentelemetry::OpenTelemetryConfig;
@@ -6,7 +11,8 @@ use crate::config::opentelemetry::OpenTelemetryConfig;
 ///
 /// Configuration for logging and tracing.
 ///
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct LoggingConfig {
     /// Format for log output.
     /// The default format is `default`, which is "full" human-readable format.
@@ -14,6 +20,13 @@ pub struct LoggingConfig {
     #[serde(default)]
     pub format: LogFormat,
 
+    /// Log filter directives in `RUST_LOG` syntax (e.g. `"info,my_crate=debug"`).
+    /// When set, takes precedence over the `RUST_LOG` environment variable.
+    /// This value is reloadable: a [`ConfigWatcher`](crate::ConfigWatcher) applies
+    /// changes to the running subscriber without a restart.
+    #[serde(default)]
+    pub filter: Option<String>,
+
     /// OpenTelemetry configuration (optional).
     /// When configured, enables distributed tracing with OTLP export.
     #[cfg(feature = "opentelemetry")]
@@ -22,10 +35,64 @@ pub struct LoggingConfig {
 }
 
 impl LoggingConfig {
-    /// Validates the logging configuration. Currently always succeeds; present
-    /// for symmetry with the other config sections and future validation.
+    /// Validates the logging configuration: the `filter` directives, if any,
+    /// must parse.
     pub fn validate(&self) -> Result<()> {
-        Ok(())
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
+
+    /// Checks the logging configuration, collecting every problem with its
+    /// key path relative to `[logging]`.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        if let Some(filter) = &self.filter
+            && let Err(e) = EnvFilter::try_new(filter)
+        {
+            report.error(
+                "filter",
+                Error::config(format!("Invalid logging filter {filter:?}: {e}")),
+            );
+        }
+        report
+    }
+
+    /// Builds the filter for the tracing subscriber: `filter` when set,
+    /// otherwise `RUST_LOG`.
+    pub(crate) fn env_filter(&self) -> EnvFilter {
+        self.filter
+            .as_deref()
+            .and_then(|filter| EnvFilter::try_new(filter).ok())
+            .unwrap_or_else(EnvFilter::from_default_env)
+    }
+}
+
+/// The subscriber below the filter layer: the registry with the fmt layer.
+pub(crate) type FmtSubscriber = Layered<Box<dyn Layer<Registry> + Send + Sync>, Registry>;
+
+/// Handle to the filter of the subscriber installed by
+/// [`Config::setup_tracing`](crate::Config::setup_tracing), used to apply a
+/// reloaded `logging.filter`.
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, FmtSubscriber>> = OnceLock::new();
+
+/// Remembers the filter handle of the global subscriber once it is installed.
+pub(crate) fn set_filter_handle(handle: reload::Handle<EnvFilter, FmtSubscriber>) {
+    let _ = FILTER_HANDLE.set(handle);
+}
+
+/// Replaces the filter of the global subscriber with the one described by
+/// `logging`. Returns `false` when tracing was not set up by this crate.
+pub(crate) fn reload_filter(logging: &LoggingConfig) -> bool {
+    let Some(handle) = FILTER_HANDLE.get() else {
+        return false;
+    };
+    match handle.reload(logging.env_filter()) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to apply the reloaded logging filter");
+            false
+        }
     }
 }
 
@@ -43,7 +110,8 @@ impl LoggingConfig {
 /// [logging]
 /// format = "json"  # Recommended for production
 /// ```
-#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum LogFormat {
     /// JSON format - structured logs ideal for log aggregation systems.
@@ -120,4 +188,18 @@ mod tests {
     fn validate_always_succeeds() {
         assert!(parse("[logging]\nformat = \"json\"").validate().is_ok());
     }
+
+    #[test]
+    fn filter_is_optional_and_validated() {
+        assert!(parse("").filter.is_none());
+
+        let config = parse("[logging]\nfilter = \"info,axum_conf=debug\"");
+        assert_eq!(config.filter.as_deref(), Some("info,axum_conf=debug"));
+        assert!(config.validate().is_ok());
+
+        let err = parse("[logging]\nfilter = \"info,[\"")
+            .validate()
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid logging filter"), "{err}");
+    }
 }
@@ -2,7 +2,9 @@
 //! Configuration structures and utilities for wiring up the application or service.
 //!
 //! A configuration can be created in many ways:
-//! - From an environment-specific TOML file via `Config::from_rust_env` or `Config::from_toml_file`
+//! - From an environment-specific TOML file via `Config::from_rust_env` or `Config::from_toml_file`,
+//!   optionally layered on top of a shared `config/base.toml` and overridden per key by
+//!   `AXUM_CONF__SECTION__KEY` environment variables
 //! - From a TOML string via `Config::from_toml`
 //! - Constructed programmatically via the builder methods on `Config`
 //!
@@ -59,29 +61,57 @@
 //! ```
 //!
 //!
+#[cfg(feature = "encryption")]
+mod encryption;
+mod env_overrides;
+mod format;
 mod http;
+mod layered;
+mod locate;
 mod logging;
+#[cfg(feature = "json-schema")]
+mod schema;
+pub(crate) mod secrets;
+mod unknown_keys;
+mod validation;
+mod watch;
 
 #[cfg(feature = "postgres")]
 mod database;
 #[cfg(feature = "postgres")]
 pub use database::DatabaseConfig;
 
+#[cfg(feature = "encryption")]
+pub use encryption::{ENCRYPTION_KEY_FILE_VAR, ENCRYPTION_KEY_VAR, EncryptionKey};
 #[cfg(feature = "keycloak")]
 pub use http::HttpOidcConfig;
+pub(crate) use http::parse_socket_mode;
 pub use http::{
     AllRoles, AnyRole, ApplicationRole, ApplicationRoles, AuthMethod, AuthenticatedIdentity,
-    CorsHeader, CorsMethod, HttpConfig, HttpCorsConfig, HttpDeduplicationConfig, HttpMiddleware,
-    HttpMiddlewareConfig, HttpProxyOidcConfig, HttpXFrameConfig, MetricBucketsConfig, MetricMatch,
+    BindTarget, CorsHeader, CorsMethod, HttpAdminConfig, HttpConfig, HttpConnectionConfig,
+    HttpCorsConfig, HttpDeduplicationConfig, HttpMiddleware, HttpMiddlewareConfig,
+    HttpProxyOidcConfig, HttpXFrameConfig, MetricBucketsConfig, MetricMatch, ProxyProtocol,
     SharedIdentity, StaticDirConfig, StaticDirRoute, WithRole, XFrameOptions,
 };
 #[cfg(feature = "basic-auth")]
 pub use http::{BasicAuthApiKey, BasicAuthMode, BasicAuthUser, HttpBasicAuthConfig};
+#[cfg(feature = "tls")]
+pub use http::{
+    CertAttribute, ClientCertRoleMapping, HttpClientCertAuthConfig, HttpTlsConfig, TlsVersion,
+};
 #[cfg(feature = "circuit-breaker")]
 pub use http::{CircuitBreakerConfig, CircuitBreakerTargetConfig};
 #[cfg(feature = "session")]
 pub use http::{SameSiteConfig, SessionStoreConfig};
 pub use logging::{LogFormat, LoggingConfig};
+pub use secrets::{
+    DirectorySecretProvider, EnvSecretProvider, FileSecretProvider, SecretProvider,
+    register_secret_provider,
+};
+pub use validation::{Severity, ValidateAppConfig, ValidationIssue, ValidationReport};
+pub use watch::{ConfigReload, ConfigWatcher};
+
+use unknown_keys::UnknownKey;
 
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry;
@@ -97,17 +127,24 @@ pub use byte_unit::Byte;
 
 use {
     crate::{Error, Result, utils::replace_handlebars_with_env},
-    serde::{Deserialize, de::DeserializeOwned},
-    std::{env, fs, str::FromStr, time::Duration},
-    tracing_subscriber::{EnvFilter, Layer, Registry, layer::Layered},
+    serde::{Deserialize, Serialize, de::DeserializeOwned},
+    std::{env, path::Path, str::FromStr, time::Duration},
+    tracing_subscriber::{EnvFilter, Layer, Registry, layer::Layered, reload},
 };
 
 /// The base subscriber type with fmt layer and env filter pre-configured.
 ///
 /// This type is used as the input to [`Config::setup_tracing_with`], allowing
-/// you to add additional layers before initialization.
-pub type TracingBase =
-    Layered<EnvFilter, Layered<Box<dyn Layer<Registry> + Send + Sync>, Registry>>;
+/// you to add additional layers before initialization. The filter is wrapped in
+/// a [`reload::Layer`] so a reloaded `logging.filter` can be applied at runtime.
+pub type TracingBase = Layered<
+    reload::Layer<EnvFilter, Layered<Box<dyn Layer<Registry> + Send + Sync>, Registry>>,
+    Layered<Box<dyn Layer<Registry> + Send + Sync>, Registry>,
+>;
+
+/// Top-level sections owned by this crate; every other root key belongs to the
+/// application type `T`.
+pub(crate) const LIBRARY_SECTIONS: &[&str] = &["http", "database", "logging", "circuit_breaker"];
 
 /// Root configuration structure for axum-conf applications.
 ///
@@ -118,6 +155,7 @@ pub type TracingBase =
 ///
 /// - Use `Config<()>` (the default) for applications without custom config
 /// - Use `Config<YourType>` where `YourType: DeserializeOwned + Clone + Default`
+///   and implements [`ValidateAppConfig`] (an empty `impl` accepts any value)
 ///
 /// # Example with Custom Config
 ///
@@ -146,8 +184,9 @@ pub type TracingBase =
 /// let config: Config<AppSettings> = toml.parse().unwrap();
 /// println!("Cache TTL: {}", config.app.cache_ttl_secs);
 /// ```
-#[derive(Debug, Clone, Deserialize)]
-#[serde(bound = "T: DeserializeOwned")]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(bound(deserialize = "T: DeserializeOwned", serialize = "T: Serialize"))]
 pub struct Config<T = ()>
 where
     T: Clone + Default,
@@ -179,12 +218,41 @@ where
     /// Whether the service is running in a production environment, resolved from
     /// `RUST_ENV` at load time (empty / `prod` / `production` / `release` ⇒ `true`).
     ///
-    /// This is **not** part of the TOML surface (`#[serde(skip)]`); it is the single
+    /// This is **not** read from TOML (`#[serde(skip_deserializing)]`), but it is
+    /// included when the effective configuration is serialized. It is the single
     /// source of truth for fail-safe production behavior (restrictive CORS defaults,
     /// OIDC audience fail-closed, proxy-header fail-closed) so those code paths never
     /// read `std::env` directly. Defaults to `true` (fail-safe) when unknown.
-    #[serde(skip, default = "default_is_production")]
+    #[serde(skip_deserializing, default = "default_is_production")]
     pub(crate) is_production: bool,
+
+    /// Keys of the file this configuration was parsed from that no section
+    /// consumes (see [`Config::with_strict_keys`]).
+    #[serde(skip)]
+    pub(crate) unknown_keys: Vec<UnknownKey>,
+
+    /// Whether unknown keys fail validation; `None` reports them as warnings.
+    #[serde(skip)]
+    pub(crate) strict_keys: Option<bool>,
+
+    /// Renders the configuration with the application settings for the
+    /// effective-config route, once enabled by [`Config::render_app`].
+    #[serde(skip)]
+    pub(crate) app_renderer: Option<fn(&Self) -> Result<RedactedConfig>>,
+}
+
+/// The effective configuration, masked and rendered in both formats served by
+/// the effective-config route.
+#[derive(Debug, Clone)]
+pub(crate) struct RedactedConfig {
+    pub(crate) json: String,
+    pub(crate) toml: String,
+}
+
+/// The process environment as UTF-8 pairs; entries that are not valid Unicode
+/// cannot name a config key and are skipped.
+fn env_vars() -> impl Iterator<Item = (String, String)> {
+    env::vars_os().filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
 }
 
 /// Default for [`Config::is_production`]: assume production (fail-safe) when the
@@ -210,7 +278,7 @@ where
     /// This will attempt to load configuration from the file based on the RUST_ENV
     /// environment variable falling back to a default configuration if the environment
     /// variable is not set. Configuration files should be located in the "config/"
-    /// directory of your project.
+    /// directory of your project (or the directory named by `AXUM_CONF_DIR`).
     ///
     fn default() -> Self {
         match Self::from_rust_env() {
@@ -224,6 +292,9 @@ where
                 circuit_breaker: CircuitBreakerConfig::default(),
                 app: T::default(),
                 is_production: default_is_production(),
+                unknown_keys: Vec::new(),
+                strict_keys: None,
+                app_renderer: None,
             },
         }
     }
@@ -266,17 +337,252 @@ where
     /// The configuration file is expected to be located at "config/{env}.toml"
     /// where {env} is the provided environment name (e.g., "dev", "prod").
     ///
+    /// The directory is `$AXUM_CONF_DIR` when set. Otherwise the first of
+    /// `config/` in the working directory, `config/` next to the executable and
+    /// `/etc/<executable name>` that holds the file is used; if none does, the
+    /// error lists every location tried. Use [`from_path`](Config::from_path)
+    /// to name the directory or file in code.
+    ///
+    /// The file is layered on top of a parent before deserialization: the file
+    /// named by a top-level `extends = "<name>"` key, or else `config/base.toml`
+    /// when it exists. Tables are deep-merged key-by-key and every other value
+    /// (arrays included) is replaced by the environment file, so shared settings
+    /// only need to be written once.
+    ///
+    /// Finally, any `AXUM_CONF__<SECTION>__<KEY>` environment variable overrides
+    /// the matching key (e.g. `AXUM_CONF__HTTP__BIND_PORT=8080` sets
+    /// `http.bind_port`), including fields of the application type `T`.
+    ///
+    /// Despite the name, any file may also be written in YAML or JSON:
+    /// `config/{env}.yaml`, `config/{env}.yml` and `config/{env}.json` are tried,
+    /// in that order, when `config/{env}.toml` does not exist (see
+    /// [`from_file`](Config::from_file)).
+    ///
     pub fn from_toml_file(env: impl AsRef<str>) -> Result<Config<T>> {
         let env = env.as_ref();
-        let path = format!("config/{env}.toml");
-        let text = fs::read_to_string(path)?;
-        let mut config = Self::from_toml(&text)?;
+        let dir = locate::find_config_dir(&locate::process_search_dirs(), env)?;
+        Self::from_toml_file_in(&dir, env, env_vars())
+    }
+
+    ///
+    /// Loads the configuration from an explicit location instead of searching
+    /// for the `config/` directory.
+    ///
+    /// - If `path` is a directory, the environment file named by `RUST_ENV` is
+    ///   loaded from it exactly as [`from_toml_file`](Config::from_toml_file)
+    ///   would (e.g. `{path}/prod.toml` with `{path}/base.toml` under it).
+    /// - Otherwise `path` is the configuration file itself, loaded with
+    ///   [`from_file`](Config::from_file).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use axum_conf::Config;
+    ///
+    /// # fn example() -> axum_conf::Result<()> {
+    /// // e.g. a ConfigMap mounted at /etc/myapp holding prod.toml
+    /// let config = Config::<()>::from_path("/etc/myapp")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Config<T>> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            let env = env::var("RUST_ENV")?;
+            let dir = locate::find_config_dir(&[path.to_path_buf()], &env)?;
+            Self::from_toml_file_in(&dir, env, env_vars())
+        } else {
+            Self::from_file(path)
+        }
+    }
+
+    /// Loads the configuration like [`from_toml_file`](Config::from_toml_file)
+    /// and keeps watching the files for changes.
+    ///
+    /// Reloaded configurations are published on the watcher's
+    /// [`subscribe`](ConfigWatcher::subscribe) channel; pass it to
+    /// [`FluentRouter::with_config_updates`](crate::FluentRouter::with_config_updates)
+    /// to apply the reloadable settings to a running router.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial load or validation fails, or if no Tokio
+    /// runtime is running.
+    pub fn watch_toml_file(env: impl AsRef<str>) -> Result<ConfigWatcher<T>>
+    where
+        T: ValidateAppConfig + Send + Sync + 'static,
+    {
+        let env = env.as_ref();
+        let dir = locate::find_config_dir(&locate::process_search_dirs(), env)?;
+        ConfigWatcher::spawn(&dir, env)
+    }
+
+    /// Same as [`watch_toml_file`](Config::watch_toml_file), with the environment
+    /// name taken from `RUST_ENV`.
+    pub fn watch_rust_env() -> Result<ConfigWatcher<T>>
+    where
+        T: ValidateAppConfig + Send + Sync + 'static,
+    {
+        Self::watch_toml_file(env::var("RUST_ENV")?)
+    }
+
+    /// Same as [`from_toml_file`](Config::from_toml_file), resolving files in `dir`
+    /// and taking override variables from `vars` instead of the process environment.
+    pub(crate) fn from_toml_file_in<I>(
+        dir: &Path,
+        env: impl AsRef<str>,
+        vars: I,
+    ) -> Result<Config<T>>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        Self::load_layered(dir, env.as_ref(), vars).map(|(config, _)| config)
+    }
+
+    /// Loads `{dir}/{env}.toml` with its parents and environment overrides, and
+    /// also returns the merged table and files it was built from (used by
+    /// [`ConfigWatcher`] to detect and describe changes).
+    pub(crate) fn load_layered<I>(
+        dir: &Path,
+        env: &str,
+        vars: I,
+    ) -> Result<(Config<T>, layered::LayeredTable)>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let layered = layered::load_layered_table(dir, env)?;
+        Self::from_layered(layered, env, vars)
+    }
+
+    /// Deserializes a merged table after applying environment overrides; `env`
+    /// resolves the production flag.
+    fn from_layered<I>(
+        mut layered: layered::LayeredTable,
+        env: &str,
+        vars: I,
+    ) -> Result<(Config<T>, layered::LayeredTable)>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let overrides = env_overrides::apply_env_overrides(&mut layered.table, vars)?;
+        let (config, fingerprint) =
+            secrets::fingerprinted(|| toml::Value::Table(layered.table.clone()).try_into());
+        layered.secrets = fingerprint;
+        let mut config: Config<T> = config.map_err(|e: toml::de::Error| {
+            if overrides.is_empty() {
+                Error::from(e)
+            } else {
+                Error::config(format!(
+                    "Invalid configuration after applying environment overrides ({}): {e}",
+                    overrides.join(", ")
+                ))
+            }
+        })?;
         // Resolve the deployment environment once, here, so no downstream code
         // path needs to read `RUST_ENV` from the process environment.
         config.is_production = is_production_env(env);
+        // Only an explicitly named production environment rejects unknown keys;
+        // an unset `RUST_ENV` is production for fail-safe defaults, not for this.
+        config.strict_keys = Some(!env.trim().is_empty() && config.is_production);
+        config.unknown_keys = unknown_keys::find_unknown_keys(&layered.table, &config);
+        Ok((config, layered))
+    }
+
+    ///
+    /// Loads the configuration file at `path`, detecting its format from the
+    /// extension: `.toml`, `.yaml` / `.yml` (with the `yaml` feature) or `.json`.
+    ///
+    /// The file is processed like [`from_toml_file`](Config::from_toml_file):
+    /// `{{ VAR }}` placeholders are substituted, the file is layered over its
+    /// `extends` parent or a `base` file in the same directory, and
+    /// `AXUM_CONF__*` environment overrides are applied. Since the file name
+    /// need not be an environment name, the production flag is resolved from
+    /// `RUST_ENV` (unset means production).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use axum_conf::Config;
+    ///
+    /// # fn example() -> axum_conf::Result<()> {
+    /// let config = Config::<()>::from_file("/etc/myapp/config.yaml")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Config<T>> {
+        Self::from_file_with(path.as_ref(), env_vars())
+    }
+
+    /// Same as [`from_file`](Config::from_file), taking `RUST_ENV` and override
+    /// variables from `vars` instead of the process environment.
+    pub(crate) fn from_file_with<I>(path: &Path, vars: I) -> Result<Config<T>>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let vars: Vec<_> = vars.into_iter().collect();
+        let rust_env = vars
+            .iter()
+            .find_map(|(key, value)| (key == "RUST_ENV").then_some(value.as_str()))
+            .unwrap_or_default()
+            .to_string();
+        let layered = layered::load_layered_file(path)?;
+        Self::from_layered(layered, &rust_env, vars).map(|(config, _)| config)
+    }
+
+    /// Parses a string in the given format, without layering or overrides.
+    fn from_format(format: format::ConfigFormat, s: &str) -> Result<Config<T>> {
+        let table = format.parse_table(&replace_handlebars_with_env(s)?)?;
+        let mut config: Config<T> =
+            secrets::with_resolution(|| toml::Value::Table(table.clone()).try_into())?;
+        config.unknown_keys = unknown_keys::find_unknown_keys(&table, &config);
         Ok(config)
     }
 
+    ///
+    /// Parses a configuration string in YAML format into a Config struct.
+    ///
+    /// `{{ VAR }}` placeholders are substituted first, exactly as for
+    /// [`from_toml`](Config::from_toml), and the production flag likewise
+    /// defaults to `true`. Null values are treated as absent keys.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum_conf::Config;
+    ///
+    /// let config = Config::<()>::from_yaml(
+    ///     "http:\n  bind_port: 8080\n  max_payload_size_bytes: 1KiB\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(config.http.bind_port, 8080);
+    /// ```
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml_str: &str) -> Result<Config<T>> {
+        Self::from_format(format::ConfigFormat::Yaml, yaml_str)
+    }
+
+    ///
+    /// Parses a configuration string in JSON format into a Config struct.
+    ///
+    /// `{{ VAR }}` placeholders are substituted first, exactly as for
+    /// [`from_toml`](Config::from_toml), and the production flag likewise
+    /// defaults to `true`. Null values are treated as absent keys.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum_conf::Config;
+    ///
+    /// let config = Config::<()>::from_json(
+    ///     r#"{"http": {"bind_port": 8080, "max_payload_size_bytes": "1KiB"}}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(config.http.bind_port, 8080);
+    /// ```
+    pub fn from_json(json_str: &str) -> Result<Config<T>> {
+        Self::from_format(format::ConfigFormat::Json, json_str)
+    }
+
     ///
     /// Parses a configuration string in TOML format into a Config struct.
     ///
@@ -290,7 +596,8 @@ where
     /// [`from_rust_env`]: Config::from_rust_env
     /// [`with_production`]: Config::with_production
     pub fn from_toml(toml_str: &str) -> Result<Config<T>> {
-        replace_handlebars_with_env(toml_str).parse()
+        // `FromStr` substitutes the placeholders, exactly once.
+        toml_str.parse()
     }
 
     /// Overrides whether the service is treated as running in production.
@@ -304,6 +611,35 @@ where
         self
     }
 
+    /// Sets whether configuration keys that no section consumes (typically
+    /// misspellings such as `max_request_per_sec`) fail validation.
+    ///
+    /// Unknown keys are always reported by [`Config::validation_report`], with
+    /// the closest known field name. In strict mode they are errors, otherwise
+    /// warnings. Strict mode is on by default when the file is loaded with
+    /// `RUST_ENV` set to `prod`, `production` or `release`, and off otherwise
+    /// (including when `RUST_ENV` is unset). Sections of disabled
+    /// crate features, such as `[database]` without `postgres`, are only ever
+    /// warnings.
+    #[must_use]
+    pub fn with_strict_keys(mut self, strict: bool) -> Self {
+        self.strict_keys = Some(strict);
+        self
+    }
+
+    /// Includes the application settings `T` in the effective configuration
+    /// served on `http.debug_config_route`, masked like
+    /// [`Config::to_redacted_toml`]. Without it the route only shows the
+    /// library sections, since the router does not keep `T`.
+    #[must_use]
+    pub fn render_app(mut self) -> Self
+    where
+        T: Serialize,
+    {
+        self.app_renderer = Some(Self::to_redacted);
+        self
+    }
+
     /// Sets the HTTP server bind address of the HttpConfig.
     #[must_use]
     pub fn with_bind_addr<S: AsRef<str>>(mut self, addr: S) -> Self {
@@ -388,6 +724,16 @@ where
         self
     }
 
+    /// Sets the route serving the redacted effective configuration
+    /// (`http.debug_config_route`) and the role or group its callers must have
+    /// (`http.debug_config_role`). An authentication method must be configured.
+    #[must_use]
+    pub fn with_debug_config_route(mut self, route: &str, role: &str) -> Self {
+        self.http.debug_config_route = Some(route.into());
+        self.http.debug_config_role = Some(role.into());
+        self
+    }
+
     /// Adds a per-metric Prometheus histogram bucket override (exact-name match).
     ///
     /// Turns a metric recorded via the global `metrics` facade into a true
@@ -565,49 +911,53 @@ where
     /// Most configuration values are either optional or have sensible defaults.
     /// Some are required and since and here we ensure that those required values
     /// are set.
-    pub fn validate(&self) -> Result<()> {
+    ///
+    /// Every section is checked, including the application settings through
+    /// [`ValidateAppConfig`]: warnings are logged and the returned error lists
+    /// all problems with their key paths (see [`Self::validation_report`]).
+    pub fn validate(&self) -> Result<()>
+    where
+        T: ValidateAppConfig,
+    {
+        let report = self.validation_report();
+        report.log_warnings();
+        report.into_result()
+    }
+
+    /// Checks every section of the configuration, collecting all errors and
+    /// warnings with their TOML key paths (e.g. `http.directories[1].cache_max_age`)
+    /// instead of stopping at the first problem. Issues reported by the
+    /// application type's [`ValidateAppConfig`] implementation come last.
+    pub fn validation_report(&self) -> ValidationReport
+    where
+        T: ValidateAppConfig,
+    {
+        let mut report = ValidationReport::new();
+        let strict = self.strict_keys.unwrap_or(false);
+        for key in &self.unknown_keys {
+            if strict && !key.is_feature_gated() {
+                report.error(key.path.clone(), Error::config(key.message()));
+            } else {
+                report.warning(key.path.clone(), key.message());
+            }
+        }
         #[cfg(feature = "postgres")]
-        self.database.validate()?;
-        self.http.validate()?;
-        self.logging.validate()?;
-        Ok(())
+        report.merge("database", self.database.validation_report());
+        report.merge("http", self.http.validation_report());
+        report.merge("logging", self.logging.validation_report());
+        report.merge("", self.app.validation_report());
+        report
     }
 
     ///
     /// Sets up the tracing subscriber for logging based on the LoggingConfig.
+    /// Events are filtered by `logging.filter` when set, otherwise by `RUST_LOG`.
     ///
     /// NOTE: This should be called early during startup to ensure logging is configured
     ///       before any log messages are emitted.
     ///
     pub fn setup_tracing(&self) {
-        use tracing_subscriber::{EnvFilter, prelude::*};
-        let env_filter = EnvFilter::from_default_env();
-        match self.logging.format {
-            LogFormat::Json => {
-                let _ = tracing_subscriber::registry()
-                    .with(tracing_subscriber::fmt::layer().json())
-                    .with(env_filter)
-                    .try_init();
-            }
-            LogFormat::Default => {
-                let _ = tracing_subscriber::registry()
-                    .with(tracing_subscriber::fmt::layer())
-                    .with(env_filter)
-                    .try_init();
-            }
-            LogFormat::Compact => {
-                let _ = tracing_subscriber::registry()
-                    .with(tracing_subscriber::fmt::layer().compact())
-                    .with(env_filter)
-                    .try_init();
-            }
-            LogFormat::Pretty => {
-                let _ = tracing_subscriber::registry()
-                    .with(tracing_subscriber::fmt::layer().pretty())
-                    .with(env_filter)
-                    .try_init();
-            }
-        }
+        self.setup_tracing_with(|subscriber| subscriber);
     }
 
     /// Sets up tracing with additional customization via a callback.
@@ -652,11 +1002,12 @@ where
             LogFormat::Pretty => Box::new(tracing_subscriber::fmt::layer().pretty()),
         };
 
-        let base = tracing_subscriber::registry()
-            .with(fmt_layer)
-            .with(EnvFilter::from_default_env());
+        let (filter, handle) = reload::Layer::new(self.logging.env_filter());
+        let base = tracing_subscriber::registry().with(fmt_layer).with(filter);
 
-        let _ = customize(base).try_init();
+        if customize(base).try_init().is_ok() {
+            logging::set_filter_handle(handle);
+        }
     }
 
     ///
@@ -683,6 +1034,113 @@ where
     }
 }
 
+impl<T> Config<T>
+where
+    T: Serialize + Clone + Default,
+{
+    /// Renders the effective configuration as TOML.
+    ///
+    /// The output reflects what the service actually runs with: defaults filled
+    /// in, placeholders and `AXUM_CONF__*` overrides applied, and the resolved
+    /// `is_production` flag. Every [`Sensitive`](crate::Sensitive) value is
+    /// written as `"****"` and passwords embedded in connection URLs are masked,
+    /// so the result is safe to log or serve from an admin endpoint (see
+    /// `http.debug_config_route`). Fields of the application type `T` are
+    /// included; wrap its secrets in `Sensitive` to have them masked too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T` cannot be represented as TOML.
+    pub fn to_redacted_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| {
+            Error::internal(format!("Failed to render the configuration as TOML: {e}"))
+        })
+    }
+
+    /// Renders the effective configuration as pretty-printed JSON, masked like
+    /// [`to_redacted_toml`](Config::to_redacted_toml).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T` cannot be represented as JSON.
+    pub fn to_redacted_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            Error::internal(format!("Failed to render the configuration as JSON: {e}"))
+        })
+    }
+
+    /// Renders the configuration in both formats served by the
+    /// effective-config route.
+    pub(crate) fn to_redacted(&self) -> Result<RedactedConfig> {
+        Ok(RedactedConfig {
+            json: self.to_redacted_json()?,
+            toml: self.to_redacted_toml()?,
+        })
+    }
+}
+
+impl<T> Config<T>
+where
+    T: Clone + Default,
+{
+    /// Renders the configuration served by the effective-config route: with the
+    /// application settings once enabled by [`Config::render_app`], otherwise
+    /// only the library sections.
+    pub(crate) fn render_effective(&self) -> Result<RedactedConfig> {
+        match self.app_renderer {
+            Some(render) => render(self),
+            None => self.library_sections().to_redacted(),
+        }
+    }
+
+    /// Copies the library sections into a configuration without application
+    /// settings.
+    fn library_sections(&self) -> Config<()> {
+        Config {
+            http: self.http.clone(),
+            #[cfg(feature = "postgres")]
+            database: self.database.clone(),
+            logging: self.logging.clone(),
+            #[cfg(feature = "circuit-breaker")]
+            circuit_breaker: self.circuit_breaker.clone(),
+            app: (),
+            is_production: self.is_production,
+            unknown_keys: Vec::new(),
+            strict_keys: None,
+            app_renderer: None,
+        }
+    }
+}
+
+#[cfg(feature = "json-schema")]
+impl<T> Config<T>
+where
+    T: schemars::JsonSchema + Clone + Default,
+{
+    /// Returns a JSON Schema (draft 2020-12) describing the configuration file
+    /// format, for schema-aware editors and for linting `config/*.toml` in CI
+    /// (`json-schema` feature).
+    ///
+    /// The schema covers every library section enabled by the crate features
+    /// (`[http]` with its auth, CORS, deduplication and static-directory tables,
+    /// `[database]`, `[logging]`, `[circuit_breaker]`) and the fields of the
+    /// application type `T`, which must derive `schemars::JsonSchema` (schemars
+    /// 1.x). Doc comments become `description`s and serde defaults become
+    /// `default`s; durations and byte sizes are described in their TOML form
+    /// (`"30s"`, `"32KiB"`).
+    ///
+    /// ```rust
+    /// use axum_conf::Config;
+    ///
+    /// let schema = Config::<()>::json_schema();
+    /// assert!(schema["properties"]["http"].is_object());
+    /// ```
+    #[must_use]
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(Self).into()
+    }
+}
+
 ///
 /// Parses a configuration string with references to environment variables
 /// into a Config struct by substituting the environment variables and then
@@ -694,8 +1152,13 @@ where
 {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
-        let config_file = replace_handlebars_with_env(s);
-        let config = toml::from_str::<Config<T>>(&config_file)?;
+        let config_file = replace_handlebars_with_env(s)?;
+        let mut config = secrets::with_resolution(|| toml::from_str::<Config<T>>(&config_file))?;
+        // The generic table cannot hold every value the typed structs accept
+        // (e.g. integers beyond `i64`); such files are not checked for unknown keys.
+        if let Ok(table) = toml::from_str::<toml::Table>(&config_file) {
+            config.unknown_keys = unknown_keys::find_unknown_keys(&table, &config);
+        }
         Ok(config)
     }
 }
@@ -709,7 +1172,7 @@ mod tests {
     #[test]
     fn test_replace_handlebars_with_env_no_variables() {
         let input = "This is a plain string with no variables";
-        let output = replace_handlebars_with_env(input);
+        let output = replace_handlebars_with_env(input).unwrap();
         assert_eq!(output, input);
     }
 
@@ -720,7 +1183,7 @@ mod tests {
             env::set_var("ANOTHER_VAR", "another_value");
         }
         let input = "Database URL: {{ TEST_VAR }}, Host: {{ ANOTHER_VAR }}";
-        let output = replace_handlebars_with_env(input);
+        let output = replace_handlebars_with_env(input).unwrap();
         assert_eq!(output, "Database URL: test_value, Host: another_value");
 
         unsafe {
@@ -736,7 +1199,7 @@ mod tests {
         }
 
         let input = "Value: {{ NONEXISTENT_VAR }}";
-        let output = replace_handlebars_with_env(input);
+        let output = replace_handlebars_with_env(input).unwrap();
         assert_eq!(output, "Value: ");
     }
 
@@ -747,7 +1210,7 @@ mod tests {
         }
 
         let input = "{{SPACED_VAR}} {{ SPACED_VAR }} {{  SPACED_VAR  }}";
-        let output = replace_handlebars_with_env(input);
+        let output = replace_handlebars_with_env(input).unwrap();
         assert_eq!(output, "value value value");
 
         unsafe {
@@ -762,7 +1225,7 @@ mod tests {
         }
 
         let input = "{{ REPEATED_VAR }} and {{ REPEATED_VAR }} again";
-        let output = replace_handlebars_with_env(input);
+        let output = replace_handlebars_with_env(input).unwrap();
         assert_eq!(output, "repeated and repeated again");
 
         unsafe {
@@ -770,6 +1233,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_toml_substitutes_placeholders_once() {
+        unsafe { env::set_var("AXUM_CONF_TEST_NESTED_PLACEHOLDER", "{{ HOME }}") };
+        let config = Config::<()>::from_toml(
+            r#"
+            [http]
+            bind_addr = "{{ AXUM_CONF_TEST_NESTED_PLACEHOLDER }}"
+            max_payload_size_bytes = "1KiB"
+            "#,
+        );
+        unsafe { env::remove_var("AXUM_CONF_TEST_NESTED_PLACEHOLDER") };
+        assert_eq!(config.unwrap().http.bind_addr, "{{ HOME }}");
+    }
+
+    #[test]
+    fn test_from_toml_required_variable_fails_with_its_name() {
+        unsafe { env::remove_var("AXUM_CONF_TEST_REQUIRED_SECRET") };
+        let err = Config::<()>::from_toml(
+            r#"
+            [http.proxy_oidc]
+            shared_secret = "{{ AXUM_CONF_TEST_REQUIRED_SECRET | required }}"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        assert!(
+            err.to_string().contains("AXUM_CONF_TEST_REQUIRED_SECRET"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_from_toml_file_secret_populates_sensitive_field() {
+        let path = env::temp_dir().join(format!("axum-conf-proxy-secret-{}", uuid::Uuid::now_v7()));
+        std::fs::write(&path, "from-file-secret\n").unwrap();
+        let config = Config::<()>::from_toml(&format!(
+            r#"
+            [http]
+            max_payload_size_bytes = "1KiB"
+
+            [http.proxy_oidc]
+            shared_secret = "{{{{ file:{} }}}}"
+            "#,
+            path.display()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let proxy = config.unwrap().http.proxy_oidc.unwrap();
+        let secret: Option<crate::Sensitive<String>> = proxy.shared_secret;
+        assert_eq!(secret.unwrap().expose_secret(), "from-file-secret");
+    }
+
     #[test]
     fn test_config_from_str_valid() {
         unsafe {
@@ -1011,6 +1526,258 @@ format = "compact"
         );
     }
 
+    #[test]
+    fn test_from_toml_file_merges_base_file() {
+        let dir = env::temp_dir().join(format!("axum-conf-base-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("base.toml"),
+            r#"
+            [http]
+            bind_port = 3000
+            max_payload_size_bytes = "2KiB"
+            liveness_route = "/alive"
+
+            [logging]
+            format = "compact"
+            "#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("staging.toml"), "[http]\nbind_port = 8080\n").unwrap();
+
+        let result = Config::<()>::from_toml_file_in(&dir, "staging", std::iter::empty());
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = result.unwrap();
+
+        assert_eq!(config.http.bind_port, 8080);
+        assert_eq!(config.http.max_payload_size_bytes.as_u64(), 2048);
+        assert_eq!(config.http.liveness_route, "/alive");
+        assert!(matches!(config.logging.format, LogFormat::Compact));
+        assert!(!config.is_production);
+    }
+
+    #[test]
+    fn test_from_toml_file_applies_env_overrides() {
+        #[derive(Debug, Clone, Default, Deserialize)]
+        struct AppConfig {
+            #[serde(default)]
+            cache_ttl_secs: u64,
+            #[serde(default)]
+            api_key: String,
+        }
+
+        let dir = env::temp_dir().join(format!("axum-conf-overrides-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("prod.toml"),
+            r#"
+            api_key = "from-file"
+            cache_ttl_secs = 60
+
+            [http]
+            bind_port = 3000
+            max_payload_size_bytes = "1KiB"
+            "#,
+        )
+        .unwrap();
+        let vars = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let config = Config::<AppConfig>::from_toml_file_in(
+            &dir,
+            "prod",
+            vars(&[
+                ("AXUM_CONF__HTTP__BIND_PORT", "8080"),
+                ("AXUM_CONF__HTTP__MAX_REQUESTS_PER_SEC", "50"),
+                ("AXUM_CONF__LOGGING__FORMAT", "compact"),
+                ("AXUM_CONF__CACHE_TTL_SECS", "300"),
+                ("AXUM_CONF__API_KEY", "42"),
+            ]),
+        );
+        let bad = Config::<AppConfig>::from_toml_file_in(
+            &dir,
+            "prod",
+            vars(&[("AXUM_CONF__HTTP__BIND_PORT", "70000")]),
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let config = config.unwrap();
+        assert_eq!(config.http.bind_port, 8080);
+        assert_eq!(config.http.max_requests_per_sec, 50);
+        assert!(matches!(config.logging.format, LogFormat::Compact));
+        assert_eq!(config.app.cache_ttl_secs, 300);
+        assert_eq!(config.app.api_key, "42");
+
+        let err = bad.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        assert!(
+            err.to_string().contains("AXUM_CONF__HTTP__BIND_PORT"),
+            "error should name the override: {err}"
+        );
+    }
+
+    #[test]
+    fn test_env_override_sets_defaulted_app_field_missing_from_file() {
+        #[derive(Debug, Clone, Default, Deserialize)]
+        struct AppConfig {
+            #[serde(default)]
+            cache_ttl_secs: u64,
+            #[serde(default)]
+            region: String,
+        }
+
+        let dir = env::temp_dir().join(format!("axum-conf-overrides-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("prod.toml"),
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\n",
+        )
+        .unwrap();
+
+        let config = Config::<AppConfig>::from_toml_file_in(
+            &dir,
+            "prod",
+            [
+                ("AXUM_CONF__CACHE_TTL_SECS".to_string(), "300".to_string()),
+                ("AXUM_CONF__REGION".to_string(), "eu-west-1".to_string()),
+            ],
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let config = config.unwrap();
+        assert_eq!(config.app.cache_ttl_secs, 300);
+        assert_eq!(config.app.region, "eu-west-1");
+    }
+
+    #[test]
+    fn test_from_json_matches_from_toml() {
+        let toml: Config = r#"
+            [http]
+            bind_port = 8080
+            max_payload_size_bytes = "1KiB"
+            request_timeout = "5s"
+            [logging]
+            format = "compact"
+            "#
+        .parse()
+        .unwrap();
+        let json = Config::<()>::from_json(
+            r#"{
+                "http": {
+                    "bind_port": 8080,
+                    "max_payload_size_bytes": "1KiB",
+                    "request_timeout": "5s",
+                    "oidc": null
+                },
+                "logging": {"format": "compact"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json.to_redacted_json().unwrap(),
+            toml.to_redacted_json().unwrap()
+        );
+
+        let err = Config::<()>::from_json(r#"{"http": {"bind_port": "x"}}"#).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+    }
+
+    #[test]
+    fn test_from_json_reports_unknown_keys() {
+        let config = Config::<()>::from_json(
+            r#"{"http": {"max_payload_size_bytes": "1KiB", "max_request_per_sec": 5}}"#,
+        )
+        .unwrap();
+        let report = config.validation_report();
+        assert!(
+            report
+                .warnings()
+                .any(|issue| issue.path() == "http.max_request_per_sec")
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml_substitutes_placeholders() {
+        let config = Config::<()>::from_yaml(
+            r#"
+            http:
+              bind_port: {{ AXUM_CONF_TEST_YAML_PORT | default: "4000" }}
+              max_payload_size_bytes: 1KiB
+              liveness_route: /alive
+            logging:
+              format: json
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.http.bind_port, 4000);
+        assert_eq!(config.http.liveness_route, "/alive");
+        assert!(matches!(config.logging.format, LogFormat::Json));
+        assert!(config.is_production);
+    }
+
+    #[test]
+    fn test_from_file_detects_format_and_layers_over_base() {
+        let dir = env::temp_dir().join(format!("axum-conf-from-file-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("base.toml"),
+            "[http]\nbind_port = 3000\nmax_payload_size_bytes = \"2KiB\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("service.json"), r#"{"http": {"bind_port": 8080}}"#).unwrap();
+        std::fs::write(dir.join("service.ini"), "").unwrap();
+
+        let vars = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let dev = Config::<()>::from_file_with(
+            &dir.join("service.json"),
+            vars(&[
+                ("RUST_ENV", "dev"),
+                ("AXUM_CONF__LOGGING__FORMAT", "compact"),
+            ]),
+        );
+        let unset = Config::<()>::from_file_with(&dir.join("service.json"), vars(&[]));
+        let unknown = Config::<()>::from_file_with(&dir.join("service.ini"), vars(&[]));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let dev = dev.unwrap();
+        assert_eq!(dev.http.bind_port, 8080);
+        assert_eq!(dev.http.max_payload_size_bytes.as_u64(), 2048);
+        assert!(matches!(dev.logging.format, LogFormat::Compact));
+        assert!(!dev.is_production);
+        assert!(unset.unwrap().is_production, "unset RUST_ENV fails safe");
+        assert_eq!(unknown.unwrap_err().kind(), ErrorKind::Configuration);
+    }
+
+    #[test]
+    fn test_from_path_loads_a_file_and_names_a_missing_one() {
+        let dir = env::temp_dir().join(format!("axum-conf-from-path-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("service.toml"),
+            "[http]\nbind_port = 8080\nmax_payload_size_bytes = \"1KiB\"\n",
+        )
+        .unwrap();
+
+        let config = Config::<()>::from_path(dir.join("service.toml"));
+        let missing = Config::<()>::from_path(dir.join("missing.toml"));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(config.unwrap().http.bind_port, 8080);
+        let err = missing.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Io);
+        assert!(err.to_string().contains("missing.toml"), "{err}");
+    }
+
     #[test]
     #[cfg(feature = "postgres")]
     fn test_validate_empty_database_url() {
@@ -1979,4 +2746,469 @@ max_payload_size_bytes = "1KiB"
             }
         }
     }
+
+    #[test]
+    fn redacted_dump_masks_secrets_and_shows_resolved_values() {
+        #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+        struct AppConfig {
+            region: String,
+            api_token: crate::Sensitive<String>,
+        }
+
+        let config: Config<AppConfig> = r#"
+            region = "eu-west-1"
+            api_token = "hunter2-token"
+            [http]
+            max_payload_size_bytes = "1KiB"
+            x_frame_options = "https://example.com"
+            [http.cors]
+            allowed_methods = ["GET", "POST"]
+            allowed_headers = ["x-api-key"]
+            max_age = "1h"
+        "#
+        .parse()
+        .unwrap();
+        let config = config.with_production(false);
+
+        let toml = config.to_redacted_toml().unwrap();
+        assert!(!toml.contains("hunter2"), "{toml}");
+        let dumped: toml::Table = toml.parse().unwrap();
+        assert_eq!(dumped["api_token"].as_str(), Some("****"));
+        assert_eq!(dumped["region"].as_str(), Some("eu-west-1"));
+        assert_eq!(dumped["is_production"].as_bool(), Some(false));
+        // Defaults that were never written in the file are part of the dump.
+        assert_eq!(dumped["http"]["bind_port"].as_integer(), Some(3000));
+        assert_eq!(dumped["http"]["shutdown_timeout"].as_str(), Some("30s"));
+        assert_eq!(
+            dumped["http"]["x_frame_options"].as_str(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            dumped["http"]["cors"]["allowed_methods"],
+            toml::Value::Array(vec!["GET".into(), "POST".into()])
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&config.to_redacted_json().unwrap()).unwrap();
+        assert_eq!(json["api_token"], "****");
+        assert_eq!(json["is_production"], false);
+        assert_eq!(json["http"]["cors"]["max_age"], "1h");
+    }
+
+    #[test]
+    fn redacted_toml_reads_back_as_the_same_configuration() {
+        let config: Config = r#"
+            [http]
+            bind_port = 8080
+            max_payload_size_bytes = "64KiB"
+            request_timeout = "15s"
+            x_frame_options = "SAMEORIGIN"
+            [[http.directories]]
+            directory = "./public"
+            route = "/static"
+            [logging]
+            format = "json"
+        "#
+        .parse()
+        .unwrap();
+
+        let reparsed: Config = config.to_redacted_toml().unwrap().parse().unwrap();
+        assert_eq!(reparsed.http.bind_port, 8080);
+        assert_eq!(
+            reparsed.http.max_payload_size_bytes,
+            config.http.max_payload_size_bytes
+        );
+        assert_eq!(reparsed.http.request_timeout, Some(Duration::from_secs(15)));
+        assert!(matches!(
+            reparsed.http.x_frame_options.0,
+            XFrameOptions::SameOrigin
+        ));
+        assert_eq!(reparsed.http.directories[0].directory, "./public");
+        assert!(matches!(reparsed.logging.format, LogFormat::Json));
+    }
+
+    #[test]
+    fn validation_report_collects_every_problem_with_its_path() {
+        let config: Config = r#"
+            [http]
+            bind_addr = "not-an-ip"
+            max_concurrent_requests = 0
+            max_payload_size_bytes = "1KiB"
+            [[http.directories]]
+            directory = "./public"
+            route = "/static"
+            [[http.directories]]
+            directory = ""
+            route = "/assets"
+            cache_max_age = 99999999999
+            [logging]
+            filter = "info,[broken"
+        "#
+        .parse()
+        .unwrap();
+
+        let report = config.validation_report();
+        let errors: Vec<_> = report.errors().map(ValidationIssue::path).collect();
+        assert_eq!(
+            errors,
+            [
+                "http.bind_addr",
+                "http.max_concurrent_requests",
+                "http.directories[1].directory",
+                "http.directories[1].cache_max_age",
+                "logging.filter",
+            ]
+        );
+        assert!(report.warnings().any(|issue| issue.path() == "http.cors"));
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        let message = err.to_string();
+        assert!(message.starts_with("5 configuration errors:"), "{message}");
+        assert!(message.contains("http.directories[1].cache_max_age: cache_max_age exceeds"));
+    }
+
+    #[test]
+    fn secret_references_in_sensitive_fields_are_resolved() {
+        #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+        struct AppSettings {
+            #[serde(default)]
+            api_token: crate::Sensitive<String>,
+            #[serde(default)]
+            label: String,
+        }
+
+        struct TestSecrets;
+
+        impl SecretProvider for TestSecrets {
+            fn resolve(&self, key: &str) -> Result<Option<crate::Sensitive<String>>> {
+                Ok((key == "config-test/api_token").then(|| crate::Sensitive::from("s3cr3t")))
+            }
+        }
+
+        register_secret_provider(TestSecrets);
+
+        let config: Config<AppSettings> = r#"
+            api_token = "secret://config-test/api_token"
+            label = "secret://config-test/api_token"
+            [http]
+            max_payload_size_bytes = "1KiB"
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(config.app.api_token.expose_secret(), "s3cr3t");
+        assert_eq!(
+            config.app.label, "secret://config-test/api_token",
+            "only Sensitive fields are resolved"
+        );
+        assert!(!config.to_redacted_json().unwrap().contains("s3cr3t"));
+
+        let err = r#"api_token = "secret://config-test/unknown""#
+            .parse::<Config<AppSettings>>()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        assert!(err.to_string().contains("config-test/unknown"), "{err}");
+    }
+
+    #[test]
+    fn secret_references_are_resolved_once_per_load() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Debug, Clone, Default, Deserialize)]
+        struct AppSettings {
+            #[serde(default)]
+            api_token: crate::Sensitive<String>,
+        }
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        struct CountingSecrets;
+
+        impl SecretProvider for CountingSecrets {
+            fn resolve(&self, key: &str) -> Result<Option<crate::Sensitive<String>>> {
+                if key != "config-test/counted" {
+                    return Ok(None);
+                }
+                CALLS.fetch_add(1, Ordering::SeqCst);
+                Ok(Some(crate::Sensitive::from("s3cr3t")))
+            }
+        }
+
+        register_secret_provider(CountingSecrets);
+
+        let config: Config<AppSettings> = r#"
+            api_token = "secret://config-test/counted"
+            [http]
+            max_payload_size_bytes = "1KiB"
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(config.app.api_token.expose_secret(), "s3cr3t");
+        assert!(config.unknown_keys.is_empty());
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn secret_references_outside_config_loading_are_literal() {
+        struct LeakySecrets;
+
+        impl SecretProvider for LeakySecrets {
+            fn resolve(&self, key: &str) -> Result<Option<crate::Sensitive<String>>> {
+                Ok((key == "config-test/leaky").then(|| crate::Sensitive::from("s3cr3t")))
+            }
+        }
+
+        register_secret_provider(LeakySecrets);
+
+        // e.g. a request body: the reference must not reach the providers.
+        let value: crate::Sensitive<String> =
+            serde_json::from_str(r#""secret://config-test/leaky""#).unwrap();
+        assert_eq!(value.expose_secret(), "secret://config-test/leaky");
+        let value: crate::Sensitive<String> = serde_json::from_str(r#""enc:v1:AAAA""#).unwrap();
+        assert_eq!(value.expose_secret(), "enc:v1:AAAA");
+
+        let value: crate::Sensitive<String> =
+            secrets::with_resolution(|| serde_json::from_str(r#""secret://config-test/leaky""#))
+                .unwrap();
+        assert_eq!(value.expose_secret(), "s3cr3t");
+    }
+
+    #[test]
+    fn encrypted_values_without_a_key_fail_loading() {
+        let err: Error = secrets::with_resolution(|| {
+            toml::Value::from("enc:v1:AAAA").try_into::<crate::Sensitive<String>>()
+        })
+        .unwrap_err()
+        .into();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        #[cfg(feature = "encryption")]
+        assert!(
+            err.to_string().contains("AXUM_CONF_ENCRYPTION_KEY"),
+            "{err}"
+        );
+        #[cfg(not(feature = "encryption"))]
+        assert!(
+            err.to_string().contains("`encryption` crate feature"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_warnings_unless_strict() {
+        let toml_str = r#"
+            [http]
+            max_payload_size_bytes = "1KiB"
+            max_request_per_sec = 50
+        "#;
+        let config: Config = toml_str.parse().unwrap();
+        assert!(config.is_production);
+
+        assert!(config.validate().is_ok());
+        let report = config.validation_report();
+        assert!(
+            report
+                .warnings()
+                .any(|issue| issue.path() == "http.max_request_per_sec")
+        );
+
+        let err = config
+            .clone()
+            .with_production(false)
+            .with_strict_keys(true)
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        assert_eq!(
+            err.to_string(),
+            "http.max_request_per_sec: unknown configuration key; did you mean `max_requests_per_sec`?"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_fail_validation_in_a_named_production_environment() {
+        let dir = env::temp_dir().join(format!("axum-conf-strict-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("service.toml");
+        std::fs::write(
+            &path,
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\nmax_request_per_sec = 50\n",
+        )
+        .unwrap();
+
+        let load = |rust_env: Option<&str>| {
+            let vars = rust_env.map(|env| ("RUST_ENV".to_string(), env.to_string()));
+            Config::<()>::from_file_with(&path, vars)
+        };
+        let production = load(Some("production"));
+        let unset = load(None);
+        let dev = load(Some("dev"));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let production = production.unwrap();
+        assert!(production.validate().is_err());
+        assert!(production.with_strict_keys(false).validate().is_ok());
+        let unset = unset.unwrap();
+        assert!(unset.is_production);
+        assert!(unset.validate().is_ok());
+        assert!(dev.unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn unknown_keys_are_detected_in_layered_files() {
+        let dir = env::temp_dir().join(format!("axum-conf-unknown-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("base.toml"),
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\n[logging]\nfromat = \"json\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("prod.toml"), "[http]\nbind_port = 8080\n").unwrap();
+
+        let result = Config::<()>::from_toml_file_in(&dir, "prod", std::iter::empty());
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = result.unwrap();
+        let errors: Vec<_> = config
+            .validation_report()
+            .errors()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            errors,
+            ["logging.fromat: unknown configuration key; did you mean `format`?"]
+        );
+    }
+
+    #[test]
+    fn validation_report_keeps_the_kind_of_a_single_error() {
+        let mut config = Config::new();
+        config.http.max_concurrent_requests = 0;
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(
+            err.to_string().starts_with(
+                "http.max_concurrent_requests: HTTP max_concurrent_requests must be > 0"
+            )
+        );
+    }
+
+    #[test]
+    fn app_settings_are_validated_with_the_built_in_sections() {
+        #[derive(Debug, Clone, Default, Deserialize)]
+        struct AppSettings {
+            retries: u32,
+            upstream: String,
+        }
+
+        impl ValidateAppConfig for AppSettings {
+            fn validation_report(&self) -> ValidationReport {
+                let mut report = ValidationReport::new();
+                if self.retries > 10 {
+                    report.error("retries", Error::config("retries must be at most 10"));
+                }
+                if self.upstream.is_empty() {
+                    report.warning("upstream", "no upstream configured");
+                }
+                report
+            }
+        }
+
+        let config: Config<AppSettings> = r#"
+            retries = 50
+            upstream = ""
+            [http]
+            max_concurrent_requests = 0
+            max_payload_size_bytes = "1KiB"
+        "#
+        .parse()
+        .unwrap();
+
+        let report = config.validation_report();
+        let errors: Vec<_> = report.errors().map(ValidationIssue::path).collect();
+        assert_eq!(errors, ["http.max_concurrent_requests", "retries"]);
+        assert!(report.warnings().any(|issue| issue.path() == "upstream"));
+
+        let Err(err) = crate::FluentRouter::without_state(config.clone()) else {
+            panic!("invalid app settings must be rejected");
+        };
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        assert!(
+            err.to_string()
+                .contains("\n  - retries: retries must be at most 10")
+        );
+
+        let mut config = config;
+        config.http.max_concurrent_requests = 4096;
+        let err = config.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        assert_eq!(err.to_string(), "retries: retries must be at most 10");
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn json_schema_describes_library_sections_and_app_fields() {
+        #[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+        struct AppSettings {
+            /// Greeting returned by the index route.
+            greeting: String,
+            #[serde(default)]
+            retries: u32,
+        }
+
+        let schema = Config::<AppSettings>::json_schema();
+        let props = &schema["properties"];
+        assert_eq!(
+            props["greeting"]["description"],
+            "Greeting returned by the index route."
+        );
+        assert_eq!(props["retries"]["default"], 0);
+        assert_eq!(schema["required"], serde_json::json!(["greeting"]));
+        assert!(props.get("is_production").is_none());
+
+        let http = &schema["$defs"]["HttpConfig"];
+        assert_eq!(
+            http["required"],
+            serde_json::json!(["max_payload_size_bytes"])
+        );
+        assert_eq!(http["properties"]["bind_port"]["default"], 3000);
+        assert_eq!(http["properties"]["shutdown_timeout"]["default"], "30s");
+        assert_eq!(http["properties"]["shutdown_timeout"]["type"], "string");
+        assert_eq!(http["properties"]["x_frame_options"]["default"], "DENY");
+        assert!(
+            http["properties"]["max_payload_size_bytes"]["description"]
+                .as_str()
+                .unwrap()
+                .starts_with("Maximum payload size")
+        );
+        assert_eq!(
+            schema["$defs"]["StaticDirConfig"]["properties"]["cache_max_age"]["type"],
+            serde_json::json!(["integer", "null"])
+        );
+        assert_eq!(
+            schema["$defs"]["LoggingConfig"]["properties"]["format"]["default"],
+            "default"
+        );
+    }
+
+    #[cfg(all(feature = "json-schema", feature = "basic-auth", feature = "cors"))]
+    #[test]
+    fn json_schema_covers_nested_sections_and_marks_secrets() {
+        let schema = Config::<()>::json_schema();
+        let defs = &schema["$defs"];
+
+        let password = &defs["BasicAuthUser"]["properties"]["password"];
+        assert_eq!(password["type"], "string");
+        assert_eq!(password["writeOnly"], true);
+        assert_eq!(
+            defs["HttpCorsConfig"]["properties"]["allowed_methods"]["items"]["type"],
+            "string"
+        );
+        for section in [
+            "HttpDeduplicationConfig",
+            "StaticDirConfig",
+            "HttpProxyOidcConfig",
+            "CircuitBreakerConfig",
+        ] {
+            assert!(defs[section]["properties"].is_object(), "{section} missing");
+        }
+    }
 }
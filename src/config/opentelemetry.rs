@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 ///
 /// Configuration for OpenTelemetry distributed tracing.
@@ -13,7 +13,8 @@ use serde::Deserialize;
 /// endpoint = "http://localhost:4317"
 /// service_name = "my-service"
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct OpenTelemetryConfig {
     /// OTLP endpoint URL (e.g., "http://localhost:4317")
     pub endpoint: String,
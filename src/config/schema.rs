@@ -0,0 +1,28 @@
+//!
+//! JSON Schema fragments for configuration values whose TOML form differs from
+//! their Rust type (see [`Config::json_schema`](crate::Config::json_schema)).
+//!
+use schemars::{Schema, SchemaGenerator, json_schema};
+
+/// A human-readable duration parsed by `humantime` (`"30s"`, `"5m"`, `"1h 30m"`).
+pub(crate) fn duration(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "string",
+        "examples": ["30s", "5m", "1h 30m"]
+    })
+}
+
+/// A byte size: a string with an optional unit (`"32KiB"`, `"1 MB"`) or a plain
+/// number of bytes.
+pub(crate) fn byte_size(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": ["string", "integer"],
+        "minimum": 0,
+        "examples": ["32KiB", "1MiB"]
+    })
+}
+
+/// A string parsed into a richer type (HTTP method, header name, CIDR range, …).
+pub(crate) fn string(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": "string" })
+}
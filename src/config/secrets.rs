@@ -0,0 +1,444 @@
+//!
+//! Pluggable secret providers.
+//!
+//! A [`Sensitive<String>`](crate::Sensitive) field whose value in the
+//! configuration file is a reference such as `secret://db/password` is resolved
+//! while the configuration is deserialized: the key (`db/password`) is offered to
+//! each registered [`SecretProvider`] in registration order, and the first one
+//! that has it supplies the value. The secret never appears in the file, in the
+//! merged table, or in the process environment, and it is zeroized when the
+//! `Sensitive` holding it is dropped. Only the configuration loaders resolve
+//! references: a `Sensitive` deserialized elsewhere keeps the literal value.
+//!
+//! Providers are registered once, before the configuration is loaded, with
+//! [`register_secret_provider`]. Built-in providers read environment variables
+//! ([`EnvSecretProvider`]), individual files ([`FileSecretProvider`]) and a
+//! directory of files ([`DirectorySecretProvider`]); applications implement
+//! [`SecretProvider`] for anything else (Vault, a cloud secret manager, ...).
+//!
+//! ```rust
+//! use axum_conf::{Config, DirectorySecretProvider, register_secret_provider};
+//!
+//! # fn example() -> axum_conf::Result<()> {
+//! // Kubernetes / Docker secrets mounted as files under /run/secrets
+//! register_secret_provider(DirectorySecretProvider::new("/run/secrets"));
+//!
+//! // [http.oidc]
+//! // client_secret = "secret://oidc/client_secret"   # reads /run/secrets/oidc/client_secret
+//! let config = Config::<()>::from_rust_env()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+use {
+    crate::{Error, Result, Sensitive},
+    std::{
+        cell::RefCell,
+        collections::HashMap,
+        hash::{BuildHasher, DefaultHasher, Hash, Hasher, RandomState},
+        path::{Component, Path, PathBuf},
+        sync::{Arc, LazyLock, PoisonError, RwLock},
+    },
+};
+
+/// Prefix marking a `Sensitive<String>` value as a reference to a secret.
+pub(crate) const SECRET_REF_PREFIX: &str = "secret://";
+
+/// Resolves secret references found in `Sensitive<String>` configuration fields.
+///
+/// `resolve` receives the reference without its `secret://` prefix and returns
+/// `Ok(None)` when this provider does not hold the key, so that the next
+/// provider is asked. An error stops resolution and fails loading; it must not
+/// contain the secret itself.
+///
+/// # Example
+///
+/// ```rust
+/// use axum_conf::{Result, SecretProvider, Sensitive};
+///
+/// struct StaticSecrets;
+///
+/// impl SecretProvider for StaticSecrets {
+///     fn resolve(&self, key: &str) -> Result<Option<Sensitive<String>>> {
+///         Ok((key == "api/token").then(|| Sensitive::from("s3cr3t")))
+///     }
+/// }
+/// ```
+pub trait SecretProvider: Send + Sync + 'static {
+    /// Returns the secret stored under `key`, or `None` if this provider does
+    /// not have it.
+    fn resolve(&self, key: &str) -> Result<Option<Sensitive<String>>>;
+}
+
+/// Providers consulted for `secret://` references, in registration order.
+static SECRET_PROVIDERS: RwLock<Vec<Arc<dyn SecretProvider>>> = RwLock::new(Vec::new());
+
+/// Registers a provider for `secret://` references in `Sensitive<String>`
+/// fields. Providers are consulted in the order they were registered, for every
+/// configuration loaded (or reloaded) afterwards.
+pub fn register_secret_provider(provider: impl SecretProvider) {
+    SECRET_PROVIDERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Arc::new(provider));
+}
+
+/// Prefix marking a `Sensitive<String>` value as encrypted with the current
+/// format (see the `encryption` feature). Any other value, including one that
+/// merely starts with `enc:`, is taken literally.
+pub(crate) const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+thread_local! {
+    /// Set by [`with_resolution`] while this thread deserializes a
+    /// configuration, hashing every value resolved meanwhile.
+    static RESOLUTION: RefCell<Option<DefaultHasher>> = const { RefCell::new(None) };
+}
+
+/// Hash keys of [`SecretsFingerprint`], random per process so that a
+/// fingerprint cannot be matched against guessed secrets elsewhere.
+static FINGERPRINT_KEYS: LazyLock<RandomState> = LazyLock::new(RandomState::new);
+
+/// A keyed hash of the secrets resolved while loading a configuration. The
+/// [`ConfigWatcher`](crate::ConfigWatcher) compares it across reloads to notice
+/// a rotated secret when the configuration files did not change.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SecretsFingerprint(u64);
+
+/// Runs `f` with the resolution of `Sensitive<String>` values turned on for
+/// this thread. Only the configuration loaders call it, so a `Sensitive`
+/// deserialized anywhere else (a request body, a second pass over the same
+/// table) takes `secret://` and `enc:v1:` values literally, without reaching
+/// the secret providers or the decryption key.
+pub(crate) fn with_resolution<R>(f: impl FnOnce() -> R) -> R {
+    fingerprinted(f).0
+}
+
+/// Same as [`with_resolution`], also returning the fingerprint of the values
+/// resolved by `f`.
+pub(crate) fn fingerprinted<R>(f: impl FnOnce() -> R) -> (R, SecretsFingerprint) {
+    struct Restore(Option<DefaultHasher>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RESOLUTION.set(self.0.take());
+        }
+    }
+
+    let restore = Restore(RESOLUTION.replace(Some(FINGERPRINT_KEYS.build_hasher())));
+    let result = f();
+    let fingerprint = RESOLUTION.with_borrow(|hasher| hasher.as_ref().map(Hasher::finish));
+    drop(restore);
+    (result, SecretsFingerprint(fingerprint.unwrap_or_default()))
+}
+
+/// Whether this thread is inside [`with_resolution`].
+pub(crate) fn resolution_enabled() -> bool {
+    RESOLUTION.with_borrow(Option::is_some)
+}
+
+/// Resolves the deserialized value of a `Sensitive<String>` field: a
+/// `secret://` reference or an `enc:v1:` encrypted value yields the secret, any
+/// other value is taken literally (`None`). Inside [`with_resolution`], the
+/// secret is added to the fingerprint.
+pub(crate) fn resolve_sensitive_value(value: &str) -> Result<Option<Sensitive<String>>> {
+    let secret = if value.starts_with(SECRET_REF_PREFIX) {
+        resolve_secret_ref(value)?
+    } else if value.starts_with(ENCRYPTED_PREFIX) {
+        decrypt_value(value)?
+    } else {
+        return Ok(None);
+    };
+    RESOLUTION.with_borrow_mut(|hasher| {
+        if let Some(hasher) = hasher {
+            secret.expose_secret().hash(hasher);
+        }
+    });
+    Ok(Some(secret))
+}
+
+#[cfg(feature = "encryption")]
+fn decrypt_value(value: &str) -> Result<Sensitive<String>> {
+    super::encryption::decrypt_config_value(value)
+}
+
+#[cfg(not(feature = "encryption"))]
+fn decrypt_value(_value: &str) -> Result<Sensitive<String>> {
+    Err(Error::config(
+        "Encrypted configuration values (`enc:v1:`) require the `encryption` crate feature",
+    ))
+}
+
+/// Resolves `reference` (a value starting with [`SECRET_REF_PREFIX`]) through
+/// the registered providers.
+pub(crate) fn resolve_secret_ref(reference: &str) -> Result<Sensitive<String>> {
+    let key = reference
+        .strip_prefix(SECRET_REF_PREFIX)
+        .unwrap_or(reference);
+    // Clone the list so that providers run without holding the lock.
+    let providers = SECRET_PROVIDERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if providers.is_empty() {
+        return Err(Error::config(format!(
+            "Cannot resolve `{reference}`: no secret provider is registered \
+             (see `axum_conf::register_secret_provider`)"
+        )));
+    }
+    for provider in providers {
+        if let Some(secret) = provider.resolve(key)? {
+            return Ok(secret);
+        }
+    }
+    Err(Error::config(format!(
+        "Cannot resolve `{reference}`: no registered secret provider has `{key}`"
+    )))
+}
+
+/// Reads secrets from environment variables named after the key: `db/password`
+/// is read from `DB_PASSWORD` (upper-cased, every character other than a
+/// letter or digit replaced by `_`), after an optional prefix.
+///
+/// Environment variables are visible in `/proc/<pid>/environ`; prefer
+/// [`DirectorySecretProvider`] or [`FileSecretProvider`] where possible.
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretProvider {
+    prefix: String,
+}
+
+impl EnvSecretProvider {
+    /// Creates a provider reading `DB_PASSWORD` for `db/password`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prepends `prefix` to every variable name, e.g. `MYAPP_` reads
+    /// `MYAPP_DB_PASSWORD` for `db/password`.
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    fn var_name(&self, key: &str) -> String {
+        let name: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}{name}", self.prefix)
+    }
+}
+
+impl SecretProvider for EnvSecretProvider {
+    fn resolve(&self, key: &str) -> Result<Option<Sensitive<String>>> {
+        Ok(std::env::var(self.var_name(key)).ok().map(Sensitive))
+    }
+}
+
+/// Reads each secret from a file named explicitly for its key. Surrounding
+/// whitespace (such as the final newline) is trimmed.
+///
+/// ```rust
+/// use axum_conf::FileSecretProvider;
+///
+/// let provider = FileSecretProvider::new()
+///     .with_file("db/password", "/run/secrets/postgres-password")
+///     .with_file("oidc/client_secret", "/run/secrets/keycloak-client");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FileSecretProvider {
+    files: HashMap<String, PathBuf>,
+}
+
+impl FileSecretProvider {
+    /// Creates a provider without any file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the secret `key` from `path`. A missing file is an error, since it
+    /// was named explicitly.
+    #[must_use]
+    pub fn with_file(mut self, key: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.files.insert(key.into(), path.into());
+        self
+    }
+}
+
+impl SecretProvider for FileSecretProvider {
+    fn resolve(&self, key: &str) -> Result<Option<Sensitive<String>>> {
+        match self.files.get(key) {
+            Some(path) => read_secret_file(path).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Reads secrets from files in a directory, the key being the path relative to
+/// it: `db/password` is read from `{dir}/db/password`. Surrounding whitespace
+/// is trimmed. This matches Kubernetes and Docker secrets mounted as files.
+///
+/// Keys that would leave the directory (absolute paths, `..`) are rejected, and
+/// a missing file lets the next provider try.
+#[derive(Debug, Clone)]
+pub struct DirectorySecretProvider {
+    dir: PathBuf,
+}
+
+impl DirectorySecretProvider {
+    /// Creates a provider reading secrets from files in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SecretProvider for DirectorySecretProvider {
+    fn resolve(&self, key: &str) -> Result<Option<Sensitive<String>>> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(Error::config(format!(
+                "Invalid secret key `{key}`: expected a relative path such as `db/password`"
+            )));
+        }
+        let path = self.dir.join(relative);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        read_secret_file(&path).map(Some)
+    }
+}
+
+/// Reads a secret file, trimming surrounding whitespace without leaving an
+/// untrimmed copy behind.
+fn read_secret_file(path: &Path) -> Result<Sensitive<String>> {
+    let contents = Sensitive(std::fs::read_to_string(path).map_err(|e| {
+        Error::config(format!(
+            "Failed to read secret file `{}`: {e}",
+            path.display()
+        ))
+    })?);
+    Ok(Sensitive(contents.0.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch directory removed on drop.
+    struct SecretDir(PathBuf);
+
+    impl SecretDir {
+        fn new(files: &[(&str, &str)]) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("axum-conf-secrets-{}", uuid::Uuid::now_v7()));
+            for (name, contents) in files {
+                let path = dir.join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, contents).unwrap();
+            }
+            Self(dir)
+        }
+    }
+
+    impl Drop for SecretDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn exposed(secret: Option<Sensitive<String>>) -> Option<String> {
+        secret.map(|s| s.expose_secret().clone())
+    }
+
+    #[test]
+    fn values_outside_the_encrypted_format_are_literal() {
+        assert!(resolve_sensitive_value("enc:foo").unwrap().is_none());
+        assert!(resolve_sensitive_value("enc:v2:abc").unwrap().is_none());
+        assert!(resolve_sensitive_value("plain").unwrap().is_none());
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[test]
+    fn encrypted_values_require_the_feature() {
+        let err = resolve_sensitive_value("enc:v1:AAAA").unwrap_err();
+        assert!(err.to_string().contains("`encryption` crate feature"));
+    }
+
+    #[test]
+    fn env_provider_maps_keys_to_variable_names() {
+        let provider = EnvSecretProvider::new().with_prefix("MYAPP_");
+        assert_eq!(provider.var_name("db/password"), "MYAPP_DB_PASSWORD");
+        assert_eq!(
+            provider.var_name("oidc.client-secret"),
+            "MYAPP_OIDC_CLIENT_SECRET"
+        );
+        assert_eq!(
+            exposed(provider.resolve("axum-conf-test/unset").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn directory_provider_reads_trimmed_files_inside_the_directory() {
+        let dir = SecretDir::new(&[("db/password", "hunter2\n")]);
+        let provider = DirectorySecretProvider::new(&dir.0);
+
+        assert_eq!(
+            exposed(provider.resolve("db/password").unwrap()).as_deref(),
+            Some("hunter2")
+        );
+        assert_eq!(exposed(provider.resolve("db/missing").unwrap()), None);
+        for key in ["../etc/passwd", "/etc/passwd", "db/../db/password", ""] {
+            assert!(provider.resolve(key).is_err(), "{key:?} must be rejected");
+        }
+    }
+
+    #[test]
+    fn file_provider_reads_named_files_only() {
+        let dir = SecretDir::new(&[("pg-password", "  s3cr3t \n")]);
+        let provider = FileSecretProvider::new()
+            .with_file("db/password", dir.0.join("pg-password"))
+            .with_file("db/missing", dir.0.join("missing"));
+
+        assert_eq!(
+            exposed(provider.resolve("db/password").unwrap()).as_deref(),
+            Some("s3cr3t")
+        );
+        assert_eq!(exposed(provider.resolve("other").unwrap()), None);
+        let err = provider.resolve("db/missing").unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Configuration);
+    }
+
+    #[test]
+    fn registered_providers_are_tried_in_order() {
+        struct Fixed(&'static str, &'static str);
+
+        impl SecretProvider for Fixed {
+            fn resolve(&self, key: &str) -> Result<Option<Sensitive<String>>> {
+                Ok((key == self.0).then(|| Sensitive::from(self.1)))
+            }
+        }
+
+        // Keys are unique to this test since the registry is process-wide.
+        register_secret_provider(Fixed("order-test/a", "first"));
+        register_secret_provider(Fixed("order-test/a", "second"));
+        register_secret_provider(Fixed("order-test/b", "third"));
+
+        let a = resolve_secret_ref("secret://order-test/a").unwrap();
+        let b = resolve_secret_ref("secret://order-test/b").unwrap();
+        assert_eq!(a.expose_secret(), "first");
+        assert_eq!(b.expose_secret(), "third");
+
+        let err = resolve_secret_ref("secret://order-test/missing").unwrap_err();
+        assert!(err.to_string().contains("order-test/missing"), "{err}");
+    }
+}
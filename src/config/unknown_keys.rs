@@ -0,0 +1,330 @@
+//!
+//! Detection of configuration keys that no section consumes.
+//!
+//! The config structs accept unknown fields, so a misspelled key such as
+//! `max_request_per_sec` would otherwise be silently ignored. After a file is
+//! parsed, its raw table is compared against the keys the configuration knows:
+//!
+//! - Library sections (`[http]`, `[logging]`, ...) are compared with their own
+//!   serialized form, which lists every field (unset options included), down
+//!   through nested tables and arrays of tables.
+//! - Top-level keys are otherwise offered to the application type `T`; keys it
+//!   consumes are subtracted, and keys it ignores inside its own tables are
+//!   reported too.
+//!
+//! Each unknown key carries the closest known sibling name as a suggestion.
+//! Sections of disabled crate features (e.g. `[database]` without `postgres`)
+//! are reported separately: they are recognized, just inactive.
+//!
+use {
+    super::Config,
+    serde::de::DeserializeOwned,
+    serde_json::Value as Json,
+    std::collections::BTreeSet,
+    toml::{Table, Value},
+};
+
+/// Minimum Jaro-Winkler similarity for a known field to be suggested.
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// Keys that are only recognized when a crate feature is enabled.
+const FEATURE_GATED_KEYS: &[(&str, &str)] = &[
+    ("database", "postgres"),
+    ("circuit_breaker", "circuit-breaker"),
+    ("http.basic_auth", "basic-auth"),
+    ("http.oidc", "keycloak"),
+    ("http.session_secure_cookie", "session"),
+    ("http.session_same_site", "session"),
+    ("http.session_store", "session"),
+    ("http.session_signing_key", "session"),
+    ("http.tls", "tls"),
+    ("http.client_cert_auth", "tls"),
+    ("logging.opentelemetry", "opentelemetry"),
+];
+
+/// A key present in the configuration file that no section consumes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnknownKey {
+    /// TOML key path, e.g. `http.cors.allowed_origin`.
+    pub(crate) path: String,
+    /// Closest known field at the same level, if any is similar enough.
+    suggestion: Option<String>,
+    /// Crate feature that would make this key known.
+    feature: Option<&'static str>,
+}
+
+impl UnknownKey {
+    /// Whether the key belongs to a section of a disabled crate feature rather
+    /// than being misspelled.
+    pub(crate) fn is_feature_gated(&self) -> bool {
+        self.feature.is_some()
+    }
+
+    /// Describes the problem, without the path.
+    pub(crate) fn message(&self) -> String {
+        match (self.feature, &self.suggestion) {
+            (Some(feature), _) => {
+                format!("ignored because the `{feature}` crate feature is not enabled")
+            }
+            (None, Some(suggestion)) => {
+                format!("unknown configuration key; did you mean `{suggestion}`?")
+            }
+            (None, None) => "unknown configuration key".to_string(),
+        }
+    }
+}
+
+/// Returns the keys of `table` (the raw file `config` was parsed from) that no
+/// part of the configuration consumes.
+pub(crate) fn find_unknown_keys<T>(table: &Table, config: &Config<T>) -> Vec<UnknownKey>
+where
+    T: DeserializeOwned + Clone + Default,
+{
+    let sections = [
+        ("http", serde_json::to_value(&config.http).ok()),
+        ("logging", serde_json::to_value(&config.logging).ok()),
+        #[cfg(feature = "postgres")]
+        ("database", serde_json::to_value(&config.database).ok()),
+        #[cfg(feature = "circuit-breaker")]
+        (
+            "circuit_breaker",
+            serde_json::to_value(&config.circuit_breaker).ok(),
+        ),
+    ];
+
+    // Keys the application type ignores, as paths from the root.
+    let mut ignored_by_app = Vec::new();
+    // `T` was already deserialized from this table; this pass runs outside
+    // `with_resolution`, so secrets are neither fetched nor decrypted again.
+    let app_consumes_root =
+        serde_ignored::deserialize::<_, _, T>(Value::Table(table.clone()), |path| {
+            ignored_by_app.push(format_path(&path))
+        })
+        .is_ok();
+    let ignored_by_app: BTreeSet<String> = ignored_by_app.into_iter().collect();
+    let consumed_by_app = |key: &str| app_consumes_root && !ignored_by_app.contains(key);
+
+    let mut unknown = Vec::new();
+    let root_candidates: Vec<&str> = sections
+        .iter()
+        .map(|(name, _)| *name)
+        .chain(
+            table
+                .keys()
+                .map(String::as_str)
+                .filter(|k| consumed_by_app(k)),
+        )
+        .collect();
+
+    for (key, value) in table {
+        match sections.iter().find(|(name, _)| name == key) {
+            Some((_, Some(known))) => compare(value, known, key, &mut unknown),
+            Some((_, None)) => {}
+            None if consumed_by_app(key) => {}
+            None => unknown.push(unknown_key(key.clone(), key, &root_candidates)),
+        }
+    }
+
+    // Keys ignored inside the application's own tables.
+    for path in &ignored_by_app {
+        let root = path.split(['.', '[']).next().unwrap_or_default();
+        if root != path && consumed_by_app(root) {
+            unknown.push(UnknownKey {
+                path: path.clone(),
+                suggestion: None,
+                feature: None,
+            });
+        }
+    }
+
+    unknown
+}
+
+/// Compares a raw value with the serialized form of the section that consumed
+/// it, recording raw keys the section does not have.
+fn compare(raw: &Value, known: &Json, path: &str, unknown: &mut Vec<UnknownKey>) {
+    match (raw, known) {
+        (Value::Table(table), Json::Object(fields)) => {
+            let candidates: Vec<&str> = fields.keys().map(String::as_str).collect();
+            for (key, value) in table {
+                let path = format!("{path}.{key}");
+                match fields.get(key) {
+                    Some(known) => compare(value, known, &path, unknown),
+                    None => unknown.push(unknown_key(path, key, &candidates)),
+                }
+            }
+        }
+        (Value::Array(items), Json::Array(known_items)) => {
+            for (index, (item, known)) in items.iter().zip(known_items).enumerate() {
+                compare(item, known, &format!("{path}[{index}]"), unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn unknown_key(path: String, key: &str, candidates: &[&str]) -> UnknownKey {
+    let feature = FEATURE_GATED_KEYS
+        .iter()
+        .find(|(gated, _)| *gated == path)
+        .map(|(_, feature)| *feature);
+    UnknownKey {
+        suggestion: feature
+            .is_none()
+            .then(|| closest(key, candidates))
+            .flatten(),
+        path,
+        feature,
+    }
+}
+
+/// The candidate most similar to `key`, if it is similar enough.
+fn closest(key: &str, candidates: &[&str]) -> Option<String> {
+    candidates
+        .iter()
+        .map(|candidate| (strsim::jaro_winkler(key, candidate), candidate))
+        .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Formats a `serde_ignored` path as a TOML key path (`cache.rules[1].ttl`).
+fn format_path(path: &serde_ignored::Path<'_>) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{index}]", format_path(parent)),
+        Path::Map { parent, key } => match format_path(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{parent}.{key}"),
+        },
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => format_path(parent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    fn unknown<T>(toml_str: &str) -> Vec<UnknownKey>
+    where
+        T: DeserializeOwned + Clone + Default,
+    {
+        let table: Table = toml_str.parse().unwrap();
+        let config: Config<T> = toml_str.parse().unwrap();
+        find_unknown_keys(&table, &config)
+    }
+
+    fn paths(keys: &[UnknownKey]) -> Vec<&str> {
+        keys.iter().map(|key| key.path.as_str()).collect()
+    }
+
+    #[test]
+    fn known_keys_are_not_reported() {
+        let keys = unknown::<()>(
+            r#"
+            [http]
+            max_payload_size_bytes = "1KiB"
+            max_requests_per_sec = 50
+            x_frame_options = "SAMEORIGIN"
+            Exclude = ["rate-limiting"]
+            [http.metrics_global_labels]
+            service = "demo"
+            [[http.directories]]
+            directory = "./public"
+            route = "/static"
+            [logging]
+            format = "json"
+            "#,
+        );
+        assert_eq!(keys, []);
+    }
+
+    #[test]
+    fn misspelled_keys_are_reported_with_a_suggestion() {
+        let keys = unknown::<()>(
+            r#"
+            [http]
+            max_payload_size_bytes = "1KiB"
+            max_request_per_sec = 50
+            [http.cors]
+            allowed_origin = ["https://example.com"]
+            [[http.directories]]
+            directory = "./public"
+            route = "/static"
+            [[http.directories]]
+            directory = "./dist"
+            fallback = true
+            cache_max_ag = 60
+            [loging]
+            format = "json"
+            "#,
+        );
+        assert_eq!(
+            paths(&keys),
+            [
+                "http.max_request_per_sec",
+                "http.cors.allowed_origin",
+                "http.directories[1].cache_max_ag",
+                "loging",
+            ]
+        );
+        assert_eq!(
+            keys[0].message(),
+            "unknown configuration key; did you mean `max_requests_per_sec`?"
+        );
+        assert_eq!(keys[1].suggestion.as_deref(), Some("allowed_origins"));
+        assert_eq!(keys[2].suggestion.as_deref(), Some("cache_max_age"));
+        assert_eq!(keys[3].suggestion.as_deref(), Some("logging"));
+    }
+
+    #[test]
+    fn keys_consumed_by_the_app_are_subtracted() {
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Default, Deserialize)]
+        struct Cache {
+            ttl_secs: u64,
+        }
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Default, Deserialize)]
+        struct App {
+            greeting: String,
+            #[serde(default)]
+            cache: Option<Cache>,
+        }
+
+        let keys = unknown::<App>(
+            r#"
+            greeting = "hello"
+            greting = "typo"
+            [cache]
+            ttl_secs = 5
+            ttl_sec = 5
+            [http]
+            max_payload_size_bytes = "1KiB"
+            "#,
+        );
+        assert_eq!(paths(&keys), ["greting", "cache.ttl_sec"]);
+        assert_eq!(keys[0].suggestion.as_deref(), Some("greeting"));
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    #[test]
+    fn sections_of_disabled_features_are_flagged_as_such() {
+        let keys = unknown::<()>(
+            r#"
+            [database]
+            url = "postgres://localhost/app"
+            "#,
+        );
+        assert_eq!(paths(&keys), ["database"]);
+        assert!(keys[0].is_feature_gated());
+        assert_eq!(
+            keys[0].message(),
+            "ignored because the `postgres` crate feature is not enabled"
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//!
+//! Aggregated configuration validation: every error and warning found in a
+//! configuration, each with the TOML key path it refers to.
+//!
+use crate::{Error, ErrorKind, Result};
+use std::fmt;
+
+/// Whether a [`ValidationIssue`] prevents the configuration from being used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The configuration is invalid; [`ValidationReport::into_result`] fails.
+    Error,
+    /// The configuration works but is likely not what was intended.
+    Warning,
+}
+
+/// A single problem found while validating a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    severity: Severity,
+    kind: ErrorKind,
+    path: String,
+    message: String,
+}
+
+impl ValidationIssue {
+    /// Whether this is an error or a warning.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// The error kind reported when this issue fails validation (warnings are
+    /// [`ErrorKind::Configuration`]).
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// TOML key path of the offending value, e.g. `http.directories[1].cache_max_age`.
+    /// Empty when the issue concerns the configuration as a whole.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Human-readable description of the problem, with guidance on fixing it.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Every error and warning found while validating a configuration.
+///
+/// Each section's `validation_report()` checks everything instead of stopping
+/// at the first problem, so a broken config file can be fixed in one go.
+/// Paths are relative to the section that produced the report;
+/// [`Config::validation_report`](crate::Config::validation_report) returns
+/// them relative to the file root.
+///
+/// ```rust
+/// use axum_conf::Config;
+///
+/// let config: Config = r#"
+///     [http]
+///     bind_addr = "localhost"
+///     max_concurrent_requests = 0
+///     max_payload_size_bytes = "1KiB"
+/// "#
+/// .parse()
+/// .unwrap();
+///
+/// let report = config.validation_report();
+/// let paths: Vec<_> = report.errors().map(|issue| issue.path()).collect();
+/// assert_eq!(paths, ["http.bind_addr", "http.max_concurrent_requests"]);
+/// assert!(report.into_result().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an error at `path`, keeping the kind and message of `error`.
+    pub fn error(&mut self, path: impl Into<String>, error: Error) {
+        self.issues.push(ValidationIssue {
+            severity: Severity::Error,
+            kind: error.kind(),
+            path: path.into(),
+            message: error.to_string(),
+        });
+    }
+
+    /// Records a warning at `path`.
+    pub fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            kind: ErrorKind::Configuration,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Appends the issues of a nested section's report, prefixing their paths
+    /// with `prefix` (e.g. `http` or `directories[1]`).
+    pub fn merge(&mut self, prefix: &str, other: ValidationReport) {
+        self.issues
+            .extend(other.issues.into_iter().map(|mut issue| {
+                issue.path = join_path(prefix, &issue.path);
+                issue
+            }));
+    }
+
+    /// All issues, in the order they were found.
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    /// The issues that make the configuration invalid.
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// The issues that are reported but do not fail validation.
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    /// Returns true if any error was recorded.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Returns true if no error or warning was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Emits every warning through `tracing`.
+    pub fn log_warnings(&self) {
+        for issue in self.warnings() {
+            tracing::warn!(path = %issue.path, "{}", issue.message);
+        }
+    }
+
+    /// Returns `Ok` when there are no errors, otherwise an error listing all of
+    /// them. The error kind is that of the errors when they agree (e.g.
+    /// [`ErrorKind::Database`] for `[database]` problems) and
+    /// [`ErrorKind::Configuration`] otherwise.
+    pub fn into_result(self) -> Result<()> {
+        let errors: Vec<_> = self.errors().collect();
+        let Some(first) = errors.first() else {
+            return Ok(());
+        };
+        let kind = if errors.iter().all(|issue| issue.kind == first.kind) {
+            first.kind
+        } else {
+            ErrorKind::Configuration
+        };
+        if errors.len() == 1 {
+            return Err(Error::new(kind, first.to_string()));
+        }
+        let mut message = format!("{} configuration errors:", errors.len());
+        for issue in errors {
+            message.push_str("\n  - ");
+            message.push_str(&issue.to_string());
+        }
+        Err(Error::new(kind, message))
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let label = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            write!(f, "{label}: {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validation of the application's own settings, the `T` of
+/// [`Config<T>`](crate::Config).
+///
+/// [`Config::validate`](crate::Config::validate),
+/// [`FluentRouter::with_state`](crate::FluentRouter::with_state) and the
+/// [`ConfigWatcher`](crate::ConfigWatcher) check `T` together with the built-in
+/// sections, so application problems land in the same [`ValidationReport`] and
+/// fail startup (or a reload) the same way. The default implementation accepts
+/// everything: a type without constraints only needs an empty `impl`.
+///
+/// Application fields are flattened into the root of the file, so report paths
+/// from the root (e.g. `cache.ttl_secs`).
+///
+/// ```rust
+/// use axum_conf::{Config, Error, ErrorKind, ValidateAppConfig, ValidationReport};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Clone, Default, Deserialize)]
+/// struct AppSettings {
+///     #[serde(default)]
+///     cache_ttl_secs: u64,
+/// }
+///
+/// impl ValidateAppConfig for AppSettings {
+///     fn validation_report(&self) -> ValidationReport {
+///         let mut report = ValidationReport::new();
+///         if self.cache_ttl_secs == 0 {
+///             report.error("cache_ttl_secs", Error::config("must be greater than 0"));
+///         }
+///         report
+///     }
+/// }
+///
+/// let config: Config<AppSettings> = r#"
+///     cache_ttl_secs = 0
+///
+///     [http]
+///     max_payload_size_bytes = "1KiB"
+/// "#
+/// .parse()
+/// .unwrap();
+///
+/// let err = config.validate().unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Configuration);
+/// assert_eq!(err.to_string(), "cache_ttl_secs: must be greater than 0");
+/// ```
+pub trait ValidateAppConfig {
+    /// Checks the application settings, returning every error and warning with
+    /// its key path from the file root.
+    fn validation_report(&self) -> ValidationReport {
+        ValidationReport::new()
+    }
+}
+
+impl ValidateAppConfig for () {}
+
+/// Joins a section prefix and a relative key path.
+fn join_path(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        path.to_string()
+    } else if path.is_empty() {
+        prefix.to_string()
+    } else if path.starts_with('[') {
+        format!("{prefix}{path}")
+    } else {
+        format!("{prefix}.{path}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_prefixes_nested_paths() {
+        let mut dir = ValidationReport::new();
+        dir.error("cache_max_age", Error::invalid_input("too large"));
+        let mut http = ValidationReport::new();
+        http.merge("directories[1]", dir);
+        http.warning("", "no CORS");
+        let mut root = ValidationReport::new();
+        root.merge("http", http);
+
+        let paths: Vec<_> = root.issues().iter().map(ValidationIssue::path).collect();
+        assert_eq!(paths, ["http.directories[1].cache_max_age", "http"]);
+    }
+
+    #[test]
+    fn into_result_lists_every_error() {
+        let mut report = ValidationReport::new();
+        report.warning("http.cors", "permissive");
+        assert!(report.clone().into_result().is_ok());
+
+        report.error("http.bind_addr", Error::invalid_input("bad address"));
+        let err = report.clone().into_result().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "http.bind_addr: bad address");
+
+        report.error("database.url", Error::database_config("URL is required"));
+        let err = report.into_result().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Configuration);
+        let message = err.to_string();
+        assert!(message.starts_with("2 configuration errors:"));
+        assert!(message.contains("\n  - http.bind_addr: bad address"));
+        assert!(message.contains("\n  - database.url: Database configuration error"));
+    }
+
+    #[test]
+    fn display_labels_severity() {
+        let mut report = ValidationReport::new();
+        report.error("logging.filter", Error::config("invalid"));
+        report.warning("http.cors", "permissive");
+        assert_eq!(
+            report.to_string(),
+            "error: logging.filter: invalid\nwarning: http.cors: permissive"
+        );
+    }
+}
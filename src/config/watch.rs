@@ -0,0 +1,730 @@
+//!
+//! Hot reloading of the configuration files.
+//!
+//! A [`ConfigWatcher`] loads `config/{env}.toml` like
+//! [`Config::from_toml_file`], then keeps checking the files it was built from
+//! (and `config/base.toml`) for changes. On Unix, `SIGHUP` forces a reload.
+//! Every successful reload that changes something is published on a
+//! [`tokio::sync::watch`] channel.
+//!
+//! Only some keys take effect while the service is running:
+//! `http.max_requests_per_sec`, `http.cors.allowed_origins`,
+//! `http.basic_auth.users`, `http.basic_auth.api_keys`, `logging.filter` and
+//! `circuit_breaker.targets`. Changes to any other key owned by this crate (the
+//! bind address, timeouts, the middleware list, …) are reported in
+//! [`ConfigReload::restart_required_keys`] and logged, never silently ignored.
+//! Keys of the application type `T` are published as-is; reacting to them is up
+//! to the application.
+//!
+//! A reload also publishes when only a resolved secret changed — a rotated
+//! file behind a [`DirectorySecretProvider`](crate::DirectorySecretProvider),
+//! say — even though the files did not. Secret stores are not watched, so
+//! such a rotation is picked up by the next [`ConfigWatcher::reload`] or
+//! `SIGHUP`.
+//!
+use {
+    super::{
+        Config, LIBRARY_SECTIONS, ValidateAppConfig, env_vars,
+        layered::{self, BASE_CONFIG_NAME},
+        logging,
+        secrets::SecretsFingerprint,
+    },
+    crate::{Error, Result},
+    serde::de::DeserializeOwned,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+        time::{Duration, SystemTime},
+    },
+    tokio::{runtime::Handle, sync::watch},
+    tokio_util::task::AbortOnDropHandle,
+    toml::{Table, Value},
+};
+
+/// How often the configuration files are checked for changes by default.
+pub(crate) const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Keys (and everything below them) that are applied without a restart.
+pub(crate) const RELOADABLE_KEYS: &[&str] = &[
+    "http.max_requests_per_sec",
+    "http.cors.allowed_origins",
+    "http.basic_auth.users",
+    "http.basic_auth.api_keys",
+    "logging.filter",
+    "circuit_breaker.targets",
+];
+
+/// Outcome of a configuration reload.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigReload {
+    /// Every key whose value changed, as a dotted TOML path (e.g.
+    /// `http.max_requests_per_sec`), sorted. Includes application keys.
+    pub changed_keys: Vec<String>,
+    /// The subset of `changed_keys` that only takes effect after a restart.
+    pub restart_required_keys: Vec<String>,
+    /// Whether a resolved `secret://` reference or `enc:v1:` value changed.
+    /// The keys holding them are not listed in `changed_keys` unless the
+    /// reference itself changed.
+    pub secrets_changed: bool,
+}
+
+impl ConfigReload {
+    /// Returns `true` when the reload did not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.changed_keys.is_empty() && !self.secrets_changed
+    }
+}
+
+/// Watches the configuration files and publishes reloaded configurations.
+///
+/// Create one with [`Config::watch_toml_file`] or [`Config::watch_rust_env`].
+/// The files are checked every few seconds (see
+/// [`with_poll_interval`](Self::with_poll_interval)), which also catches the
+/// symlink swap Kubernetes performs when a mounted ConfigMap is updated. On
+/// Unix, sending `SIGHUP` to the process reloads immediately.
+///
+/// A reload that fails to parse or validate is logged and the current
+/// configuration is kept. Dropping the watcher stops watching and closes the
+/// channel.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum_conf::{Config, FluentRouter};
+///
+/// # async fn example() -> axum_conf::Result<()> {
+/// let watcher = Config::<()>::watch_rust_env()?;
+/// let config = (*watcher.current()).clone();
+///
+/// FluentRouter::without_state(config)?
+///     .setup_middleware()
+///     .await?
+///     .with_config_updates(watcher.subscribe())
+///     .start()
+///     .await
+/// # }
+/// ```
+pub struct ConfigWatcher<T = ()>
+where
+    T: Clone + Default,
+{
+    shared: Arc<Shared<T>>,
+    runtime: Handle,
+    _task: AbortOnDropHandle<()>,
+}
+
+struct Shared<T: Clone + Default> {
+    dir: PathBuf,
+    env: String,
+    sender: watch::Sender<Arc<Config<T>>>,
+    state: Mutex<WatchState>,
+}
+
+/// The merged table of the current configuration and the files it came from.
+struct WatchState {
+    table: Table,
+    secrets: SecretsFingerprint,
+    files: Vec<PathBuf>,
+    stamps: Vec<Option<FileStamp>>,
+}
+
+/// Modification time and length of a file, used to detect changes.
+type FileStamp = (Option<SystemTime>, u64);
+
+impl<T> ConfigWatcher<T>
+where
+    T: DeserializeOwned + Clone + Default + ValidateAppConfig + Send + Sync + 'static,
+{
+    /// Loads `{dir}/{env}.toml` and starts watching it.
+    pub(crate) fn spawn(dir: &Path, env: &str) -> Result<Self> {
+        let runtime = Handle::try_current().map_err(|_| {
+            Error::internal("Watching the configuration requires a running Tokio runtime")
+        })?;
+
+        let (config, layered) = Config::<T>::load_layered(dir, env, env_vars())?;
+        config.validate()?;
+        let files = watched_files(dir, layered.files);
+        let state = WatchState {
+            table: layered.table,
+            secrets: layered.secrets,
+            stamps: stamp_files(&files),
+            files,
+        };
+
+        let shared = Arc::new(Shared {
+            dir: dir.to_path_buf(),
+            env: env.to_string(),
+            sender: watch::Sender::new(Arc::new(config)),
+            state: Mutex::new(state),
+        });
+        let task = spawn_watch_task(&runtime, &shared, DEFAULT_POLL_INTERVAL);
+        Ok(Self {
+            shared,
+            runtime,
+            _task: task,
+        })
+    }
+
+    /// Sets how often the configuration files are checked for changes
+    /// (default: 5 seconds).
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        // Replacing the guard aborts the previous task.
+        self._task = spawn_watch_task(&self.runtime, &self.shared, interval);
+        self
+    }
+
+    /// Returns a receiver that observes every published configuration.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Config<T>>> {
+        self.shared.sender.subscribe()
+    }
+
+    /// Returns the current configuration.
+    pub fn current(&self) -> Arc<Config<T>> {
+        self.shared.sender.borrow().clone()
+    }
+
+    /// Reloads the configuration files now and publishes the result if
+    /// anything changed, including a secret resolved through a
+    /// [`SecretProvider`](crate::SecretProvider).
+    ///
+    /// This reads the files and resolves secrets on the calling thread; from
+    /// async code, call it through [`tokio::task::spawn_blocking`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files cannot be read, parsed, or validated; the
+    /// current configuration is kept in that case.
+    pub fn reload(&self) -> Result<ConfigReload> {
+        self.shared.reload()
+    }
+}
+
+impl<T> Shared<T>
+where
+    T: DeserializeOwned + Clone + Default + ValidateAppConfig + Send + Sync + 'static,
+{
+    fn state(&self) -> MutexGuard<'_, WatchState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reloads when any watched file changed since the last check.
+    fn poll(&self) {
+        {
+            let files = self.state().files.clone();
+            let stamps = stamp_files(&files);
+            let mut state = self.state();
+            if stamps == state.stamps {
+                return;
+            }
+            // Remember the new stamps even if the reload fails, so a broken file
+            // is reported once rather than on every poll.
+            state.stamps = stamps;
+        }
+        self.reload_logged();
+    }
+
+    fn reload_logged(&self) {
+        if let Err(e) = self.reload() {
+            tracing::error!(
+                error = %e,
+                env = %self.env,
+                "Failed to reload configuration; keeping the current configuration"
+            );
+        }
+    }
+
+    fn reload(&self) -> Result<ConfigReload> {
+        // Read the files and resolve secrets before taking the lock.
+        let (mut config, layered) = Config::<T>::load_layered(&self.dir, &self.env, env_vars())?;
+        {
+            let current = self.sender.borrow();
+            config.strict_keys = current.strict_keys;
+            config.app_renderer = current.app_renderer;
+        }
+        config.validate()?;
+        let files = watched_files(&self.dir, layered.files);
+        let stamps = stamp_files(&files);
+
+        let mut state = self.state();
+        state.files = files;
+        state.stamps = stamps;
+
+        let changed_keys = changed_keys(&state.table, &layered.table);
+        let secrets_changed = state.secrets != layered.secrets;
+        if changed_keys.is_empty() && !secrets_changed {
+            return Ok(ConfigReload::default());
+        }
+
+        let current = self.sender.borrow().clone();
+        let report = ConfigReload {
+            secrets_changed,
+            ..classify(changed_keys, &current, &config)
+        };
+
+        tracing::info!(
+            keys = ?report.changed_keys,
+            secrets_changed,
+            "Configuration reloaded"
+        );
+        if !report.restart_required_keys.is_empty() {
+            tracing::warn!(
+                keys = ?report.restart_required_keys,
+                "Configuration changes require a restart to take effect"
+            );
+        }
+        if report.changed_keys.iter().any(|k| k == "logging.filter")
+            && !logging::reload_filter(&config.logging)
+        {
+            tracing::debug!("logging.filter changed but tracing was not set up by axum-conf");
+        }
+
+        state.table = layered.table;
+        state.secrets = layered.secrets;
+        self.sender.send_replace(Arc::new(config));
+        Ok(report)
+    }
+}
+
+fn spawn_watch_task<T>(
+    runtime: &Handle,
+    shared: &Arc<Shared<T>>,
+    interval: Duration,
+) -> AbortOnDropHandle<()>
+where
+    T: DeserializeOwned + Clone + Default + ValidateAppConfig + Send + Sync + 'static,
+{
+    let shared = Arc::clone(shared);
+    AbortOnDropHandle::new(runtime.spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // The first tick completes immediately; the files were just loaded.
+        ticker.tick().await;
+
+        #[cfg(unix)]
+        let mut hangup =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok();
+
+        loop {
+            #[cfg(unix)]
+            let hangup_received = async {
+                if let Some(signal) = hangup.as_mut()
+                    && signal.recv().await.is_some()
+                {
+                    return;
+                }
+                std::future::pending().await
+            };
+            #[cfg(not(unix))]
+            let hangup_received = std::future::pending::<()>();
+
+            // Reading the files and resolving secrets blocks, so it runs off
+            // the async workers.
+            let shared = Arc::clone(&shared);
+            let _ = tokio::select! {
+                _ = ticker.tick() => tokio::task::spawn_blocking(move || shared.poll()).await,
+                () = hangup_received => {
+                    tracing::info!("SIGHUP received, reloading configuration");
+                    tokio::task::spawn_blocking(move || shared.reload_logged()).await
+                }
+            };
+        }
+    }))
+}
+
+/// The files to watch: those the configuration was built from, plus every
+/// name `base` may have (`base.toml`, `base.yaml`, ...) so that creating it is
+/// noticed too.
+fn watched_files(dir: &Path, mut files: Vec<PathBuf>) -> Vec<PathBuf> {
+    for base in layered::candidate_paths(dir, BASE_CONFIG_NAME) {
+        if !files.contains(&base) {
+            files.push(base);
+        }
+    }
+    files
+}
+
+fn stamp_files(files: &[PathBuf]) -> Vec<Option<FileStamp>> {
+    files
+        .iter()
+        .map(|path| {
+            // `metadata` follows symlinks, so a swapped ConfigMap target shows up
+            // as a change even though the link itself is untouched.
+            fs::metadata(path)
+                .ok()
+                .map(|meta| (meta.modified().ok(), meta.len()))
+        })
+        .collect()
+}
+
+/// Returns the dotted paths of every leaf value that differs between `old`
+/// and `new`, sorted. Arrays are compared as a whole.
+pub(crate) fn changed_keys(old: &Table, new: &Table) -> Vec<String> {
+    let mut keys = Vec::new();
+    diff_tables("", Some(old), Some(new), &mut keys);
+    keys.sort();
+    keys
+}
+
+fn diff_tables(prefix: &str, old: Option<&Table>, new: Option<&Table>, out: &mut Vec<String>) {
+    let empty = Table::new();
+    let (old, new) = (old.unwrap_or(&empty), new.unwrap_or(&empty));
+    let old_only = old.keys().filter(|k| !new.contains_key(*k));
+    for key in new.keys().chain(old_only) {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (old.get(key), new.get(key)) {
+            (Some(a), Some(b)) if a == b => {}
+            (a @ (Some(Value::Table(_)) | None), b @ (Some(Value::Table(_)) | None)) => {
+                diff_tables(
+                    &path,
+                    a.and_then(Value::as_table),
+                    b.and_then(Value::as_table),
+                    out,
+                );
+            }
+            _ => out.push(path),
+        }
+    }
+}
+
+/// Splits `changed_keys` into those applied live and those needing a restart.
+fn classify<T>(changed_keys: Vec<String>, old: &Config<T>, new: &Config<T>) -> ConfigReload
+where
+    T: Clone + Default,
+{
+    // Rate limiting is only installed when the limit is non-zero at startup, so
+    // switching it on or off needs a restart. The same goes for Basic Auth.
+    let rate_limit_toggled =
+        (old.http.max_requests_per_sec == 0) != (new.http.max_requests_per_sec == 0);
+    #[cfg(feature = "basic-auth")]
+    let basic_auth_toggled = old.http.basic_auth.is_some() != new.http.basic_auth.is_some();
+    #[cfg(not(feature = "basic-auth"))]
+    let basic_auth_toggled = false;
+
+    let restart_required_keys = changed_keys
+        .iter()
+        .filter(|key| {
+            if !is_library_key(key) {
+                return false;
+            }
+            if key.as_str() == "http.max_requests_per_sec" {
+                return rate_limit_toggled;
+            }
+            if basic_auth_toggled && key.starts_with("http.basic_auth.") {
+                return true;
+            }
+            !is_reloadable(key)
+        })
+        .cloned()
+        .collect();
+    ConfigReload {
+        changed_keys,
+        restart_required_keys,
+        secrets_changed: false,
+    }
+}
+
+fn is_reloadable(key: &str) -> bool {
+    RELOADABLE_KEYS.iter().any(|prefix| {
+        key.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+fn is_library_key(key: &str) -> bool {
+    let section = key.split('.').next().unwrap_or(key);
+    LIBRARY_SECTIONS.contains(&section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch `config/` directory removed on drop.
+    struct ConfigDir(PathBuf);
+
+    impl ConfigDir {
+        fn new() -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("axum-conf-watch-{}", uuid::Uuid::now_v7()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, name: &str, contents: &str) {
+            fs::write(self.0.join(format!("{name}.toml")), contents).unwrap();
+        }
+    }
+
+    impl Drop for ConfigDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn table(s: &str) -> Table {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn changed_keys_reports_leaf_paths() {
+        let old = table(
+            r#"
+            api_key = "a"
+            [http]
+            bind_port = 3000
+            max_requests_per_sec = 10
+            [http.cors]
+            allowed_origins = ["https://a.example"]
+            "#,
+        );
+        let new = table(
+            r#"
+            api_key = "a"
+            [http]
+            bind_port = 3000
+            max_requests_per_sec = 20
+            [http.cors]
+            allowed_origins = ["https://a.example", "https://b.example"]
+            [circuit_breaker.targets.payments]
+            failure_threshold = 3
+            "#,
+        );
+        assert_eq!(
+            changed_keys(&old, &new),
+            vec![
+                "circuit_breaker.targets.payments.failure_threshold",
+                "http.cors.allowed_origins",
+                "http.max_requests_per_sec",
+            ]
+        );
+        assert!(changed_keys(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn changed_keys_reports_removed_keys() {
+        let old = table("[http]\nbind_port = 3000\nbind_addr = \"0.0.0.0\"");
+        let new = table("[http]\nbind_port = 3000");
+        assert_eq!(changed_keys(&old, &new), vec!["http.bind_addr"]);
+    }
+
+    #[test]
+    fn classify_separates_reloadable_and_restart_keys() {
+        let config = Config::<()>::default().with_production(false);
+        let report = classify(
+            vec![
+                "cache_ttl_secs".to_string(),
+                "circuit_breaker.targets.db.failure_threshold".to_string(),
+                "http.basic_auth.mode".to_string(),
+                "http.basic_auth.users".to_string(),
+                "http.bind_addr".to_string(),
+                "http.max_requests_per_sec".to_string(),
+                "logging.filter".to_string(),
+                "logging.format".to_string(),
+            ],
+            &config,
+            &config,
+        );
+        assert_eq!(
+            report.restart_required_keys,
+            vec!["http.basic_auth.mode", "http.bind_addr", "logging.format"]
+        );
+        assert_eq!(report.changed_keys.len(), 8);
+    }
+
+    #[test]
+    fn enabling_rate_limiting_requires_restart() {
+        let mut old = Config::<()>::default();
+        old.http.max_requests_per_sec = 0;
+        let mut new = old.clone();
+        new.http.max_requests_per_sec = 50;
+        let report = classify(vec!["http.max_requests_per_sec".to_string()], &old, &new);
+        assert_eq!(
+            report.restart_required_keys,
+            vec!["http.max_requests_per_sec"]
+        );
+    }
+
+    #[cfg(feature = "basic-auth")]
+    #[test]
+    fn removing_basic_auth_requires_restart() {
+        let mut old = Config::<()>::default();
+        old.http.basic_auth = Some(Default::default());
+        let mut new = old.clone();
+        new.http.basic_auth = None;
+        let report = classify(
+            vec![
+                "http.basic_auth.users".to_string(),
+                "http.max_requests_per_sec".to_string(),
+            ],
+            &old,
+            &new,
+        );
+        assert_eq!(report.restart_required_keys, vec!["http.basic_auth.users"]);
+
+        // Changing the credentials alone is applied live.
+        let report = classify(vec!["http.basic_auth.users".to_string()], &old, &old);
+        assert!(report.restart_required_keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reload_publishes_changed_configuration() {
+        let dir = ConfigDir::new();
+        dir.write(
+            "base",
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\nmax_requests_per_sec = 10",
+        );
+        dir.write("dev", "[http]\nbind_port = 3000");
+
+        let watcher = ConfigWatcher::<()>::spawn(&dir.0, "dev").unwrap();
+        let mut rx = watcher.subscribe();
+        assert_eq!(watcher.current().http.max_requests_per_sec, 10);
+
+        assert!(watcher.reload().unwrap().is_empty());
+        assert!(!rx.has_changed().unwrap());
+
+        dir.write(
+            "base",
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\nmax_requests_per_sec = 20",
+        );
+        dir.write("dev", "[http]\nbind_port = 4000");
+        let report = watcher.reload().unwrap();
+        assert_eq!(
+            report.changed_keys,
+            vec!["http.bind_port", "http.max_requests_per_sec"]
+        );
+        assert_eq!(report.restart_required_keys, vec!["http.bind_port"]);
+
+        assert!(rx.has_changed().unwrap());
+        let config = rx.borrow_and_update().clone();
+        assert_eq!(config.http.max_requests_per_sec, 20);
+        assert_eq!(config.http.bind_port, 4000);
+    }
+
+    #[tokio::test]
+    async fn reload_publishes_rotated_provider_secrets() {
+        #[derive(Debug, Clone, Default, serde::Deserialize)]
+        struct AppSettings {
+            api_token: crate::Sensitive<String>,
+        }
+
+        impl ValidateAppConfig for AppSettings {}
+
+        let secrets = ConfigDir::new();
+        fs::create_dir_all(secrets.0.join("watch-test")).unwrap();
+        let secret_file = secrets.0.join("watch-test/api_token");
+        fs::write(&secret_file, "old-token").unwrap();
+        crate::register_secret_provider(crate::DirectorySecretProvider::new(&secrets.0));
+
+        let dir = ConfigDir::new();
+        dir.write(
+            "dev",
+            "api_token = \"secret://watch-test/api_token\"
+[http]
+max_payload_size_bytes = \"1KiB\"",
+        );
+        let watcher = ConfigWatcher::<AppSettings>::spawn(&dir.0, "dev").unwrap();
+        let mut rx = watcher.subscribe();
+        assert_eq!(watcher.current().app.api_token.expose_secret(), "old-token");
+
+        assert!(watcher.reload().unwrap().is_empty());
+        assert!(!rx.has_changed().unwrap());
+
+        // The configuration file is untouched; only the secret behind it rotates.
+        fs::write(&secret_file, "new-token").unwrap();
+        let report = watcher.reload().unwrap();
+        assert!(report.secrets_changed);
+        assert!(report.changed_keys.is_empty());
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(
+            rx.borrow_and_update().app.api_token.expose_secret(),
+            "new-token"
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_reload_keeps_current_configuration() {
+        let dir = ConfigDir::new();
+        dir.write("dev", "[http]\nmax_payload_size_bytes = \"1KiB\"");
+        let watcher = ConfigWatcher::<()>::spawn(&dir.0, "dev").unwrap();
+
+        dir.write("dev", "[http]\nmax_payload_size_bytes = ");
+        assert!(watcher.reload().is_err());
+        assert_eq!(watcher.current().http.max_payload_size_bytes.as_u64(), 1024);
+    }
+
+    #[tokio::test]
+    async fn reloads_keep_validating_the_app_settings() {
+        use crate::config::ValidationReport;
+
+        #[derive(Debug, Clone, Default, serde::Deserialize)]
+        struct AppSettings {
+            retries: u32,
+        }
+
+        impl ValidateAppConfig for AppSettings {
+            fn validation_report(&self) -> ValidationReport {
+                let mut report = ValidationReport::new();
+                if self.retries > 10 {
+                    report.error("retries", Error::config("must be at most 10"));
+                }
+                report
+            }
+        }
+
+        let dir = ConfigDir::new();
+        dir.write(
+            "dev",
+            "retries = 3
+[http]
+max_payload_size_bytes = \"1KiB\"",
+        );
+        let watcher = ConfigWatcher::<AppSettings>::spawn(&dir.0, "dev").unwrap();
+
+        dir.write(
+            "dev",
+            "retries = 50
+[http]
+max_payload_size_bytes = \"1KiB\"",
+        );
+        let err = watcher.reload().unwrap_err();
+        assert_eq!(err.to_string(), "retries: must be at most 10");
+        assert_eq!(watcher.current().app.retries, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_picks_up_file_changes() {
+        let dir = ConfigDir::new();
+        dir.write(
+            "dev",
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\nmax_requests_per_sec = 10",
+        );
+        let watcher = ConfigWatcher::<()>::spawn(&dir.0, "dev")
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(100));
+        let mut rx = watcher.subscribe();
+
+        // A different length guarantees a new stamp even on coarse-mtime filesystems.
+        dir.write(
+            "dev",
+            "[http]\nmax_payload_size_bytes = \"1KiB\"\nmax_requests_per_sec = 100",
+        );
+        tokio::time::timeout(Duration::from_secs(5), rx.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(rx.borrow().http.max_requests_per_sec, 100);
+    }
+
+    #[test]
+    fn spawn_requires_a_runtime() {
+        let dir = ConfigDir::new();
+        dir.write("dev", "[http]\nmax_payload_size_bytes = \"1KiB\"");
+        assert!(ConfigWatcher::<()>::spawn(&dir.0, "dev").is_err());
+    }
+}
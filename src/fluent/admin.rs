@@ -0,0 +1,73 @@
+//! The admin listener: health and metrics routes on a port of their own.
+//!
+//! With `[http.admin]`, the `setup_*` methods for the liveness, readiness and
+//! metrics routes add them to a separate router, which
+//! [`FluentRouter::start`](super::FluentRouter::start) serves on the admin
+//! address alongside the public listener.
+
+use super::router::FluentRouter;
+use super::server::{ServeFuture, Server, connect_info};
+
+use {
+    crate::{Result, config::HttpConfig},
+    axum::{Router, routing::MethodRouter},
+    http::StatusCode,
+    std::{future::Future, net::SocketAddr},
+    tokio::net::TcpListener,
+    tokio_util::sync::CancellationToken,
+    tower_http::{catch_panic::CatchPanicLayer, timeout::TimeoutLayer},
+};
+
+impl<State> FluentRouter<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    /// Adds an operational route (health, metrics) to the admin router
+    /// when `[http.admin]` is configured, or to the public router otherwise.
+    pub(crate) fn route_operational(&mut self, path: &str, route: MethodRouter<State>) {
+        match self.admin.take() {
+            Some(admin) => self.admin = Some(admin.route(path, route)),
+            None => self.inner = std::mem::take(&mut self.inner).route(path, route),
+        }
+    }
+}
+
+/// Binds `[http.admin]` and returns the future serving `router` on it until
+/// `signal` resolves (or `abort` is cancelled), or `None` when no admin
+/// listener is configured.
+///
+/// The admin routes only get panic recovery and the request timeout; the rest
+/// of the public middleware stack does not apply to them.
+pub(crate) async fn serve_admin<F>(
+    http: &HttpConfig,
+    router: Option<Router>,
+    signal: F,
+    abort: CancellationToken,
+) -> Result<Option<ServeFuture>>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let (Some(admin), Some(mut router)) = (&http.admin, router) else {
+        return Ok(None);
+    };
+
+    if let Some(timeout) = http.request_timeout {
+        router = router.layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            timeout,
+        ));
+    }
+    let router = router.layer(CatchPanicLayer::new());
+
+    let bind_addr = admin.full_bind_addr(&http.bind_addr);
+    let listener = TcpListener::bind(&bind_addr).await?;
+    tracing::info!("Admin endpoints bound to {}", listener.local_addr()?);
+
+    Ok(Some(Server::new(&http.connection).serve(
+        listener,
+        router,
+        |_, addr: &SocketAddr| connect_info(*addr),
+        signal,
+        abort,
+    )))
+}
@@ -1,4 +1,4 @@
-//! Authentication middleware: OIDC, Basic Auth, and user span recording.
+//! Authentication middleware: OIDC, Basic Auth, client certificates, and user span recording.
 
 use super::router::FluentRouter;
 use super::user_span;
@@ -13,7 +13,7 @@ use std::sync::Arc;
 use std::sync::Arc;
 
 #[cfg(feature = "basic-auth")]
-use super::basic_auth;
+use super::{basic_auth, reload::LiveValue};
 
 impl<State> FluentRouter<State>
 where
@@ -217,12 +217,14 @@ where
                 passthrough,
                 "BasicAuth middleware enabled"
             );
-            let config = Arc::new(basic_auth_config.clone());
+            // Read per request so reloaded users and API keys apply immediately.
+            let config = LiveValue::new(Arc::new(basic_auth_config.clone()));
+            let _ = self.live.basic_auth.set(config.clone());
 
             self.inner = self
                 .inner
                 .route_layer(axum::middleware::from_fn(move |request, next| {
-                    let config = Arc::clone(&config);
+                    let config = config.get();
                     basic_auth::basic_auth_middleware(config, passthrough, request, next)
                 }));
         }
@@ -302,6 +304,51 @@ where
         self
     }
 
+    /// Sets up client-certificate (mutual TLS) authentication.
+    ///
+    /// When configured, maps the client certificate verified during the TLS
+    /// handshake to an [`AuthenticatedIdentity`](crate::AuthenticatedIdentity)
+    /// with [`AuthMethod::ClientCertificate`](crate::AuthMethod::ClientCertificate).
+    /// Certificates are only available when `start` serves HTTPS with
+    /// `[http.tls] client_ca_file` set; without one, requests pass through
+    /// without an identity.
+    ///
+    /// # Configuration
+    ///
+    /// ```toml
+    /// [http.tls]
+    /// cert_file = "/etc/tls/tls.crt"
+    /// key_file = "/etc/tls/tls.key"
+    /// client_ca_file = "/etc/tls/mesh-ca.crt"
+    ///
+    /// [http.client_cert_auth]
+    /// user = "cn"
+    /// groups = ["ou"]
+    ///
+    /// [[http.client_cert_auth.role_mappings]]
+    /// attribute = "san_uri"
+    /// value = "spiffe://cluster.local/ns/shop/sa/orders"
+    /// roles = ["orders-writer"]
+    /// ```
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn setup_client_cert_auth(mut self) -> Self {
+        if let Some(client_cert_config) = &self.config.http.client_cert_auth
+            && self.is_middleware_enabled(HttpMiddleware::ClientCertAuth)
+        {
+            tracing::trace!(user = ?client_cert_config.user, "ClientCertAuth middleware enabled");
+            let config = std::sync::Arc::new(client_cert_config.clone());
+
+            self.inner = self
+                .inner
+                .route_layer(axum::middleware::from_fn(move |request, next| {
+                    let config = std::sync::Arc::clone(&config);
+                    super::client_cert::client_cert_auth_middleware(config, request, next)
+                }));
+        }
+        self
+    }
+
     /// Sets up browser login redirect middleware.
     ///
     /// When OIDC auth code flow is enabled with `auto_redirect_to_login = true`,
@@ -1,14 +1,26 @@
 //! Orchestration and router delegation: setup_middleware(), start(), layer(), route(), etc.
 
+use super::admin::serve_admin;
+use super::handle::ServerHandle;
+use super::listener::ServerListener;
 use super::router::FluentRouter;
-use super::shutdown::{ShutdownNotifier, ShutdownPhase};
-use crate::Result;
+use super::shutdown::{ShutdownNotifier, ShutdownPhase, hooks_reserve, run_shutdown_hooks};
+use super::tasks::join_tasks;
+use crate::{Error, Result};
 
 use {
     axum::{Router, body::Body, routing::Route},
     http::Request,
-    std::{convert::Infallible, env, net::SocketAddr, time::Duration},
-    tokio::signal,
+    std::{
+        convert::Infallible,
+        env,
+        future::Future,
+        net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+        sync::{Arc, OnceLock},
+        time::Duration,
+    },
+    tokio::{signal, time::Instant},
+    tokio_util::sync::CancellationToken,
     tower::{Layer, Service},
 };
 
@@ -58,9 +70,9 @@ where
     ///
     /// The current order (innermost → outermost) is grouped as:
     ///
-    /// 1. **Authentication & routing** — protected static files, OIDC / Basic-Auth / proxy-header
-    ///    authentication (applied as `route_layer`s), public static files, the OIDC login routes,
-    ///    and session handling.
+    /// 1. **Authentication & routing** — protected static files, the effective-config route,
+    ///    OIDC / Basic-Auth / proxy-header / client-certificate authentication (applied as
+    ///    `route_layer`s), public static files, the OIDC login routes, and session handling.
     /// 2. **Request shaping** — deduplication, concurrency limit, payload limit, (de)compression,
     ///    path normalization, sensitive-header redaction, and API versioning.
     /// 3. **Cross-cutting** — CORS, security headers (Helmet), logging, and metrics.
@@ -141,52 +153,58 @@ where
         // Protected static files must be added BEFORE auth so route_layer applies to them.
         let router = self.setup_protected_files()?; // position 1
 
+        // The effective-config route likewise sits behind the auth route_layers.
+        let router = router.setup_debug_config()?; // position 2
+
         // Browser login redirect is the innermost route_layer so it runs AFTER all
         // auth middleware has resolved identity.
         #[cfg(feature = "keycloak")]
-        let router = router.setup_browser_login_redirect(); // position 2
+        let router = router.setup_browser_login_redirect(); // position 3
 
         #[cfg(feature = "keycloak")]
-        let router = router.setup_oidc().await?; // position 3 (route_layer)
+        let router = router.setup_oidc().await?; // position 4 (route_layer)
 
         #[cfg(feature = "basic-auth")]
-        let router = router.setup_basic_auth()?; // position 4 (route_layer)
+        let router = router.setup_basic_auth()?; // position 5 (route_layer)
+
+        let router = router.setup_proxy_oidc(); // position 6 (route_layer)
 
-        let router = router.setup_proxy_oidc(); // position 5 (route_layer)
+        #[cfg(feature = "tls")]
+        let router = router.setup_client_cert_auth(); // position 7 (route_layer)
 
         // Public static files added AFTER auth so they're accessible without authentication.
-        let router = router.setup_public_files()?; // position 6
+        let router = router.setup_public_files()?; // position 8
 
         // OIDC auth code flow routes (login/callback/logout) - public, after auth middleware.
         #[cfg(feature = "keycloak")]
-        let router = router.setup_oidc_routes().await?; // position 7
+        let router = router.setup_oidc_routes().await?; // position 9
 
-        let router = router.setup_user_span(); // position 8 (record username on the span)
+        let router = router.setup_user_span(); // position 10 (record username on the span)
 
         // Session handling must wrap auth middleware so sessions are established
         // before session_to_identity middleware reads them.
         #[cfg(feature = "session")]
-        let router = router.setup_session_handling().await?; // position 9
+        let router = router.setup_session_handling().await?; // position 11
 
         let router = router
-            .setup_deduplication() // position 10
-            .setup_concurrency_limit() // position 11
-            .setup_max_payload_size() // position 12
-            .setup_compression() // position 13
-            .setup_path_normalization() // position 14
-            .setup_sensitive_headers() // position 15
-            .setup_api_versioning(default_api_version) // position 16
-            .setup_cors() // position 17
-            .setup_helmet() // position 18
-            .setup_logging() // position 19
-            .setup_metrics() // position 20
-            .setup_readiness() // position 21 (benefits from timeout/rate limiting)
-            .setup_timeout() // position 22
-            .setup_rate_limiting() // position 23
-            .setup_request_id() // position 24 (early so all requests get IDs)
-            .setup_liveness() // position 25 (always accessible, very early)
-            .setup_catch_panic() // position 26 (outermost - panic recovery)
-            .setup_fallback_files()?; // position 27 (must be last)
+            .setup_deduplication() // position 12
+            .setup_concurrency_limit() // position 13
+            .setup_max_payload_size() // position 14
+            .setup_compression() // position 15
+            .setup_path_normalization() // position 16
+            .setup_sensitive_headers() // position 17
+            .setup_api_versioning(default_api_version) // position 18
+            .setup_cors() // position 19
+            .setup_helmet() // position 20
+            .setup_logging() // position 21
+            .setup_metrics() // position 22
+            .setup_readiness() // position 23 (benefits from timeout/rate limiting)
+            .setup_timeout() // position 24
+            .setup_rate_limiting() // position 25
+            .setup_request_id() // position 26 (early so all requests get IDs)
+            .setup_liveness() // position 27 (always accessible, very early)
+            .setup_catch_panic() // position 28 (outermost - panic recovery)
+            .setup_fallback_files()?; // position 29 (must be last)
 
         Ok(router)
     }
@@ -196,17 +214,45 @@ where
     /// The server supports both HTTP/1.1 and HTTP/2 protocols automatically.
     /// HTTP/2 will be used when clients request it via ALPN negotiation.
     ///
+    /// With the `tls` feature and an `[http.tls]` section, the server speaks
+    /// HTTPS, reloading its certificate from disk when the files change (see
+    /// [`HttpTlsConfig`](crate::HttpTlsConfig)).
+    ///
+    /// The server listens on `bind_addr:bind_port`, or on the Unix socket or
+    /// socket-activated listener selected by `[http] bind` (see
+    /// [`BindTarget`](crate::BindTarget)). Use
+    /// [`start_with_listener`](Self::start_with_listener) to serve on a
+    /// listener you opened yourself, or [`spawn`](Self::spawn) to run the
+    /// server in the background and stop it through a handle.
+    ///
+    /// With `[http.admin]`, the liveness, readiness and metrics routes are
+    /// served on a second listener at the admin address (see
+    /// [`HttpAdminConfig`](crate::HttpAdminConfig)), which shuts down together
+    /// with the public one.
+    ///
     /// # Graceful Shutdown
     ///
     /// When a shutdown signal is received (SIGTERM or SIGINT), the server:
     ///
     /// 1. Emits [`ShutdownPhase::Initiated`] to all subscribers
-    /// 2. Triggers the cancellation token (stopping background tasks)
-    /// 3. Stops accepting new connections
-    /// 4. Emits [`ShutdownPhase::GracePeriodStarted`] with the configured timeout
-    /// 5. Waits for in-flight requests to complete (up to `shutdown_timeout`)
-    /// 6. Emits [`ShutdownPhase::GracePeriodEnded`] if timeout expires
-    /// 7. Exits
+    /// 2. Triggers the cancellation token (stopping background tasks) and
+    ///    answers 503 on the readiness route
+    /// 3. Keeps serving for `shutdown_pre_stop_delay`, if set, so load
+    ///    balancers stop routing new traffic to the instance
+    /// 4. Emits [`ShutdownPhase::GracePeriodStarted`] with the time the
+    ///    connections have to drain: `shutdown_timeout`, less the part
+    ///    reserved for the [`on_shutdown`](Self::on_shutdown) hooks (the sum
+    ///    of their timeouts, at most a quarter of `shutdown_timeout`)
+    /// 5. Stops accepting new connections
+    /// 6. Waits for in-flight requests to complete, for at most that time
+    /// 7. Emits [`ShutdownPhase::GracePeriodEnded`] if that time runs out, then
+    ///    closes the remaining connections and drops their requests, logging
+    ///    and counting them in `http_requests_aborted_total`
+    /// 8. Waits for the [`spawn_task`](Self::spawn_task) tasks to stop, in
+    ///    what is left of that time
+    /// 9. Runs the `on_shutdown` hooks in the rest of `shutdown_timeout`,
+    ///    which includes their reserve even when the drain used up its share
+    /// 10. Exits
     ///
     /// If all connections drain before the timeout, shutdown completes early
     /// without waiting for the full timeout duration.
@@ -236,10 +282,115 @@ where
     where
         State: Clone + Send + Sync + 'static,
     {
-        let bind_addr = self.config.http.full_bind_addr();
-        let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+        let listener = ServerListener::bind(&self.config.http).await?;
+        self.start_with_listener(listener).await
+    }
+
+    /// Starts the HTTP server on a listener opened by the caller.
+    ///
+    /// Behaves like [`start`](Self::start), including graceful shutdown, but
+    /// serves on `listener` instead of binding the address in `[http]`. Pass a
+    /// tokio `TcpListener` or, on Unix, `UnixListener`; `[http.tls]` applies
+    /// to TCP listeners.
+    ///
+    /// ```rust,no_run
+    /// use axum_conf::{Config, FluentRouter};
+    /// use tokio::net::TcpListener;
+    ///
+    /// # async fn example() -> axum_conf::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let port = listener.local_addr()?.port();
+    /// tracing::info!("Serving on port {port}");
+    ///
+    /// FluentRouter::without_state(Config::<()>::default())?
+    ///     .setup_middleware()
+    ///     .await?
+    ///     .start_with_listener(listener)
+    ///     .await
+    /// # }
+    /// ```
+    pub async fn start_with_listener(self, listener: impl Into<ServerListener>) -> Result<()>
+    where
+        State: Clone + Send + Sync + 'static,
+    {
+        self.serve(listener.into(), std::future::pending()).await
+    }
+
+    /// Starts the server in the background and returns a handle to it.
+    ///
+    /// The server binds the address in `[http]` like [`start`](Self::start),
+    /// so with `bind_port = 0` the operating system picks a free port, which
+    /// [`ServerHandle::local_addr`] reports. [`ServerHandle::shutdown`] runs
+    /// the same graceful shutdown as a SIGTERM, emitting the
+    /// [`ShutdownPhase`]s to subscribers. This makes it possible to test the
+    /// full middleware stack over real sockets:
+    ///
+    /// ```rust,no_run
+    /// use axum_conf::{Config, FluentRouter};
+    ///
+    /// # async fn example() -> axum_conf::Result<()> {
+    /// let mut config = Config::<()>::default();
+    /// config.http.bind_port = 0;
+    ///
+    /// let server = FluentRouter::without_state(config)?
+    ///     .setup_middleware()
+    ///     .await?
+    ///     .spawn()
+    ///     .await?;
+    /// let health = reqwest::get(format!("{}/live", server.base_url())).await;
+    /// server.shutdown().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The server must listen on TCP; a Unix socket `[http] bind` is rejected.
+    /// Dropping the handle also shuts the server down, without waiting for it.
+    pub async fn spawn(self) -> Result<ServerHandle>
+    where
+        State: Clone + Send + Sync + 'static,
+    {
+        let listener = ServerListener::bind(&self.config.http).await?;
+        let Some(local_addr) = listener.tcp_addr() else {
+            return Err(Error::config(
+                "FluentRouter::spawn() serves on TCP only; set [http] bind_addr and bind_port.",
+            ));
+        };
+        let mut local_addr = local_addr?;
+        // Clients cannot connect to the unspecified address; the loopback
+        // address of the same family reaches a server listening on it.
+        if local_addr.ip().is_unspecified() {
+            local_addr.set_ip(match local_addr {
+                SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+            });
+        }
+        #[cfg(feature = "tls")]
+        let scheme = if self.config.http.tls.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+        #[cfg(not(feature = "tls"))]
+        let scheme = "http";
+
+        let stop = CancellationToken::new();
+        let shutdown_notifier = self.shutdown_notifier.clone();
+        let task = tokio::spawn(self.serve(listener, stop.clone().cancelled_owned()));
+        Ok(ServerHandle::new(
+            local_addr,
+            scheme,
+            stop,
+            shutdown_notifier,
+            task,
+        ))
+    }
 
-        tracing::info!("Bound to {}", &bind_addr);
+    /// Serves on `listener` until a shutdown signal or `requested` resolves,
+    /// then shuts down gracefully.
+    async fn serve<F>(self, listener: ServerListener, requested: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         tracing::info!("Waiting for connections");
         tracing::info!("Max req/s: {}", self.config.http.max_requests_per_sec);
 
@@ -248,56 +399,111 @@ where
         #[cfg(feature = "opentelemetry")]
         let otel_provider = self.otel_provider;
 
-        let service = self
-            .inner
-            .with_state(self.state)
-            .into_make_service_with_connect_info::<SocketAddr>();
+        let shutdown_hooks = self.shutdown_hooks;
+        let tasks = self.tasks;
+        let admin_router = self.admin.map(|admin| admin.with_state(self.state.clone()));
+        let router = self.inner.with_state(self.state);
 
         let shutdown_timeout = self.config.http.shutdown_timeout;
+        let pre_stop_delay = self.config.http.shutdown_pre_stop_delay;
+        // The hooks' reserve comes out of the grace period, so shutdown as a
+        // whole still fits in `shutdown_timeout`.
+        let drain_timeout = shutdown_timeout - hooks_reserve(&shutdown_hooks, shutdown_timeout);
         let shutdown_notifier = self.shutdown_notifier.clone();
 
         // Subscribe to shutdown notifications to know when signal is received
         let mut shutdown_rx = shutdown_notifier.subscribe();
 
-        let serve_future = axum::serve(listener, service).with_graceful_shutdown(
-            shutdown_signal_with_notifications(shutdown_timeout, shutdown_notifier.clone()),
-        );
+        // Cancelled once the pre-stop delay has passed and the listeners
+        // should stop accepting, which is when the grace period starts.
+        let stop_accepting = CancellationToken::new();
+        let grace_started = Arc::new(OnceLock::new());
+        let signal = {
+            let notifier = shutdown_notifier.clone();
+            let stop_accepting = stop_accepting.clone();
+            let grace_started = grace_started.clone();
+            async move {
+                shutdown_signal_with_notifications(
+                    drain_timeout,
+                    pre_stop_delay,
+                    notifier,
+                    requested,
+                )
+                .await;
+                let _ = grace_started.set(Instant::now());
+                stop_accepting.cancel();
+            }
+        };
+        // Cancelled when the grace period ends, closing the connections left.
+        let abort = CancellationToken::new();
+        let serve_future = listener.serve(&self.config.http, router, signal, abort.clone())?;
+
+        // The admin listener keeps serving the failing readiness probe during
+        // the pre-stop delay, then stops accepting and drains with the public one.
+        let admin_future = serve_admin(
+            &self.config.http,
+            admin_router,
+            stop_accepting.cancelled_owned(),
+            abort.clone(),
+        )
+        .await?;
+        let serve_future = async move {
+            let admin = async {
+                match admin_future {
+                    Some(admin_future) => admin_future.await,
+                    None => Ok(()),
+                }
+            };
+            let (public, admin) = tokio::join!(serve_future, admin);
+            public.and(admin)
+        };
+        tokio::pin!(serve_future);
 
         // Wait for graceful shutdown with timeout enforcement.
-        // The timeout only starts AFTER a shutdown signal is received, not immediately.
+        // The timeout only starts once the grace period starts, after the
+        // shutdown signal and the pre-stop delay, not immediately.
         // If connections drain before the timeout, we complete early.
-        // If the timeout expires first, we emit GracePeriodEnded and stop waiting:
-        // dropping `serve_future` stops accepting connections, but in-flight handler
-        // tasks already spawned by hyper are abandoned, not actively cancelled.
+        // If the timeout expires first, we emit GracePeriodEnded and close the
+        // remaining connections, dropping the requests still in flight.
         tokio::select! {
-            result = serve_future => {
+            result = &mut serve_future => {
                 // Server shut down gracefully (connections drained)
                 tracing::info!("Graceful shutdown completed");
                 result?;
             }
             _ = async {
-                // Wait for shutdown to be initiated before starting the timeout
+                // Wait for the grace period to start before starting the timeout
                 loop {
                     match shutdown_rx.recv().await {
-                        Ok(ShutdownPhase::Initiated) => break,
+                        Ok(ShutdownPhase::GracePeriodStarted { .. }) => break,
                         Ok(_) => continue,
                         Err(_) => return, // Channel closed
                     }
                 }
-                // Now start the timeout (only after signal received)
-                tokio::time::sleep(shutdown_timeout).await;
+                // Now start the timeout (only after the listeners stop accepting)
+                tokio::time::sleep(drain_timeout).await;
             } => {
-                // Timeout expired after shutdown was initiated: stop waiting and
-                // let the server stop accepting. In-flight requests still running
-                // past the grace period are abandoned rather than force-cancelled.
+                // Timeout expired after the grace period started: close the
+                // remaining connections, which drops their in-flight requests.
                 tracing::warn!(
-                    "Graceful shutdown timeout expired; stopping the server \
-                     (in-flight requests may be abandoned)"
+                    "Graceful shutdown timeout expired; closing the remaining connections"
                 );
                 shutdown_notifier.emit(ShutdownPhase::GracePeriodEnded);
+                abort.cancel();
+                serve_future.await?;
             }
         }
 
+        // Application cleanup: the background tasks (cancelled on `Initiated`)
+        // stop within what is left of the grace period, then the hooks run
+        // until `shutdown_timeout` is up, which leaves them at least their
+        // reserve even when the drain used up the grace period.
+        let grace_started = grace_started.get().copied().unwrap_or_else(Instant::now);
+        join_tasks(tasks, grace_started + drain_timeout).await;
+        if !shutdown_hooks.is_empty() {
+            run_shutdown_hooks(shutdown_hooks, grace_started + shutdown_timeout).await;
+        }
+
         // Flush any buffered OpenTelemetry spans before exiting. The batch
         // exporter would otherwise drop un-flushed spans at process exit.
         #[cfg(feature = "opentelemetry")]
@@ -581,7 +787,14 @@ where
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// Background tasks started by [`spawn_task`](Self::spawn_task) or
+    /// [`with_config_updates`](Self::with_config_updates) keep running until
+    /// the [`cancellation_token`](Self::cancellation_token) is cancelled.
     pub fn into_inner(self) -> Router<State> {
+        for task in self.tasks {
+            task.detach();
+        }
         self.inner
     }
 }
@@ -589,10 +802,12 @@ where
 /// Returns a signal handler that emits shutdown phase notifications.
 ///
 /// This function:
-/// 1. Waits for SIGTERM or SIGINT (Ctrl+C)
-/// 2. Emits [`ShutdownPhase::Initiated`] (and triggers the cancellation token)
-/// 3. Emits [`ShutdownPhase::GracePeriodStarted`] with the configured timeout
-/// 4. Returns immediately to let axum start graceful shutdown
+/// 1. Waits for SIGTERM or SIGINT (Ctrl+C), or for `requested` to resolve
+/// 2. Emits [`ShutdownPhase::Initiated`] (and triggers the cancellation token,
+///    which makes the readiness route answer 503)
+/// 3. Waits `pre_stop_delay`, while the server keeps serving
+/// 4. Emits [`ShutdownPhase::GracePeriodStarted`] with the configured timeout
+/// 5. Returns immediately to let the server stop accepting and drain
 ///
 /// The grace period timeout is enforced by the caller (see [`FluentRouter::start`]),
 /// which wraps the serve call with a timeout. When connections drain before the
@@ -606,7 +821,9 @@ where
 /// handlers cannot be installed (e.g., in restricted environments).
 pub(crate) async fn shutdown_signal_with_notifications(
     timeout: Duration,
+    pre_stop_delay: Duration,
     notifier: ShutdownNotifier,
+    requested: impl Future<Output = ()>,
 ) {
     let ctrl_c = async {
         match signal::ctrl_c().await {
@@ -642,6 +859,7 @@ pub(crate) async fn shutdown_signal_with_notifications(
     tokio::select! {
         _ = ctrl_c => {},
         _ = terminate => {},
+        _ = requested => tracing::debug!("Shutdown requested through the server handle"),
     }
 
     // Phase 1: Initiated - signal received, cancellation token triggered
@@ -655,6 +873,12 @@ pub(crate) async fn shutdown_signal_with_notifications(
         subscriber_count
     );
 
+    // Keep serving while load balancers notice the failing readiness probe.
+    if !pre_stop_delay.is_zero() {
+        tracing::info!("Readiness now fails; closing the listener in {pre_stop_delay:?}");
+        tokio::time::sleep(pre_stop_delay).await;
+    }
+
     // Phase 2: Grace period started - in-flight requests draining
     // Return immediately to let axum start graceful shutdown.
     // The timeout is enforced by the caller wrapping the serve call.
@@ -0,0 +1,195 @@
+//! Client-certificate (mutual TLS) authentication middleware.
+//!
+//! The TLS listener verifies client certificates during the handshake and
+//! parses the leaf once per connection into a [`ClientCertificate`], which it
+//! attaches to every request on that connection. The middleware maps it to an
+//! [`AuthenticatedIdentity`] according to `[http.client_cert_auth]`.
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use std::sync::Arc;
+use x509_parser::{certificate::X509Certificate, extensions::GeneralName, prelude::FromDer};
+
+use crate::{AuthMethod, AuthenticatedIdentity, CertAttribute, HttpClientCertAuthConfig};
+
+/// The identity-relevant attributes of a verified client certificate.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ClientCertificate {
+    cn: Vec<String>,
+    ou: Vec<String>,
+    san_dns: Vec<String>,
+    san_uri: Vec<String>,
+    san_email: Vec<String>,
+}
+
+impl ClientCertificate {
+    /// Parses a DER-encoded certificate, or `None` when it is malformed.
+    pub(crate) fn from_der(der: &[u8]) -> Option<Self> {
+        let (_, certificate) = X509Certificate::from_der(der).ok()?;
+        let subject = certificate.subject();
+        let strings = |values: &mut dyn Iterator<Item = Option<&str>>| {
+            values.flatten().map(str::to_string).collect::<Vec<_>>()
+        };
+
+        let mut parsed = Self {
+            cn: strings(&mut subject.iter_common_name().map(|v| v.as_str().ok())),
+            ou: strings(&mut subject.iter_organizational_unit().map(|v| v.as_str().ok())),
+            ..Self::default()
+        };
+        if let Ok(Some(san)) = certificate.subject_alternative_name() {
+            for name in &san.value.general_names {
+                match name {
+                    GeneralName::DNSName(dns) => parsed.san_dns.push(dns.to_string()),
+                    GeneralName::URI(uri) => parsed.san_uri.push(uri.to_string()),
+                    GeneralName::RFC822Name(email) => parsed.san_email.push(email.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        Some(parsed)
+    }
+
+    /// The values of `attribute`, in certificate order.
+    fn values(&self, attribute: CertAttribute) -> &[String] {
+        match attribute {
+            CertAttribute::Cn => &self.cn,
+            CertAttribute::Ou => &self.ou,
+            CertAttribute::SanDns => &self.san_dns,
+            CertAttribute::SanUri => &self.san_uri,
+            CertAttribute::SanEmail => &self.san_email,
+        }
+    }
+}
+
+/// Client-certificate authentication middleware function.
+///
+/// Requests without a certificate (or whose certificate lacks the configured
+/// user attribute) pass through without an identity; the role extractors then
+/// answer 401.
+pub(crate) async fn client_cert_auth_middleware(
+    config: Arc<HttpClientCertAuthConfig>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let identity = request
+        .extensions()
+        .get::<Arc<ClientCertificate>>()
+        .and_then(|certificate| extract_identity(&config, certificate));
+
+    if let Some(identity) = identity {
+        tracing::debug!("Request authenticated via client certificate");
+        request.extensions_mut().insert(Arc::new(identity));
+    }
+
+    next.run(request).await
+}
+
+/// Maps certificate attributes to an identity if the user attribute is present.
+fn extract_identity(
+    config: &HttpClientCertAuthConfig,
+    certificate: &ClientCertificate,
+) -> Option<AuthenticatedIdentity> {
+    let user = certificate.values(config.user).first()?.clone();
+
+    let collect = |attributes: &[CertAttribute]| -> Vec<String> {
+        let mut values: Vec<String> = Vec::new();
+        for value in attributes
+            .iter()
+            .flat_map(|attribute| certificate.values(*attribute))
+        {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+        values
+    };
+
+    let groups = collect(&config.groups);
+    let mut roles = collect(&config.roles);
+    for mapping in &config.role_mappings {
+        if certificate
+            .values(mapping.attribute)
+            .contains(&mapping.value)
+        {
+            for role in &mapping.roles {
+                if !roles.contains(role) {
+                    roles.push(role.clone());
+                }
+            }
+        }
+    }
+
+    Some(AuthenticatedIdentity {
+        method: AuthMethod::ClientCertificate,
+        user,
+        email: certificate.san_email.first().cloned(),
+        groups,
+        roles,
+        preferred_username: None,
+        access_token: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClientCertRoleMapping;
+    use rustls::pki_types::{CertificateDer, pem::PemObject};
+
+    fn fixture() -> ClientCertificate {
+        let pem = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls/client.pem");
+        let der = CertificateDer::from_pem_file(pem).unwrap();
+        ClientCertificate::from_der(&der).unwrap()
+    }
+
+    #[test]
+    fn certificate_attributes_are_parsed() {
+        let certificate = fixture();
+        assert_eq!(certificate.values(CertAttribute::Cn), ["orders-service"]);
+        assert_eq!(
+            certificate.values(CertAttribute::Ou),
+            ["payments", "billing"]
+        );
+        assert_eq!(
+            certificate.values(CertAttribute::SanDns),
+            ["orders.internal"]
+        );
+        assert_eq!(
+            certificate.values(CertAttribute::SanUri),
+            ["spiffe://cluster.local/ns/shop/sa/orders"]
+        );
+        assert!(ClientCertificate::from_der(b"not a certificate").is_none());
+    }
+
+    #[test]
+    fn identity_is_mapped_from_configured_attributes() {
+        let mut config = HttpClientCertAuthConfig::default();
+        let identity = extract_identity(&config, &fixture()).unwrap();
+        assert_eq!(identity.method, AuthMethod::ClientCertificate);
+        assert_eq!(identity.user, "orders-service");
+        assert_eq!(identity.groups, ["payments", "billing"]);
+        assert!(identity.roles.is_empty());
+
+        config.user = CertAttribute::SanUri;
+        config.groups = vec![];
+        config.roles = vec![CertAttribute::Ou];
+        config.role_mappings = vec![
+            ClientCertRoleMapping {
+                attribute: CertAttribute::SanDns,
+                value: "orders.internal".into(),
+                roles: vec!["orders-writer".into(), "billing".into()],
+            },
+            ClientCertRoleMapping {
+                attribute: CertAttribute::Cn,
+                value: "inventory-service".into(),
+                roles: vec!["inventory-writer".into()],
+            },
+        ];
+        let identity = extract_identity(&config, &fixture()).unwrap();
+        assert_eq!(identity.user, "spiffe://cluster.local/ns/shop/sa/orders");
+        assert!(identity.groups.is_empty());
+        assert_eq!(identity.roles, ["payments", "billing", "orders-writer"]);
+
+        config.user = CertAttribute::SanEmail;
+        assert!(extract_identity(&config, &fixture()).is_none());
+    }
+}
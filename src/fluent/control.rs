@@ -10,11 +10,112 @@ use {
 
 #[cfg(feature = "rate-limiting")]
 use {
-    std::time::Duration,
+    super::reload::LiveValue,
+    axum::{body::Body, extract::ConnectInfo},
+    governor::middleware::NoOpMiddleware,
+    http::Request,
+    std::{
+        future::Future,
+        net::IpAddr,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll},
+        time::Duration,
+    },
     tokio_util::task::AbortOnDropHandle,
-    tower_governor::{GovernorLayer, governor::GovernorConfigBuilder},
+    tower::{Layer, Service},
+    tower_governor::{
+        governor::{Governor, GovernorConfig, GovernorConfigBuilder, SharedRateLimiter},
+        key_extractor::PeerIpKeyExtractor,
+    },
 };
 
+/// Per-IP governor configuration used by [`FluentRouter::setup_rate_limiting`].
+#[cfg(feature = "rate-limiting")]
+type IpGovernorConfig = GovernorConfig<PeerIpKeyExtractor, NoOpMiddleware>;
+
+/// The per-IP rate limiter shared by every clone of the rate-limiting service.
+#[cfg(feature = "rate-limiting")]
+pub(crate) type IpRateLimiter = SharedRateLimiter<IpAddr, NoOpMiddleware>;
+
+/// Builds the governor configuration allowing `rate` requests per second per
+/// IP (with a burst of `rate`), or `None` when `rate` is zero or too large.
+#[cfg(feature = "rate-limiting")]
+pub(crate) fn governor_config(rate: u32) -> Option<IpGovernorConfig> {
+    if rate == 0 {
+        return None;
+    }
+    GovernorConfigBuilder::default()
+        .per_nanosecond((1_000_000_000 / rate) as u64)
+        .burst_size(rate)
+        .finish()
+}
+
+/// Rate-limiting layer whose limiter can be replaced while the server runs.
+#[cfg(feature = "rate-limiting")]
+#[derive(Clone)]
+struct LiveRateLimitLayer {
+    config: Arc<IpGovernorConfig>,
+    limiter: LiveValue<IpRateLimiter>,
+}
+
+#[cfg(feature = "rate-limiting")]
+impl<S> Layer<S> for LiveRateLimitLayer {
+    type Service = LiveRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LiveRateLimit {
+            governor: Governor::new(inner, &self.config),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// [`Governor`] that picks up a replaced limiter before handling each request.
+///
+/// Requests from a Unix socket peer have no IP address to key on and are
+/// passed through unlimited.
+#[cfg(feature = "rate-limiting")]
+#[derive(Clone)]
+struct LiveRateLimit<S> {
+    governor: Governor<PeerIpKeyExtractor, NoOpMiddleware, S, Body>,
+    limiter: LiveValue<IpRateLimiter>,
+}
+
+#[cfg(feature = "rate-limiting")]
+impl<S, ReqBody> Service<Request<ReqBody>> for LiveRateLimit<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+    <Governor<PeerIpKeyExtractor, NoOpMiddleware, S, Body> as Service<Request<ReqBody>>>::Future:
+        Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.governor.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        #[cfg(unix)]
+        if req
+            .extensions()
+            .get::<ConnectInfo<crate::UnixPeer>>()
+            .is_some()
+        {
+            return Box::pin(self.governor.inner.call(req));
+        }
+        self.limiter.read(|current| {
+            if !Arc::ptr_eq(current, &self.governor.limiter) {
+                self.governor.limiter = Arc::clone(current);
+            }
+        });
+        Box::pin(self.governor.call(req))
+    }
+}
+
 impl<State> FluentRouter<State>
 where
     State: Clone + Send + Sync + 'static,
@@ -41,7 +142,8 @@ where
     /// # Notes
     ///
     /// Rate limiting is per IP address. Behind a reverse proxy, ensure the
-    /// client's real IP is forwarded correctly.
+    /// client's real IP is forwarded correctly. Requests over a Unix socket
+    /// carry no IP address ([`UnixPeer`](crate::UnixPeer)) and are not limited.
     ///
     /// This middleware is automatically included in `setup_middleware()` as one of the
     /// outermost layers to reject excessive traffic early.
@@ -57,26 +159,25 @@ where
                 max_requests_per_sec = self.config.http.max_requests_per_sec,
                 "RateLimiting middleware enabled"
             );
-            // Used for rate limiting below. `finish()` only fails on an invalid
-            // builder configuration; the values here are guarded (rate > 0) and
-            // valid, so this is a fail-fast on a programming error at startup.
+            // `governor_config` only fails for a zero or out-of-range rate; the
+            // rate is guarded (> 0) here, so this is a fail-fast on a programming
+            // error at startup.
             #[allow(clippy::expect_used)]
-            let governor_conf = Box::new(
-                GovernorConfigBuilder::default()
-                    .per_nanosecond((1_000_000_000 / self.config.http.max_requests_per_sec) as u64)
-                    .burst_size(self.config.http.max_requests_per_sec)
-                    .finish()
-                    .expect("Failed to build governor config for rate limiting"),
-            );
+            let governor_conf = governor_config(self.config.http.max_requests_per_sec)
+                .expect("Failed to build governor config for rate limiting");
 
-            // Spawn a background thread to periodically clean up old entries
-            let governor_limiter = governor_conf.limiter().clone();
+            // The limiter is shared with the config-update task, which replaces
+            // it when `max_requests_per_sec` is reloaded.
+            let limiter = LiveValue::new(governor_conf.limiter().clone());
+            let _ = self.live.rate_limiter.set(limiter.clone());
             let interval = Duration::from_secs(60);
 
             // Spawn a background task to clean up old entries
+            let cleanup_limiter = limiter.clone();
             let handle = tokio::spawn(async move {
                 loop {
                     tokio::time::sleep(interval).await;
+                    let governor_limiter = cleanup_limiter.get();
                     governor_limiter.retain_recent();
                     if !governor_limiter.is_empty() {
                         tracing::debug!("remaining rate storage size: {}", governor_limiter.len());
@@ -87,8 +188,11 @@ where
             // Wrap the handle so that it gets cancelled when the router is dropped
             self.task_guards.governor = Some(AbortOnDropHandle::new(handle));
 
-            // Add the GovernorLayer for rate limiting
-            self.inner = self.inner.layer(GovernorLayer::new(governor_conf));
+            // Add the rate-limiting layer (tower_governor, with a reloadable limiter)
+            self.inner = self.inner.layer(LiveRateLimitLayer {
+                config: Arc::new(governor_conf),
+                limiter,
+            });
         }
         self
     }
@@ -16,7 +16,21 @@ use tower_http::normalize_path::NormalizePathLayer;
 use tower_http::{compression::CompressionLayer, decompression::RequestDecompressionLayer};
 
 #[cfg(feature = "cors")]
-use {http::HeaderName, tower_http::cors::CorsLayer};
+use {
+    super::reload::LiveValue,
+    http::{HeaderName, HeaderValue},
+    tower_http::cors::{AllowOrigin, CorsLayer},
+};
+
+/// Parses the configured CORS origins, skipping values that are not valid
+/// header values.
+#[cfg(feature = "cors")]
+pub(crate) fn cors_origins(origins: &[String]) -> Vec<HeaderValue> {
+    origins
+        .iter()
+        .filter_map(|origin| HeaderValue::from_str(origin).ok())
+        .collect()
+}
 
 #[cfg(feature = "security-headers")]
 use axum_helmet::Helmet;
@@ -607,8 +621,6 @@ where
             return self;
         }
 
-        use http::HeaderValue;
-
         if let Some(cors_config) = &self.config.http.cors {
             tracing::trace!("CORS middleware enabled with custom configuration");
             let mut cors = CorsLayer::new();
@@ -616,13 +628,15 @@ where
             // By default we do NOT allow credentials
             let has_credentials = cors_config.allow_credentials.unwrap_or(false);
 
-            // Configure allowed origins
+            // Configure allowed origins. The list is shared with the
+            // config-update task so a reloaded `allowed_origins` takes effect
+            // without rebuilding the layer.
             if let Some(origins) = &cors_config.allowed_origins {
-                for origin in origins {
-                    if let Ok(header_value) = HeaderValue::from_str(origin) {
-                        cors = cors.allow_origin(header_value);
-                    }
-                }
+                let allowed = LiveValue::new(cors_origins(origins));
+                let _ = self.live.cors_origins.set(allowed.clone());
+                cors = cors.allow_origin(AllowOrigin::predicate(move |origin, _| {
+                    allowed.read(|allowed| allowed.contains(origin))
+                }));
             } else if !has_credentials {
                 // Only use wildcard if credentials is not enabled
                 cors = cors.allow_origin(tower_http::cors::Any);
@@ -702,7 +716,9 @@ where
     ///
     /// Adds a simple endpoint that always returns 200 OK to indicate the process is running.
     /// This endpoint is placed very early in the middleware stack (after panic catching) so
-    /// it remains accessible even when other middleware fails.
+    /// it remains accessible even when other middleware fails. With `[http.admin]`
+    /// it is served on the admin listener instead (see
+    /// [`HttpAdminConfig`](crate::HttpAdminConfig)).
     ///
     /// # Configuration
     ///
@@ -728,7 +744,7 @@ where
 
         let liveness_route = self.config.http.liveness_route.clone();
         tracing::trace!(route = %liveness_route, "Liveness endpoint enabled");
-        self.inner = self.inner.route(&liveness_route, get(|| async { "OK\n" }));
+        self.route_operational(&liveness_route, get(|| async { "OK\n" }));
         self
     }
 
@@ -764,11 +780,22 @@ where
     /// - Excessive health check requests don't overwhelm the service
     /// - Database queries have a timeout to prevent hanging probes
     ///
+    /// With `[http.admin]` the endpoint is served on the admin listener instead,
+    /// where only the request timeout and panic recovery apply.
+    ///
+    /// # During Shutdown
+    ///
+    /// Once shutdown is initiated, the endpoint answers `503 Service Unavailable`
+    /// without running any check. With `shutdown_pre_stop_delay`, the server keeps
+    /// serving for that long before it closes the listener, so load balancers see
+    /// the failing probe and stop routing new traffic first.
+    ///
     /// # Configuration
     ///
     /// ```toml
     /// [http]
-    /// readiness_route = "/ready" # Default
+    /// readiness_route = "/ready"     # Default
+    /// shutdown_pre_stop_delay = "5s" # Keep serving while readiness fails
     /// ```
     ///
     /// # Kubernetes Integration
@@ -792,16 +819,25 @@ where
         // Application-supplied readiness hook, composed with the built-in checks.
         let readiness_check = self.readiness_check.clone();
 
+        // Cancelled once shutdown is initiated.
+        let shutting_down = self.shutdown_notifier.cancellation_token();
+
         #[cfg(feature = "postgres")]
         let db_pool = self.db_pool.clone();
 
         #[cfg(all(feature = "circuit-breaker", feature = "postgres"))]
         let circuit_breaker_registry = self.circuit_breaker_registry.clone();
 
-        self.inner = self.inner.route(
+        self.route_operational(
             &readiness_route,
             get(
                 move |axum::extract::State(app_state): axum::extract::State<State>| async move {
+                    // 0. A shutting-down instance reports not ready, so load
+                    //    balancers stop routing to it during the pre-stop delay.
+                    if shutting_down.is_cancelled() {
+                        return (StatusCode::SERVICE_UNAVAILABLE, "Shutting down\n").into_response();
+                    }
+
                     // 1. Application-supplied readiness check (evaluated first; it is
                     //    cheap and app-specific, so a saturated service sheds load
                     //    without a database round-trip). Composes with the built-in
@@ -0,0 +1,71 @@
+//! The handle to a server started in the background by
+//! [`FluentRouter::spawn`](super::FluentRouter::spawn).
+
+use super::shutdown::ShutdownNotifier;
+
+use {
+    crate::{Error, Result},
+    std::net::SocketAddr,
+    tokio::task::JoinHandle,
+    tokio_util::sync::{CancellationToken, DropGuard},
+};
+
+/// A running server, returned by [`FluentRouter::spawn`](super::FluentRouter::spawn).
+///
+/// Dropping the handle starts a graceful shutdown without waiting for it;
+/// call [`shutdown`](Self::shutdown) to wait until the server has stopped.
+#[derive(Debug)]
+pub struct ServerHandle {
+    local_addr: SocketAddr,
+    scheme: &'static str,
+    shutdown_notifier: ShutdownNotifier,
+    stop: DropGuard,
+    task: JoinHandle<Result<()>>,
+}
+
+impl ServerHandle {
+    pub(crate) fn new(
+        local_addr: SocketAddr,
+        scheme: &'static str,
+        stop: CancellationToken,
+        shutdown_notifier: ShutdownNotifier,
+        task: JoinHandle<Result<()>>,
+    ) -> Self {
+        Self {
+            local_addr,
+            scheme,
+            shutdown_notifier,
+            stop: stop.drop_guard(),
+            task,
+        }
+    }
+
+    /// The address the server listens on, with the port the operating system
+    /// picked when `bind_port` is 0. A server bound to the unspecified address
+    /// (`0.0.0.0` or `::`) is reported on the loopback address.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The URL of the server root, such as `http://127.0.0.1:41234`, or
+    /// `https://…` when `[http.tls]` is configured. It has no trailing slash.
+    pub fn base_url(&self) -> String {
+        format!("{}://{}", self.scheme, self.local_addr)
+    }
+
+    /// The notifier emitting the server's [`ShutdownPhase`](super::ShutdownPhase)s.
+    pub fn shutdown_notifier(&self) -> &ShutdownNotifier {
+        &self.shutdown_notifier
+    }
+
+    /// Shuts the server down gracefully, as on SIGTERM, and waits until it
+    /// has stopped.
+    ///
+    /// Returns the error the server stopped with, if any.
+    pub async fn shutdown(self) -> Result<()> {
+        drop(self.stop);
+        self.task
+            .await
+            .map_err(|e| Error::internal(format!("The server task failed: {e}")))?
+    }
+}
@@ -0,0 +1,381 @@
+//! Connection limits for [`FluentRouter::start`](super::FluentRouter::start).
+//!
+//! [`LimitedListener`] enforces `[http.connection] max_connections` and
+//! `max_connections_per_ip` as connections are accepted, before any TLS
+//! handshake or request parsing: a connection over a limit is closed right
+//! away. Each accepted connection holds a permit until it is closed. IPv6
+//! clients are counted per network (`per_ip_ipv6_prefix`, a /64 by default),
+//! as one host usually has a whole /64 to pick addresses from.
+//! Rejections are counted in the `http_connections_rejected_total` metric and
+//! logged at most once every few seconds, so a flood does not flood the logs.
+
+use {
+    axum::serve::Listener,
+    std::{
+        collections::HashMap,
+        io,
+        net::{IpAddr, Ipv6Addr, SocketAddr},
+        pin::Pin,
+        sync::{Arc, Mutex, PoisonError},
+        task::{Context, Poll},
+        time::{Duration, Instant},
+    },
+    tokio::{
+        io::{AsyncRead, AsyncWrite, ReadBuf},
+        sync::{OwnedSemaphorePermit, Semaphore},
+    },
+};
+
+/// Minimum time between two log lines about rejected connections.
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The address of a peer, as far as connection limits are concerned.
+pub(crate) trait PeerIp {
+    /// The IP address counted against `max_connections_per_ip`, if any.
+    fn peer_ip(&self) -> Option<IpAddr>;
+}
+
+impl PeerIp for SocketAddr {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        Some(self.ip())
+    }
+}
+
+#[cfg(unix)]
+impl PeerIp for tokio::net::unix::SocketAddr {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        None
+    }
+}
+
+/// Why a connection was turned away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rejection {
+    MaxConnections,
+    MaxConnectionsPerIp,
+}
+
+impl Rejection {
+    fn as_str(self) -> &'static str {
+        match self {
+            Rejection::MaxConnections => "max_connections",
+            Rejection::MaxConnectionsPerIp => "max_connections_per_ip",
+        }
+    }
+}
+
+/// The connection limits of one listener and the connections holding them.
+#[derive(Debug)]
+pub(crate) struct ConnectionLimits {
+    total: Option<Arc<Semaphore>>,
+    per_ip: Option<u32>,
+    /// Prefix length IPv6 addresses are truncated to before being counted.
+    ipv6_prefix: u8,
+    open_per_ip: Mutex<HashMap<IpAddr, u32>>,
+    log: Mutex<RejectionLog>,
+}
+
+#[derive(Debug, Default)]
+struct RejectionLog {
+    last_logged: Option<Instant>,
+    unlogged: u64,
+}
+
+impl ConnectionLimits {
+    pub(crate) fn new(
+        max_connections: Option<u32>,
+        max_connections_per_ip: Option<u32>,
+        ipv6_prefix: u8,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            total: max_connections.map(|max| Arc::new(Semaphore::new(max as usize))),
+            per_ip: max_connections_per_ip,
+            ipv6_prefix,
+            open_per_ip: Mutex::default(),
+            log: Mutex::default(),
+        })
+    }
+
+    /// Takes a permit for a connection from `ip`, or returns the limit it
+    /// would exceed.
+    fn acquire(self: &Arc<Self>, ip: Option<IpAddr>) -> Result<ConnectionPermit, Rejection> {
+        let total = match &self.total {
+            Some(total) => Some(
+                total
+                    .clone()
+                    .try_acquire_owned()
+                    .map_err(|_| Rejection::MaxConnections)?,
+            ),
+            None => None,
+        };
+        let ip = match (self.per_ip, ip) {
+            (Some(max), Some(ip)) => {
+                let ip = self.client_key(ip);
+                let mut open = self.lock_open();
+                let count = open.entry(ip).or_default();
+                if *count >= max {
+                    return Err(Rejection::MaxConnectionsPerIp);
+                }
+                *count += 1;
+                Some(ip)
+            }
+            _ => None,
+        };
+        Ok(ConnectionPermit {
+            _total: total,
+            ip,
+            limits: self.clone(),
+        })
+    }
+
+    /// The key `ip` is counted under: IPv4 addresses (including IPv4-mapped
+    /// IPv6 ones) as they are, IPv6 addresses truncated to their network.
+    fn client_key(&self, ip: IpAddr) -> IpAddr {
+        match ip.to_canonical() {
+            IpAddr::V4(v4) => v4.into(),
+            IpAddr::V6(v6) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.ipv6_prefix.min(128)))
+                    .unwrap_or(0);
+                Ipv6Addr::from_bits(v6.to_bits() & mask).into()
+            }
+        }
+    }
+
+    /// Counts a rejected connection, logging a summary at most every
+    /// [`LOG_INTERVAL`].
+    fn reject(&self, rejection: Rejection, peer: Option<IpAddr>) {
+        #[cfg(feature = "metrics")]
+        axum_prometheus::metrics::counter!(
+            "http_connections_rejected_total",
+            "reason" => rejection.as_str()
+        )
+        .increment(1);
+
+        let mut log = self.log.lock().unwrap_or_else(PoisonError::into_inner);
+        log.unlogged += 1;
+        let now = Instant::now();
+        if log
+            .last_logged
+            .is_none_or(|last| now.duration_since(last) >= LOG_INTERVAL)
+        {
+            tracing::warn!(
+                limit = rejection.as_str(),
+                peer = ?peer,
+                rejected = log.unlogged,
+                "Rejected connections over the {} limit",
+                rejection.as_str()
+            );
+            log.last_logged = Some(now);
+            log.unlogged = 0;
+        }
+    }
+
+    fn lock_open(&self) -> std::sync::MutexGuard<'_, HashMap<IpAddr, u32>> {
+        self.open_per_ip
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A connection's share of the limits, released when it is dropped.
+#[derive(Debug)]
+struct ConnectionPermit {
+    _total: Option<OwnedSemaphorePermit>,
+    /// The key counted in `open_per_ip`, if any.
+    ip: Option<IpAddr>,
+    limits: Arc<ConnectionLimits>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some(ip) = self.ip {
+            let mut open = self.limits.lock_open();
+            if let Some(count) = open.get_mut(&ip) {
+                *count -= 1;
+                if *count == 0 {
+                    open.remove(&ip);
+                }
+            }
+        }
+    }
+}
+
+/// A listener that closes connections over the limits as soon as they are
+/// accepted.
+pub(crate) struct LimitedListener<L> {
+    inner: L,
+    limits: Arc<ConnectionLimits>,
+}
+
+impl<L> LimitedListener<L> {
+    pub(crate) fn new(inner: L, limits: Arc<ConnectionLimits>) -> Self {
+        Self { inner, limits }
+    }
+}
+
+impl<L> Listener for LimitedListener<L>
+where
+    L: Listener,
+    L::Addr: PeerIp,
+{
+    type Io = LimitedStream<L::Io>;
+    type Addr = L::Addr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let (io, addr) = self.inner.accept().await;
+            let ip = addr.peer_ip();
+            match self.limits.acquire(ip) {
+                Ok(permit) => {
+                    return (
+                        LimitedStream {
+                            io,
+                            _permit: permit,
+                        },
+                        addr,
+                    );
+                }
+                // Dropping the stream closes the connection.
+                Err(rejection) => self.limits.reject(rejection, ip),
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.inner.local_addr()
+    }
+}
+
+/// A connection accepted by a [`LimitedListener`], holding its permit.
+#[derive(Debug)]
+pub(crate) struct LimitedStream<I> {
+    io: I,
+    _permit: ConnectionPermit,
+}
+
+impl<I: AsyncRead + Unpin> AsyncRead for LimitedStream<I> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_read(cx, buf)
+    }
+}
+
+impl<I: AsyncWrite + Unpin> AsyncWrite for LimitedStream<I> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn permits_are_counted_per_ip_and_released_on_drop() {
+        let limits = ConnectionLimits::new(Some(3), Some(2), 64);
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+
+        let first = limits.acquire(Some(a)).unwrap();
+        let _second = limits.acquire(Some(a)).unwrap();
+        assert_eq!(
+            limits.acquire(Some(a)).unwrap_err(),
+            Rejection::MaxConnectionsPerIp
+        );
+        let _third = limits.acquire(Some(b)).unwrap();
+        assert_eq!(
+            limits.acquire(Some(b)).unwrap_err(),
+            Rejection::MaxConnections
+        );
+
+        drop(first);
+        let _again = limits.acquire(Some(a)).unwrap();
+        assert_eq!(limits.lock_open().get(&a), Some(&2));
+    }
+
+    #[test]
+    fn ipv6_clients_are_counted_per_network() {
+        let limits = ConnectionLimits::new(None, Some(2), 64);
+        let ip = |s: &str| Some(s.parse::<IpAddr>().unwrap());
+
+        let _first = limits.acquire(ip("2001:db8:0:1::1")).unwrap();
+        let _second = limits.acquire(ip("2001:db8:0:1:ffff::2")).unwrap();
+        assert_eq!(
+            limits.acquire(ip("2001:db8:0:1::3")).unwrap_err(),
+            Rejection::MaxConnectionsPerIp
+        );
+        // Another /64 has its own count, and IPv4-mapped addresses count as IPv4.
+        let _other = limits.acquire(ip("2001:db8:0:2::1")).unwrap();
+        let _mapped = limits.acquire(ip("::ffff:10.0.0.1")).unwrap();
+        assert_eq!(
+            limits
+                .lock_open()
+                .get(&"10.0.0.1".parse::<IpAddr>().unwrap()),
+            Some(&1)
+        );
+
+        // A /128 prefix counts every address on its own.
+        let limits = ConnectionLimits::new(None, Some(1), 128);
+        let _first = limits.acquire(ip("2001:db8::1")).unwrap();
+        let _second = limits.acquire(ip("2001:db8::2")).unwrap();
+        assert!(limits.acquire(ip("2001:db8::1")).is_err());
+    }
+
+    #[tokio::test]
+    async fn connections_over_the_limit_are_closed_on_accept() {
+        use tokio::io::AsyncReadExt;
+
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let mut listener = LimitedListener::new(tcp, ConnectionLimits::new(None, Some(1), 64));
+
+        let _first = TcpStream::connect(addr).await.unwrap();
+        let (accepted, _) = listener.accept().await;
+
+        let mut rejected = TcpStream::connect(addr).await.unwrap();
+        let accept = tokio::spawn(async move { listener.accept().await.1 });
+        let mut buf = [0; 1];
+        let read = tokio::time::timeout(Duration::from_secs(5), rejected.read(&mut buf))
+            .await
+            .expect("the rejected connection was not closed");
+        assert!(matches!(read, Ok(0) | Err(_)), "{read:?}");
+
+        // Once the first connection closes, the address may connect again.
+        drop(accepted);
+        let _third = TcpStream::connect(addr).await.unwrap();
+        let peer = tokio::time::timeout(Duration::from_secs(5), accept)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(peer.ip(), addr.ip());
+    }
+}
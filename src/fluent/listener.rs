@@ -0,0 +1,663 @@
+//! The listeners [`FluentRouter::start`](super::FluentRouter::start) serves on.
+//!
+//! [`ServerListener`] is a TCP listener or, on Unix, a Unix domain socket
+//! listener. It is either bound from `[http]` (including sockets inherited
+//! through systemd socket activation) or opened by the caller and passed to
+//! [`FluentRouter::start_with_listener`](super::FluentRouter::start_with_listener).
+
+#[cfg(feature = "tls")]
+use super::tls::{TlsListener, tls_extensions};
+use super::{
+    limits::{ConnectionLimits, LimitedListener},
+    proxy_protocol::ProxyListener,
+    server::{ServeFuture, Server, connect_info},
+};
+
+#[cfg(unix)]
+use {
+    axum::serve::Listener,
+    std::{
+        env, fs,
+        net::TcpListener as StdTcpListener,
+        os::unix::{
+            fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+            io::{FromRawFd, IntoRawFd, RawFd},
+            net::{UnixListener as StdUnixListener, UnixStream as StdUnixStream},
+        },
+        path::{Path, PathBuf},
+        sync::Mutex,
+    },
+    tokio::net::{UnixListener, UnixStream},
+};
+
+use {
+    crate::{
+        Error, Result,
+        config::{BindTarget, HttpConfig},
+    },
+    axum::Router,
+    std::{future::Future, io, net::SocketAddr},
+    tokio::net::TcpListener,
+    tokio_util::sync::CancellationToken,
+};
+
+/// A listening socket to serve a [`FluentRouter`](super::FluentRouter) on.
+///
+/// Convert a tokio `TcpListener` or, on Unix, `UnixListener` with `into()` to
+/// pass it to [`FluentRouter::start_with_listener`](super::FluentRouter::start_with_listener).
+/// A TCP listener serves HTTPS when `[http.tls]` is configured.
+#[derive(Debug)]
+pub struct ServerListener {
+    socket: Socket,
+}
+
+/// The peer of a connection accepted on a Unix socket, carried by its requests
+/// as `ConnectInfo<UnixPeer>` instead of `ConnectInfo<SocketAddr>`.
+///
+/// A Unix peer has no IP address: it never matches `[http.proxy_oidc]
+/// trusted_proxies` (use `shared_secret` to trust a proxy on the socket) and
+/// per-IP rate limiting does not apply to it. Restrict who may connect with
+/// `unix_socket_mode` instead.
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnixPeer;
+
+#[derive(Debug)]
+enum Socket {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixSocketListener),
+}
+
+impl From<TcpListener> for ServerListener {
+    fn from(listener: TcpListener) -> Self {
+        Self {
+            socket: Socket::Tcp(listener),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixListener> for ServerListener {
+    /// Serves on `listener`; its socket file is left in place when the server stops.
+    fn from(listener: UnixListener) -> Self {
+        Self {
+            socket: Socket::Unix(UnixSocketListener {
+                listener,
+                path: None,
+            }),
+        }
+    }
+}
+
+impl ServerListener {
+    /// Binds the listen target of `[http]` (see [`HttpConfig::bind_target`]).
+    pub(crate) async fn bind(http: &HttpConfig) -> Result<Self> {
+        match http.bind_target()? {
+            BindTarget::Tcp(bind_addr) => {
+                let listener = TcpListener::bind(&bind_addr).await?;
+                tracing::info!("Bound to {bind_addr}");
+                Ok(listener.into())
+            }
+            #[cfg(unix)]
+            BindTarget::Unix(path) => {
+                let mode = http
+                    .unix_socket_mode
+                    .as_deref()
+                    .map(crate::config::parse_socket_mode)
+                    .transpose()?;
+                let listener = UnixSocketListener::bind(&path, mode)?;
+                tracing::info!("Bound to unix:{}", path.display());
+                Ok(Self {
+                    socket: Socket::Unix(listener),
+                })
+            }
+            #[cfg(unix)]
+            BindTarget::SocketActivation(name) => activated_listener(name.as_deref()),
+            #[cfg(not(unix))]
+            BindTarget::Unix(_) | BindTarget::SocketActivation(_) => Err(Error::config(
+                "HTTP bind = \"unix:...\" and \"systemd\" are only supported on Unix platforms.",
+            )),
+        }
+    }
+
+    /// The address of a TCP listener, or `None` for a Unix socket.
+    pub(crate) fn tcp_addr(&self) -> Option<io::Result<SocketAddr>> {
+        match &self.socket {
+            Socket::Tcp(listener) => Some(listener.local_addr()),
+            #[cfg(unix)]
+            Socket::Unix(_) => None,
+        }
+    }
+
+    /// Serves `router` until `signal` resolves and open connections drain, or
+    /// `abort` is cancelled and the remaining connections are closed.
+    ///
+    /// Connections over `[http.connection] max_connections` or
+    /// `max_connections_per_ip` are closed as soon as they are accepted. With
+    /// `[http] proxy_protocol`, TCP connections are addressed (and counted per
+    /// IP) from their PROXY header.
+    ///
+    /// Requests carry `ConnectInfo<SocketAddr>` on TCP listeners and
+    /// `ConnectInfo<UnixPeer>` on a Unix socket, whose peers have no IP address.
+    pub(crate) fn serve<F>(
+        self,
+        http: &HttpConfig,
+        router: Router,
+        signal: F,
+        abort: CancellationToken,
+    ) -> Result<ServeFuture>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let server = Server::new(&http.connection);
+        let connection = &http.connection;
+        match self.socket {
+            Socket::Tcp(listener) => match http.proxy_protocol {
+                // Behind a proxy, clients are counted by the address in the
+                // PROXY header rather than the proxy's own.
+                Some(protocol) => {
+                    tracing::info!("Expecting PROXY protocol {protocol} headers");
+                    let total = ConnectionLimits::new(connection.max_connections, None, 128);
+                    let per_ip = ConnectionLimits::new(
+                        None,
+                        connection.max_connections_per_ip,
+                        connection.per_ip_ipv6_prefix,
+                    );
+                    let listener = LimitedListener::new(listener, total);
+                    let listener =
+                        ProxyListener::new(listener, protocol, http.proxy_protocol_trusted.clone());
+                    serve_tcp(
+                        server,
+                        LimitedListener::new(listener, per_ip),
+                        http,
+                        router,
+                        signal,
+                        abort,
+                    )
+                }
+                None => {
+                    let limits = ConnectionLimits::new(
+                        connection.max_connections,
+                        connection.max_connections_per_ip,
+                        connection.per_ip_ipv6_prefix,
+                    );
+                    serve_tcp(
+                        server,
+                        LimitedListener::new(listener, limits),
+                        http,
+                        router,
+                        signal,
+                        abort,
+                    )
+                }
+            },
+            #[cfg(unix)]
+            Socket::Unix(listener) => {
+                #[cfg(feature = "tls")]
+                if http.tls.is_some() {
+                    return Err(Error::config(
+                        "[http.tls] is not supported on a Unix socket. Terminate TLS in front \
+                         of the socket or listen on TCP.",
+                    ));
+                }
+                let limits = ConnectionLimits::new(connection.max_connections, None, 128);
+                let listener = LimitedListener::new(listener, limits);
+                Ok(server.serve(
+                    listener,
+                    router,
+                    |_, _| connect_info(UnixPeer),
+                    signal,
+                    abort,
+                ))
+            }
+        }
+    }
+}
+
+/// Serves `router` on a TCP `listener`, over HTTPS when `[http.tls]` is set.
+fn serve_tcp<L, F>(
+    server: Server,
+    listener: L,
+    http: &HttpConfig,
+    router: Router,
+    signal: F,
+    abort: CancellationToken,
+) -> Result<ServeFuture>
+where
+    L: Listener<Addr = SocketAddr>,
+    F: Future<Output = ()> + Send + 'static,
+{
+    #[cfg(feature = "tls")]
+    if let Some(tls) = &http.tls {
+        let addr = listener.local_addr()?;
+        let listener = TlsListener::new(listener, tls)?;
+        tracing::info!("Serving HTTPS on {addr}");
+        return Ok(server
+            .with_http2()
+            .serve(listener, router, tls_extensions, signal, abort));
+    }
+    #[cfg(not(feature = "tls"))]
+    let _ = http;
+
+    Ok(server.serve(
+        listener,
+        router,
+        |_, addr: &SocketAddr| connect_info(*addr),
+        signal,
+        abort,
+    ))
+}
+
+/// A Unix domain socket listener that removes the socket file it created
+/// when it is dropped, i.e. once the server has stopped.
+#[cfg(unix)]
+#[derive(Debug)]
+struct UnixSocketListener {
+    listener: UnixListener,
+    /// The socket file to remove, if this listener created it.
+    path: Option<PathBuf>,
+}
+
+#[cfg(unix)]
+impl UnixSocketListener {
+    /// Binds `path`, replacing a stale socket left behind by a previous run.
+    ///
+    /// With a `mode`, the socket is bound inside a private (0700) directory
+    /// next to `path`, given its mode there and only then renamed into place,
+    /// so it is never reachable with the permissions the umask gave it.
+    fn bind(path: &Path, mode: Option<u32>) -> Result<Self> {
+        remove_stale_socket(path)?;
+        let bind_error =
+            |e: io::Error| Error::io(format!("Cannot bind Unix socket {}: {e}", path.display()));
+        let listener = match mode {
+            None => UnixListener::bind(path).map_err(bind_error)?,
+            Some(mode) => {
+                let private = PrivateDir::create(path).map_err(bind_error)?;
+                let staged = private.path.join("s");
+                let listener = UnixListener::bind(&staged).map_err(bind_error)?;
+                fs::set_permissions(&staged, fs::Permissions::from_mode(mode))?;
+                fs::rename(&staged, path).map_err(bind_error)?;
+                listener
+            }
+        };
+        Ok(Self {
+            listener,
+            path: Some(path.to_path_buf()),
+        })
+    }
+}
+
+/// A directory only the current user can enter, removed when dropped.
+#[cfg(unix)]
+struct PrivateDir {
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl PrivateDir {
+    /// Creates a private directory in the same directory as `socket`, so the
+    /// socket bound in it can be renamed to `socket` without crossing a
+    /// file system. Its name is kept short, as socket paths are limited to
+    /// about 100 bytes.
+    fn create(socket: &Path) -> io::Result<Self> {
+        let parent = match socket.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let suffix = uuid::Uuid::now_v7().simple().to_string();
+        let path = parent.join(format!(".{}", &suffix[suffix.len() - 8..]));
+        fs::DirBuilder::new().mode(0o700).create(&path)?;
+        Ok(Self { path })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for PrivateDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            tracing::warn!(path = %self.path.display(), error = %e, "Failed to remove a temporary directory");
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Listener for UnixSocketListener {
+    type Io = UnixStream;
+    type Addr = tokio::net::unix::SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        Listener::accept(&mut self.listener).await
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocketListener {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path
+            && let Err(e) = fs::remove_file(path)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to remove the Unix socket");
+        }
+    }
+}
+
+/// Removes the socket at `path` if no process is listening on it any more.
+///
+/// Anything else at `path` (a live socket, a regular file) is left alone and
+/// reported as an error.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> Result<()> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(Error::config(format!(
+            "Cannot bind Unix socket {}: the path exists and is not a socket",
+            path.display()
+        )));
+    }
+    match StdUnixStream::connect(path) {
+        Ok(_) => Err(Error::config(format!(
+            "Cannot bind Unix socket {}: another process is listening on it",
+            path.display()
+        ))),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+            tracing::info!(path = %path.display(), "Removing stale Unix socket");
+            fs::remove_file(path)?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The first file descriptor passed by systemd (`SD_LISTEN_FDS_START`).
+#[cfg(unix)]
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Descriptors already taken over from systemd.
+///
+/// The `LISTEN_*` variables stay set (removing them is not thread-safe once
+/// the runtime is up), so this keeps a second bind of the same socket from
+/// wrapping a descriptor that is already owned.
+#[cfg(unix)]
+static CLAIMED_LISTEN_FDS: Mutex<Vec<RawFd>> = Mutex::new(Vec::new());
+
+/// Takes over the listening socket passed by systemd socket activation.
+#[cfg(unix)]
+fn activated_listener(name: Option<&str>) -> Result<ServerListener> {
+    let fd = select_listen_fd(
+        std::process::id(),
+        env::var("LISTEN_PID").ok().as_deref(),
+        env::var("LISTEN_FDS").ok().as_deref(),
+        env::var("LISTEN_FDNAMES").ok().as_deref(),
+        name,
+    )?;
+    claim_listen_fd(
+        &mut CLAIMED_LISTEN_FDS.lock().unwrap_or_else(|e| e.into_inner()),
+        fd,
+    )?;
+
+    // SAFETY: `LISTEN_PID` names this process, so systemd handed it the
+    // descriptors from `LISTEN_FDS_START` on, and nothing else in the process
+    // owns them. `select_listen_fd` only returns one of those, and
+    // `claim_listen_fd` ensures it is taken over at most once.
+    let tcp = unsafe { StdTcpListener::from_raw_fd(fd) };
+    if let Ok(addr) = tcp.local_addr() {
+        tcp.set_nonblocking(true)?;
+        tracing::info!("Using socket-activated listener on {addr}");
+        return Ok(TcpListener::from_std(tcp)?.into());
+    }
+
+    // Not an IP socket: take it over as a Unix domain socket instead.
+    // SAFETY: ownership moves from `tcp` through `into_raw_fd`.
+    let unix = unsafe { StdUnixListener::from_raw_fd(tcp.into_raw_fd()) };
+    let addr = unix.local_addr().map_err(|e| {
+        Error::config(format!(
+            "Socket-activated file descriptor {fd} is not a TCP or Unix listening socket: {e}"
+        ))
+    })?;
+    unix.set_nonblocking(true)?;
+    tracing::info!("Using socket-activated listener on {addr:?}");
+    Ok(UnixListener::from_std(unix)?.into())
+}
+
+/// Records `fd` as taken over, failing if it already was.
+#[cfg(unix)]
+fn claim_listen_fd(claimed: &mut Vec<RawFd>, fd: RawFd) -> Result<()> {
+    if claimed.contains(&fd) {
+        return Err(Error::config(format!(
+            "The socket-activated file descriptor {fd} is already in use by another listener"
+        )));
+    }
+    claimed.push(fd);
+    Ok(())
+}
+
+/// Picks the descriptor to serve on from the socket activation variables.
+#[cfg(unix)]
+fn select_listen_fd(
+    pid: u32,
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    listen_fdnames: Option<&str>,
+    name: Option<&str>,
+) -> Result<RawFd> {
+    let Some(count) = listen_fds else {
+        return Err(Error::config(
+            "HTTP bind = \"systemd\" but no sockets were passed: LISTEN_FDS is not set. \
+             Start the service through a systemd .socket unit.",
+        ));
+    };
+    let count: RawFd = count
+        .parse()
+        .map_err(|_| Error::config(format!("Invalid LISTEN_FDS {count:?}")))?;
+    if listen_pid.and_then(|p| p.parse::<u32>().ok()) != Some(pid) {
+        return Err(Error::config(format!(
+            "LISTEN_FDS was set for process {}, not for this one ({pid})",
+            listen_pid.unwrap_or("<unset LISTEN_PID>")
+        )));
+    }
+    if count < 1 {
+        return Err(Error::config("LISTEN_FDS is 0: systemd passed no sockets"));
+    }
+
+    match name {
+        None => {
+            if count > 1 {
+                tracing::warn!(
+                    "systemd passed {count} sockets; serving on the first. Use \
+                     bind = \"systemd:<name>\" to pick one by FileDescriptorName."
+                );
+            }
+            Ok(LISTEN_FDS_START)
+        }
+        Some(name) => listen_fdnames
+            .unwrap_or_default()
+            .split(':')
+            .take(count as usize)
+            .position(|candidate| candidate == name)
+            .map(|index| LISTEN_FDS_START + index as RawFd)
+            .ok_or_else(|| {
+                Error::config(format!(
+                    "No socket named {name:?} was passed by systemd (LISTEN_FDNAMES={:?})",
+                    listen_fdnames.unwrap_or_default()
+                ))
+            }),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use axum::{extract::ConnectInfo, routing::get};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn socket_activation_variables_select_a_descriptor() {
+        let select = |pid, fds, names, name| select_listen_fd(42, pid, fds, names, name);
+
+        assert_eq!(select(Some("42"), Some("1"), None, None).unwrap(), 3);
+        assert_eq!(
+            select(Some("42"), Some("2"), Some("admin:http"), Some("http")).unwrap(),
+            4
+        );
+
+        let err = select(None, None, None, None).unwrap_err();
+        assert!(err.to_string().contains("LISTEN_FDS is not set"), "{err}");
+        let err = select(Some("7"), Some("1"), None, None).unwrap_err();
+        assert!(err.to_string().contains("process 7"), "{err}");
+        assert!(select(Some("42"), Some("0"), None, None).is_err());
+        assert!(select(Some("42"), Some("x"), None, None).is_err());
+        assert!(select(Some("42"), Some("1"), Some("admin:http"), Some("http")).is_err());
+
+        let mut claimed = Vec::new();
+        claim_listen_fd(&mut claimed, 3).unwrap();
+        claim_listen_fd(&mut claimed, 4).unwrap();
+        let err = claim_listen_fd(&mut claimed, 3).unwrap_err();
+        assert!(err.to_string().contains("already in use"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn unix_socket_is_served_and_cleaned_up() {
+        let dir = std::env::temp_dir().join(format!("axum-conf-unix-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("http.sock");
+
+        // A socket left behind by a crashed process is replaced...
+        drop(StdUnixListener::bind(&path).unwrap());
+        let http = HttpConfig {
+            bind: Some(format!("unix:{}", path.display())),
+            unix_socket_mode: Some("0600".into()),
+            ..Default::default()
+        };
+        let listener = ServerListener::bind(&http).await.unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The private directory it was bound in is gone.
+        let entries: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["http.sock"]);
+
+        // ...but a live one, or any other file, is not.
+        let err = ServerListener::bind(&http).await.unwrap_err();
+        assert!(err.to_string().contains("another process"), "{err}");
+        fs::write(dir.join("file"), "").unwrap();
+        let err = UnixSocketListener::bind(&dir.join("file"), None).unwrap_err();
+        assert!(err.to_string().contains("not a socket"), "{err}");
+
+        let router = Router::new().route(
+            "/",
+            get(|extensions: axum::http::Extensions| async move {
+                let addr = extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|info| info.0);
+                let unix = extensions.get::<ConnectInfo<UnixPeer>>().map(|info| info.0);
+                format!("{addr:?} {unix:?}")
+            }),
+        );
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = listener
+            .serve(
+                &http,
+                router,
+                async {
+                    let _ = stopped.await;
+                },
+                CancellationToken::new(),
+            )
+            .unwrap();
+        let server = tokio::spawn(server);
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("None Some(UnixPeer)"), "{response}");
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        let removed = !path.exists();
+        let _ = fs::remove_dir_all(&dir);
+        assert!(removed, "the socket file is removed once the server stops");
+    }
+
+    /// A Unix socket peer has no IP address: it is neither a trusted proxy
+    /// under a loopback `trusted_proxies` range nor rate limited per IP.
+    #[cfg(feature = "rate-limiting")]
+    #[tokio::test]
+    async fn unix_peers_have_no_ip_address() {
+        use crate::{AuthenticatedIdentity, Config, FluentRouter, HttpProxyOidcConfig};
+
+        let dir = std::env::temp_dir().join(format!("axum-conf-unix-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("http.sock");
+
+        let mut config =
+            Config::<()>::from_toml("[http]\nmax_payload_size_bytes = \"1KiB\"").unwrap();
+        config.http.bind = Some(format!("unix:{}", path.display()));
+        config.http.max_requests_per_sec = 1;
+        config.http.proxy_oidc = Some(HttpProxyOidcConfig {
+            trusted_proxies: vec!["127.0.0.1/32".parse().unwrap()],
+            ..Default::default()
+        });
+        let http = config.http.clone();
+        let router = FluentRouter::without_state(config)
+            .unwrap()
+            .route(
+                "/",
+                get(|identity: Option<AuthenticatedIdentity>| async move {
+                    identity.map_or("anonymous".to_string(), |identity| identity.user)
+                }),
+            )
+            .setup_proxy_oidc()
+            .setup_rate_limiting()
+            .into_inner();
+
+        let listener = ServerListener::bind(&http).await.unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = listener
+            .serve(
+                &http,
+                router,
+                async {
+                    let _ = stopped.await;
+                },
+                CancellationToken::new(),
+            )
+            .unwrap();
+        let server = tokio::spawn(server);
+
+        for _ in 0..3 {
+            let mut stream = UnixStream::connect(&path).await.unwrap();
+            stream
+                .write_all(
+                    b"GET / HTTP/1.1\r\nhost: localhost\r\nx-auth-request-user: mallory\r\n\
+                      connection: close\r\n\r\n",
+                )
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+            assert!(response.ends_with("anonymous"), "{response}");
+        }
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
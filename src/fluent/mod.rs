@@ -4,19 +4,30 @@
 //! The functionality is split across submodules for maintainability:
 //!
 //! - `router` - Core [`FluentRouter`] struct and initialization
-//! - `auth` - Authentication (OIDC, Basic Auth, user span)
+//! - `auth` - Authentication (OIDC, Basic Auth, client certificates, user span)
 //! - `observability` - Logging, metrics, OpenTelemetry
 //! - `request` - Request handling (payload, concurrency, dedup, request ID)
 //! - `features` - Features (routing, compression, CORS, Helmet, sessions, health)
 //! - `control` - Traffic control (rate limiting, panic catching)
 //! - `builder` - Orchestration (setup_middleware, start, router delegation)
+//! - `listener` - The TCP, TLS or Unix socket listener `start` serves on
+//! - `handle` - The handle to a server started with `spawn`
+//! - `admin` - The `[http.admin]` listener for health, metrics and debug routes
+//! - `server` - The connection loop, tuned by `[http.connection]`
+//! - `limits` - Connection limits enforced as connections are accepted
+//! - `proxy_protocol` - PROXY protocol headers from a load balancer
+//! - `tls` - HTTPS termination with certificate hot-reload
+//! - `reload` - Live configuration updates from a `ConfigWatcher`
 //! - `shutdown` - Graceful shutdown notifications and cancellation
+//! - `tasks` - Supervised background tasks started with `spawn_task`
 
 // Internal submodules (not part of the old public API, stay private)
 #[cfg(feature = "basic-auth")]
 mod basic_auth;
 #[cfg(feature = "keycloak")]
 mod browser_redirect;
+#[cfg(feature = "tls")]
+mod client_cert;
 #[cfg(feature = "deduplication")]
 mod dedup;
 #[cfg(feature = "keycloak")]
@@ -29,24 +40,45 @@ mod session_store;
 mod user_span;
 
 // New submodules containing split implementation
+mod admin;
 mod auth;
 mod builder;
 mod control;
 mod features;
+mod handle;
+mod limits;
+mod listener;
 mod observability;
+mod proxy_protocol;
 mod readiness;
+mod reload;
 mod request;
 mod router;
+mod server;
 mod shutdown;
+mod tasks;
+#[cfg(feature = "tls")]
+mod tls;
 
 // Re-export FluentRouter - the main public type
 pub use router::FluentRouter;
 
+// Re-export the listener accepted by `start_with_listener`
+pub use listener::ServerListener;
+#[cfg(unix)]
+pub use listener::UnixPeer;
+
+// Re-export the handle returned by `spawn`
+pub use handle::ServerHandle;
+
 // Re-export the application readiness hook types
 pub use readiness::Readiness;
 
 // Re-export shutdown types for public API
 pub use shutdown::{ShutdownNotifier, ShutdownPhase};
 
+// Re-export the supervised background task types
+pub use tasks::{RestartPolicy, TaskHealth, TaskRegistry};
+
 #[cfg(test)]
 mod tests;
@@ -1,16 +1,27 @@
-//! Observability middleware: logging, metrics, and OpenTelemetry tracing.
+//! Observability middleware: logging, metrics, OpenTelemetry tracing, and the
+//! effective-configuration route.
 
 use super::router::FluentRouter;
-use crate::HttpMiddleware;
+use crate::{AuthenticatedIdentity, HttpMiddleware, Result};
 
-use {axum::body::Body, http::Request, tower_http::trace::TraceLayer as TowerHTTPLayer};
+use {
+    axum::{
+        body::Body,
+        extract::Query,
+        response::{IntoResponse, Response},
+    },
+    http::{Request, StatusCode, header},
+    serde::Deserialize,
+    std::sync::Arc,
+    tower_http::trace::TraceLayer as TowerHTTPLayer,
+};
 
 #[cfg(feature = "metrics")]
 use axum_prometheus::PrometheusMetricLayerBuilder;
 
 #[cfg(feature = "opentelemetry")]
 use {
-    crate::{Error, Result},
+    crate::Error,
     opentelemetry::{global, trace::TracerProvider},
     opentelemetry_otlp::WithExportConfig,
     opentelemetry_sdk::{
@@ -96,8 +107,8 @@ fn build_custom_metrics_recorder(
 ) -> axum_prometheus::metrics_exporter_prometheus::PrometheusHandle {
     use axum_prometheus::metrics_exporter_prometheus::PrometheusBuilder;
 
-    let recorder = configure_metrics_recorder(buckets, global_labels, idle_timeout)
-        .unwrap_or_else(|error| {
+    let recorder =
+        configure_metrics_recorder(buckets, global_labels, idle_timeout).unwrap_or_else(|error| {
             tracing::error!(
                 %error,
                 "failed to configure custom Prometheus metrics; using a plain recorder"
@@ -140,6 +151,8 @@ where
     /// - Tracks request counts, durations, and HTTP status codes
     ///
     /// Metrics are exposed in Prometheus format for scraping by monitoring systems.
+    /// With `[http.admin]` the metrics route is served on the admin listener,
+    /// while requests are still measured on the public one.
     ///
     /// # Configuration
     ///
@@ -233,13 +246,11 @@ where
                 builder.with_default_metrics().build_pair()
             };
 
-            self.inner = self
-                .inner
-                .route(
-                    metrics_path,
-                    axum::routing::get(|| async move { metrics_handle.render() }),
-                )
-                .layer(prometheus_layer);
+            self.route_operational(
+                metrics_path,
+                axum::routing::get(|| async move { metrics_handle.render() }),
+            );
+            self.inner = std::mem::take(&mut self.inner).layer(prometheus_layer);
         }
         self
     }
@@ -337,6 +348,78 @@ where
         self
     }
 
+    /// Sets up the route serving the effective configuration
+    /// (`http.debug_config_route`), for answering questions like "why is CORS
+    /// permissive in prod" without reading source code.
+    ///
+    /// The configuration is rendered on each request, with
+    /// [`Config::to_redacted_json`](crate::Config::to_redacted_json) (the
+    /// default) and [`Config::to_redacted_toml`](crate::Config::to_redacted_toml)
+    /// (`?format=toml`), so every secret is masked. It shows the library sections
+    /// and the resolved `is_production` flag, plus the application settings when
+    /// the configuration was built with
+    /// [`Config::render_app`](crate::Config::render_app). With
+    /// [`Self::with_config_updates`] it follows the latest reloaded configuration.
+    ///
+    /// The handler requires an [`AuthenticatedIdentity`] with
+    /// `http.debug_config_role` among its roles or groups, answering
+    /// `401 Unauthorized` without an identity and `403 Forbidden` without the
+    /// role. Call this **before** the authentication `setup_*` methods so their
+    /// `route_layer` covers the route, as [`Self::setup_middleware`] does. The
+    /// route is always served on the public listener, since the admin listener
+    /// does not authenticate. Does nothing when the route is unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration cannot be rendered.
+    pub fn setup_debug_config(mut self) -> Result<Self> {
+        let Some(route) = self.config.http.debug_config_route.clone() else {
+            return Ok(self);
+        };
+        tracing::trace!(route = %route, "Effective-config route enabled");
+
+        let role = self
+            .config
+            .http
+            .debug_config_role
+            .clone()
+            .unwrap_or_default();
+        let rendered = match self.effective_config.take() {
+            Some(rendered) => rendered,
+            None => self.config.to_redacted()?,
+        };
+        let renderer: super::reload::ConfigRenderer = Arc::new(move || Ok(rendered.clone()));
+        let live = self
+            .live
+            .debug_config
+            .get_or_init(|| super::reload::LiveValue::new(renderer))
+            .clone();
+
+        self.inner = self.inner.route(
+            &route,
+            axum::routing::get(
+                move |identity: AuthenticatedIdentity, Query(query): Query<DebugConfigQuery>| {
+                    let role = role.clone();
+                    let render = live.get();
+                    async move {
+                        if !identity.roles.contains(&role) && !identity.groups.contains(&role) {
+                            return (StatusCode::FORBIDDEN, "Insufficient role").into_response();
+                        }
+                        match render() {
+                            Ok(config) => debug_config_response(
+                                query.format.as_deref(),
+                                &config.json,
+                                &config.toml,
+                            ),
+                            Err(e) => e.into_response(),
+                        }
+                    }
+                },
+            ),
+        );
+        Ok(self)
+    }
+
     /// Initializes OpenTelemetry distributed tracing with W3C Trace Context propagation.
     ///
     /// Sets up OTLP export to a collector (e.g., Jaeger, Tempo) for distributed tracing and
@@ -438,3 +521,31 @@ where
         Ok(self)
     }
 }
+
+/// Query string accepted by the effective-config route.
+#[derive(Deserialize)]
+struct DebugConfigQuery {
+    /// `json` (the default) or `toml`.
+    format: Option<String>,
+}
+
+/// Serves the pre-rendered configuration in the requested format.
+fn debug_config_response(format: Option<&str>, json: &str, toml: &str) -> Response {
+    match format {
+        None | Some("json") => (
+            [(header::CONTENT_TYPE, "application/json")],
+            json.to_owned(),
+        )
+            .into_response(),
+        Some("toml") => (
+            [(header::CONTENT_TYPE, "application/toml")],
+            toml.to_owned(),
+        )
+            .into_response(),
+        Some(other) => (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported format {other:?}; use \"json\" or \"toml\""),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,470 @@
+//! PROXY protocol v1 and v2 for [`FluentRouter::start`](super::FluentRouter::start).
+//!
+//! [`ProxyListener`] reads the PROXY header a load balancer prepends to each
+//! TCP connection, off the accept loop and within [`HEADER_TIMEOUT`], and
+//! yields the connection with the client address from the header. Bytes read
+//! past the header are replayed to the TLS handshake or HTTP parser. A
+//! connection whose header is missing or malformed is closed. With
+//! `proxy_protocol_trusted`, only connections from those peers are read for a
+//! header; others are served as they are, addressed by their own peer.
+//!
+//! See the [PROXY protocol specification](https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt).
+
+use {
+    crate::config::ProxyProtocol,
+    axum::serve::Listener,
+    ipnet::IpNet,
+    std::{
+        io,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    },
+    tokio::{
+        io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf},
+        task::JoinSet,
+    },
+};
+
+/// Maximum time a connection has to send its PROXY header.
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Starts every version 1 header.
+const V1_PREFIX: &[u8] = b"PROXY ";
+
+/// Longest version 1 header, including the trailing CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// Starts every version 2 header.
+const V2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// A listener that yields connections once their PROXY header has been read,
+/// addressed from the client the header names.
+pub(crate) struct ProxyListener<L: Listener> {
+    inner: L,
+    protocol: ProxyProtocol,
+    /// Peers whose header is read; empty reads every peer's.
+    trusted: Vec<IpNet>,
+    headers: JoinSet<Option<(ProxiedStream<L::Io>, SocketAddr)>>,
+}
+
+impl<L: Listener<Addr = SocketAddr>> ProxyListener<L> {
+    pub(crate) fn new(inner: L, protocol: ProxyProtocol, trusted: Vec<IpNet>) -> Self {
+        Self {
+            inner,
+            protocol,
+            trusted,
+            headers: JoinSet::new(),
+        }
+    }
+
+    /// Whether `peer` may send a PROXY header.
+    fn trusts(&self, peer: SocketAddr) -> bool {
+        let ip = peer.ip().to_canonical();
+        self.trusted.is_empty() || self.trusted.iter().any(|net| net.contains(&ip))
+    }
+}
+
+impl<L: Listener<Addr = SocketAddr>> Listener for ProxyListener<L> {
+    type Io = ProxiedStream<L::Io>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            tokio::select! {
+                Some(done) = self.headers.join_next(), if !self.headers.is_empty() => {
+                    if let Ok(Some(connection)) = done {
+                        return connection;
+                    }
+                }
+                (stream, peer) = self.inner.accept() => {
+                    if !self.trusts(peer) {
+                        return (ProxiedStream::new(stream, Vec::new()), peer);
+                    }
+                    let protocol = self.protocol;
+                    self.headers.spawn(async move {
+                        match tokio::time::timeout(HEADER_TIMEOUT, read_header(stream, peer, protocol)).await {
+                            Ok(Ok(connection)) => Some(connection),
+                            Ok(Err(e)) => {
+                                tracing::debug!(peer = %peer, error = %e, "Invalid PROXY protocol header");
+                                None
+                            }
+                            Err(_) => {
+                                tracing::debug!(peer = %peer, "Timed out reading the PROXY protocol header");
+                                None
+                            }
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.inner.local_addr()
+    }
+}
+
+/// Reads the PROXY header at the start of `stream`, returning the stream and
+/// the client address. `peer`, the address of the load balancer, is kept for
+/// `LOCAL` connections (health checks) and headers without an address.
+async fn read_header<I>(
+    mut stream: I,
+    peer: SocketAddr,
+    protocol: ProxyProtocol,
+) -> io::Result<(ProxiedStream<I>, SocketAddr)>
+where
+    I: AsyncRead + Unpin,
+{
+    let mut buf = Vec::with_capacity(256);
+    loop {
+        match parse(&buf, protocol) {
+            Parsed::Incomplete => {}
+            Parsed::Header { len, client } => {
+                buf.drain(..len);
+                return Ok((ProxiedStream::new(stream, buf), client.unwrap_or(peer)));
+            }
+            Parsed::None => return Ok((ProxiedStream::new(stream, buf), peer)),
+            Parsed::Invalid(reason) => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, reason));
+            }
+        }
+        if stream.read_buf(&mut buf).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+}
+
+/// What the start of a connection holds, as far as it has been read.
+#[derive(Debug, PartialEq, Eq)]
+enum Parsed {
+    /// More bytes are needed to decide.
+    Incomplete,
+    /// A header of `len` bytes, naming the client unless it has no address.
+    Header {
+        len: usize,
+        client: Option<SocketAddr>,
+    },
+    /// No header, which only `optional` accepts.
+    None,
+    Invalid(&'static str),
+}
+
+fn parse(buf: &[u8], protocol: ProxyProtocol) -> Parsed {
+    let v1 = matches!(protocol, ProxyProtocol::V1 | ProxyProtocol::Optional);
+    let v2 = matches!(protocol, ProxyProtocol::V2 | ProxyProtocol::Optional);
+    let starts = |signature: &[u8]| {
+        let n = buf.len().min(signature.len());
+        buf[..n] == signature[..n]
+    };
+
+    if v1 && starts(V1_PREFIX) {
+        if buf.len() >= V1_PREFIX.len() {
+            return parse_v1(buf);
+        }
+    } else if v2 && starts(V2_SIGNATURE) {
+        if buf.len() >= V2_SIGNATURE.len() {
+            return parse_v2(buf);
+        }
+    } else if protocol == ProxyProtocol::Optional {
+        return Parsed::None;
+    } else {
+        return Parsed::Invalid("the connection does not start with a PROXY protocol header");
+    }
+    Parsed::Incomplete
+}
+
+/// Parses `PROXY TCP4 <src> <dst> <sport> <dport>\r\n` (or `TCP6`, or `UNKNOWN ...`).
+fn parse_v1(buf: &[u8]) -> Parsed {
+    let Some(end) = buf.windows(2).position(|w| w == b"\r\n") else {
+        return if buf.len() >= V1_MAX_LEN {
+            Parsed::Invalid("PROXY v1 header is too long")
+        } else {
+            Parsed::Incomplete
+        };
+    };
+    let len = end + 2;
+    if len > V1_MAX_LEN {
+        return Parsed::Invalid("PROXY v1 header is too long");
+    }
+    let Ok(line) = std::str::from_utf8(&buf[V1_PREFIX.len()..end]) else {
+        return Parsed::Invalid("PROXY v1 header is not ASCII");
+    };
+    let fields: Vec<&str> = line.split(' ').collect();
+    let client = match fields.as_slice() {
+        ["UNKNOWN", ..] => None,
+        [
+            family @ ("TCP4" | "TCP6"),
+            source,
+            _destination,
+            source_port,
+            _destination_port,
+        ] => {
+            let (Ok(ip), Ok(port)) = (source.parse::<IpAddr>(), source_port.parse::<u16>()) else {
+                return Parsed::Invalid("PROXY v1 header has an invalid source address");
+            };
+            if ip.is_ipv4() != (*family == "TCP4") {
+                return Parsed::Invalid("PROXY v1 source address does not match its family");
+            }
+            Some(SocketAddr::new(ip, port))
+        }
+        _ => return Parsed::Invalid("PROXY v1 header is malformed"),
+    };
+    Parsed::Header { len, client }
+}
+
+/// Parses the binary header: signature, version and command, family and
+/// protocol, length, then the addresses and any TLVs (which are skipped).
+fn parse_v2(buf: &[u8]) -> Parsed {
+    const FIXED: usize = 16;
+    if buf.len() < FIXED {
+        return Parsed::Incomplete;
+    }
+    let version_command = buf[12];
+    if version_command >> 4 != 2 {
+        return Parsed::Invalid("PROXY v2 header has an unsupported version");
+    }
+    let len = FIXED + usize::from(u16::from_be_bytes([buf[14], buf[15]]));
+    if buf.len() < len {
+        return Parsed::Incomplete;
+    }
+    let addresses = &buf[FIXED..len];
+    let client = match (version_command & 0x0f, buf[13]) {
+        // LOCAL: the proxy's own connection, e.g. a health check.
+        (0x0, _) => None,
+        // PROXY over TCP (or UDP) on IPv4.
+        (0x1, 0x11 | 0x12) if addresses.len() >= 12 => {
+            let ip = Ipv4Addr::new(addresses[0], addresses[1], addresses[2], addresses[3]);
+            let port = u16::from_be_bytes([addresses[8], addresses[9]]);
+            Some(SocketAddr::new(ip.into(), port))
+        }
+        // PROXY over TCP (or UDP) on IPv6.
+        (0x1, 0x21 | 0x22) if addresses.len() >= 36 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&addresses[..16]);
+            let port = u16::from_be_bytes([addresses[32], addresses[33]]);
+            Some(SocketAddr::new(Ipv6Addr::from(octets).into(), port))
+        }
+        // PROXY with an unspecified or Unix family: no usable address.
+        (0x1, family) if family & 0xf0 != 0x10 && family & 0xf0 != 0x20 => None,
+        (0x1, _) => return Parsed::Invalid("PROXY v2 address block is too short"),
+        _ => return Parsed::Invalid("PROXY v2 header has an unsupported command"),
+    };
+    Parsed::Header { len, client }
+}
+
+/// A connection whose PROXY header has been consumed. The bytes read past the
+/// header are returned first.
+#[derive(Debug)]
+pub(crate) struct ProxiedStream<I> {
+    io: I,
+    buffered: Vec<u8>,
+    position: usize,
+}
+
+impl<I> ProxiedStream<I> {
+    fn new(io: I, buffered: Vec<u8>) -> Self {
+        Self {
+            io,
+            buffered,
+            position: 0,
+        }
+    }
+}
+
+impl<I: AsyncRead + Unpin> AsyncRead for ProxiedStream<I> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        if this.position < this.buffered.len() {
+            let remaining = &this.buffered[this.position..];
+            let n = remaining.len().min(buf.remaining());
+            buf.put_slice(&remaining[..n]);
+            this.position += n;
+            if this.position == this.buffered.len() {
+                this.buffered = Vec::new();
+                this.position = 0;
+            }
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut this.io).poll_read(cx, buf)
+    }
+}
+
+impl<I: AsyncWrite + Unpin> AsyncWrite for ProxiedStream<I> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    fn v2_header(command: u8, family: u8, addresses: &[u8]) -> Vec<u8> {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend([0x20 | command, family]);
+        header.extend((addresses.len() as u16).to_be_bytes());
+        header.extend(addresses);
+        header
+    }
+
+    #[test]
+    fn v1_headers_name_the_client() {
+        let header = b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 443\r\nGET /";
+        assert_eq!(
+            parse(header, ProxyProtocol::V1),
+            Parsed::Header {
+                len: header.len() - 5,
+                client: Some("203.0.113.7:51234".parse().unwrap()),
+            }
+        );
+        let header = b"PROXY TCP6 2001:db8::1 2001:db8::2 51234 443\r\n";
+        assert_eq!(
+            parse(header, ProxyProtocol::Optional),
+            Parsed::Header {
+                len: header.len(),
+                client: Some("[2001:db8::1]:51234".parse().unwrap()),
+            }
+        );
+        assert_eq!(
+            parse(b"PROXY UNKNOWN\r\n", ProxyProtocol::V1),
+            Parsed::Header {
+                len: 15,
+                client: None
+            }
+        );
+        assert_eq!(
+            parse(b"PROXY TCP4 203.0.113.7", ProxyProtocol::V1),
+            Parsed::Incomplete
+        );
+        assert_eq!(parse(b"PRO", ProxyProtocol::V1), Parsed::Incomplete);
+        assert!(matches!(
+            parse(
+                b"PROXY TCP4 2001:db8::1 10.0.0.1 1 2\r\n",
+                ProxyProtocol::V1
+            ),
+            Parsed::Invalid(_)
+        ));
+        let too_long = [V1_PREFIX, &[b'X'; 200]].concat();
+        assert!(matches!(
+            parse(&too_long, ProxyProtocol::V1),
+            Parsed::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn v2_headers_name_the_client() {
+        let mut addresses = vec![198, 51, 100, 9, 10, 0, 0, 1];
+        addresses.extend(40000u16.to_be_bytes());
+        addresses.extend(443u16.to_be_bytes());
+        // A TLV after the addresses is skipped.
+        addresses.extend([0x04, 0x00, 0x01, 0x00]);
+        let header = v2_header(0x1, 0x11, &addresses);
+        assert_eq!(
+            parse(&header, ProxyProtocol::V2),
+            Parsed::Header {
+                len: header.len(),
+                client: Some("198.51.100.9:40000".parse().unwrap()),
+            }
+        );
+        assert_eq!(parse(&header[..20], ProxyProtocol::V2), Parsed::Incomplete);
+
+        let local = v2_header(0x0, 0x00, &[]);
+        assert_eq!(
+            parse(&local, ProxyProtocol::Optional),
+            Parsed::Header {
+                len: 16,
+                client: None
+            }
+        );
+        assert!(matches!(
+            parse(
+                b"PROXY TCP4 203.0.113.7 10.0.0.1 1 2\r\n",
+                ProxyProtocol::V2
+            ),
+            Parsed::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn optional_accepts_connections_without_a_header() {
+        assert_eq!(
+            parse(b"GET / HTTP/1.1\r\n", ProxyProtocol::Optional),
+            Parsed::None
+        );
+        assert_eq!(
+            parse(b"\x16\x03\x01", ProxyProtocol::Optional),
+            Parsed::None
+        );
+        assert!(matches!(
+            parse(b"GET / HTTP/1.1\r\n", ProxyProtocol::V1),
+            Parsed::Invalid(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn bytes_after_the_header_are_replayed() {
+        let (mut client, server) = tokio::io::duplex(64);
+        client
+            .write_all(b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 443\r\nGET / HTTP/1.1\r\n")
+            .await
+            .unwrap();
+        drop(client);
+
+        let peer = "10.0.0.5:1234".parse().unwrap();
+        let (mut stream, client) = read_header(server, peer, ProxyProtocol::V1).await.unwrap();
+        assert_eq!(client, "203.0.113.7:51234".parse().unwrap());
+        let mut rest = String::new();
+        stream.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "GET / HTTP/1.1\r\n");
+    }
+
+    #[tokio::test]
+    async fn headers_from_untrusted_peers_are_ignored() {
+        let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let trusted = vec!["10.0.0.0/8".parse().unwrap()];
+        let mut listener = ProxyListener::new(tcp, ProxyProtocol::V1, trusted);
+
+        let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let header = "PROXY TCP4 203.0.113.7 10.0.0.1 51234 443\r\n";
+        client.write_all(header.as_bytes()).await.unwrap();
+        drop(client);
+
+        let (mut stream, peer) = listener.accept().await;
+        assert_eq!(peer.ip(), addr.ip());
+        let mut rest = String::new();
+        stream.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, header);
+    }
+}
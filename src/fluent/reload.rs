@@ -0,0 +1,198 @@
+//! Live configuration updates: applying reloaded settings to a running router.
+
+use super::router::FluentRouter;
+use crate::{Config, HttpConfig, config::RedactedConfig};
+
+use {
+    std::sync::{Arc, OnceLock, PoisonError, RwLock},
+    tokio::sync::watch,
+};
+
+/// A value shared between a middleware and the config-update task.
+pub(crate) struct LiveValue<T>(Arc<RwLock<T>>);
+
+impl<T> Clone for LiveValue<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> LiveValue<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self(Arc::new(RwLock::new(value)))
+    }
+
+    /// Calls `f` with the current value.
+    pub(crate) fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.0.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Replaces the current value.
+    pub(crate) fn set(&self, value: T) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+impl<T: Clone> LiveValue<T> {
+    /// Returns a clone of the current value.
+    pub(crate) fn get(&self) -> T {
+        self.read(T::clone)
+    }
+}
+
+/// Renders the configuration served on `http.debug_config_route`.
+pub(crate) type ConfigRenderer = Arc<dyn Fn() -> crate::Result<RedactedConfig> + Send + Sync>;
+
+/// The reloadable values used by the router's middleware.
+///
+/// Each slot is filled by the corresponding `setup_*` method when it installs
+/// its middleware; a slot that stays empty means the middleware is not active
+/// and a change to its settings needs a restart.
+#[derive(Default)]
+pub(crate) struct LiveSettings {
+    /// Per-IP rate limiter, swapped when `http.max_requests_per_sec` changes.
+    #[cfg(feature = "rate-limiting")]
+    pub(crate) rate_limiter: OnceLock<LiveValue<super::control::IpRateLimiter>>,
+    /// Origins allowed by CORS (`http.cors.allowed_origins`).
+    #[cfg(feature = "cors")]
+    pub(crate) cors_origins: OnceLock<LiveValue<Vec<http::HeaderValue>>>,
+    /// Basic Auth users and API keys.
+    #[cfg(feature = "basic-auth")]
+    pub(crate) basic_auth: OnceLock<LiveValue<Arc<crate::HttpBasicAuthConfig>>>,
+    /// The effective configuration, rendered from the latest update.
+    pub(crate) debug_config: OnceLock<LiveValue<ConfigRenderer>>,
+}
+
+impl LiveSettings {
+    /// Applies the reloadable HTTP settings that differ between `previous` and
+    /// `next`.
+    #[cfg_attr(
+        not(any(feature = "rate-limiting", feature = "cors", feature = "basic-auth")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn apply(&self, previous: &HttpConfig, next: &HttpConfig) {
+        #[cfg(feature = "rate-limiting")]
+        if previous.max_requests_per_sec != next.max_requests_per_sec {
+            let rate = next.max_requests_per_sec;
+            match (
+                self.rate_limiter.get(),
+                super::control::governor_config(rate),
+            ) {
+                (Some(live), Some(governor)) => {
+                    live.set(governor.limiter().clone());
+                    tracing::info!(max_requests_per_sec = rate, "Applied reloaded rate limit");
+                }
+                _ => tracing::warn!(
+                    max_requests_per_sec = rate,
+                    "Rate limiting was not enabled at startup (or is being disabled); \
+                     restart to apply the new http.max_requests_per_sec"
+                ),
+            }
+        }
+
+        #[cfg(feature = "cors")]
+        {
+            let origins = |http: &HttpConfig| {
+                http.cors
+                    .as_ref()
+                    .and_then(|cors| cors.allowed_origins.clone())
+            };
+            let next_origins = origins(next);
+            if origins(previous) != next_origins {
+                match (self.cors_origins.get(), next_origins) {
+                    (Some(live), Some(list)) => {
+                        live.set(super::features::cors_origins(&list));
+                        tracing::info!(origins = list.len(), "Applied reloaded CORS origins");
+                    }
+                    _ => tracing::warn!(
+                        "CORS origins were not configured at startup (or were removed); \
+                         restart to apply the new http.cors.allowed_origins"
+                    ),
+                }
+            }
+        }
+
+        #[cfg(feature = "basic-auth")]
+        if next.basic_auth.is_none() && previous.basic_auth.is_some() {
+            tracing::warn!(
+                "[http.basic_auth] was removed; authentication stays enabled with the \
+                 current credentials until restart"
+            );
+        }
+        #[cfg(feature = "basic-auth")]
+        if let Some(next_auth) = &next.basic_auth {
+            match self.basic_auth.get() {
+                Some(live) => {
+                    // Only the credentials are reloadable; mode and header stay
+                    // as they were at startup.
+                    let mut auth = (*live.get()).clone();
+                    auth.users.clone_from(&next_auth.users);
+                    auth.api_keys.clone_from(&next_auth.api_keys);
+                    live.set(Arc::new(auth));
+                }
+                None if previous.basic_auth.is_none() => tracing::warn!(
+                    "Basic Auth was not enabled at startup; restart to apply [http.basic_auth]"
+                ),
+                None => {}
+            }
+        }
+    }
+}
+
+impl<State> FluentRouter<State>
+where
+    State: Clone + Send + Sync + 'static,
+{
+    /// Applies configurations published on `updates` to this router while it
+    /// runs.
+    ///
+    /// Typically the receiver comes from a [`ConfigWatcher`](crate::ConfigWatcher).
+    /// The reloadable settings take effect on the next request:
+    ///
+    /// - `http.max_requests_per_sec` (rate limiting must be enabled at startup;
+    ///   per-IP buckets start over)
+    /// - `http.cors.allowed_origins` (when origins were configured at startup)
+    /// - `http.basic_auth.users` and `http.basic_auth.api_keys`
+    /// - `circuit_breaker.targets` (changed targets start over closed)
+    /// - the configuration served on `http.debug_config_route`
+    ///
+    /// `logging.filter` is applied by the watcher itself. Other changes need a
+    /// restart; the watcher reports them.
+    ///
+    /// The settings are picked up whenever the middleware is installed, so this
+    /// may be called before or after [`Self::setup_middleware`]. The update task
+    /// stops when shutdown begins or the sender is dropped; [`Self::start`]
+    /// joins it on shutdown, and dropping the router unstarted aborts it. Must
+    /// be called from within a Tokio runtime.
+    #[must_use]
+    pub fn with_config_updates<T>(mut self, mut updates: watch::Receiver<Arc<Config<T>>>) -> Self
+    where
+        T: Clone + Default + Send + Sync + 'static,
+    {
+        let live = Arc::clone(&self.live);
+        #[cfg(feature = "circuit-breaker")]
+        let breakers = self.circuit_breaker_registry.clone();
+        let token = self.cancellation_token();
+        let mut previous = updates.borrow_and_update().clone();
+
+        let task = super::tasks::spawn_internal("config-updates", async move {
+            loop {
+                tokio::select! {
+                    () = token.cancelled() => break,
+                    changed = updates.changed() => if changed.is_err() { break },
+                }
+                let next = updates.borrow_and_update().clone();
+                live.apply(&previous.http, &next.http);
+                #[cfg(feature = "circuit-breaker")]
+                breakers.reconfigure(&next.circuit_breaker);
+                if let Some(debug_config) = live.debug_config.get() {
+                    let config = Arc::clone(&next);
+                    debug_config.set(Arc::new(move || config.render_effective()));
+                }
+                previous = next;
+            }
+        });
+        self.tasks.push(task);
+        self
+    }
+}
@@ -10,7 +10,7 @@ use tokio_util::task::AbortOnDropHandle;
 
 use {
     super::shutdown::ShutdownNotifier,
-    crate::{Config, HttpMiddleware, Result, StaticDirRoute},
+    crate::{Config, HttpMiddleware, Result, StaticDirRoute, ValidateAppConfig},
     axum::Router,
     serde::de::DeserializeOwned,
     tokio::sync::broadcast,
@@ -94,6 +94,13 @@ pub struct FluentRouter<State = ()> {
     pub(crate) config: Config,
     pub(crate) state: State,
     pub(crate) inner: Router<State>,
+    /// Routes served on the `[http.admin]` listener; `None` when the
+    /// operational routes share the public listener.
+    pub(crate) admin: Option<Router<State>>,
+    /// The effective configuration, rendered before `T` is discarded so it
+    /// includes the application settings when [`Config::render_app`] is
+    /// enabled.
+    pub(crate) effective_config: Option<crate::config::RedactedConfig>,
     /// Guards for background maintenance tasks; each handle aborts its task when
     /// the router is dropped.
     #[cfg(any(
@@ -104,9 +111,19 @@ pub struct FluentRouter<State = ()> {
     pub(crate) task_guards: TaskGuards,
     pub(crate) panic_channel: Option<tokio::sync::mpsc::Sender<String>>,
     pub(crate) shutdown_notifier: ShutdownNotifier,
+    /// Cleanup steps registered with [`Self::on_shutdown`], run by `start`
+    /// after the connections have drained.
+    pub(crate) shutdown_hooks: Vec<super::shutdown::ShutdownHook>,
+    /// Tasks started with [`Self::spawn_task`], aborted if the router is
+    /// dropped and joined by `start` during shutdown.
+    pub(crate) tasks: Vec<super::tasks::SupervisedTask>,
+    /// Health of the tasks started with [`Self::spawn_task`].
+    pub(crate) task_registry: super::tasks::TaskRegistry,
     /// Optional application-supplied readiness check, composed with the built-in
     /// database/circuit-breaker checks in [`Self::setup_readiness`].
     pub(crate) readiness_check: Option<super::readiness::ReadinessCheck<State>>,
+    /// Reloadable middleware settings, updated by [`Self::with_config_updates`].
+    pub(crate) live: std::sync::Arc<super::reload::LiveSettings>,
     #[cfg(feature = "postgres")]
     pub(crate) db_pool: sqlx_postgres::PgPool,
     #[cfg(feature = "circuit-breaker")]
@@ -126,7 +143,7 @@ impl FluentRouter {
     /// # Example
     ///
     /// ```rust,no_run
-    /// use axum_conf::{Config, FluentRouter};
+    /// use axum_conf::{Config, FluentRouter, ValidateAppConfig};
     /// use serde::Deserialize;
     ///
     /// #[derive(Debug, Clone, Default, Deserialize)]
@@ -135,6 +152,8 @@ impl FluentRouter {
     ///     api_key: String,
     /// }
     ///
+    /// impl ValidateAppConfig for MyAppConfig {}
+    ///
     /// # fn example() -> axum_conf::Result<()> {
     /// let config: Config<MyAppConfig> = Config::default();
     /// let my_settings = config.app.clone();  // Preserve app config
@@ -145,20 +164,9 @@ impl FluentRouter {
     /// ```
     pub fn without_state<T>(config: Config<T>) -> Result<FluentRouter<()>>
     where
-        T: DeserializeOwned + Clone + Default,
+        T: DeserializeOwned + Clone + Default + ValidateAppConfig,
     {
-        // Extract base config, discarding app-specific fields
-        let base_config: Config<()> = Config {
-            http: config.http,
-            #[cfg(feature = "postgres")]
-            database: config.database,
-            logging: config.logging,
-            #[cfg(feature = "circuit-breaker")]
-            circuit_breaker: config.circuit_breaker,
-            app: (),
-            is_production: config.is_production,
-        };
-        FluentRouter::<()>::with_state(base_config, ())
+        FluentRouter::<()>::with_state(config, ())
     }
 }
 
@@ -168,7 +176,8 @@ where
 {
     /// Creates a new `FluentRouter` with the provided configuration.
     ///
-    /// Validates the configuration. Static file directories — including any
+    /// Validates the configuration, including the application settings through
+    /// [`ValidateAppConfig`]. Static file directories — including any
     /// fallback directory — are wired by [`Self::setup_middleware`]; call it (or
     /// `setup_fallback_files`/`setup_directories` explicitly) after construction.
     /// If a configuration for a database pool is provided, the pool will be created
@@ -197,9 +206,9 @@ where
     pub fn with_state<S, T>(config: Config<T>, state: S) -> Result<FluentRouter<S>>
     where
         S: Clone + Send + Sync + 'static,
-        T: DeserializeOwned + Clone + Default,
+        T: DeserializeOwned + Clone + Default + ValidateAppConfig,
     {
-        // Validate the configuration
+        // Validate the configuration, including the application settings
         config.validate()?;
 
         #[cfg(feature = "postgres")]
@@ -209,6 +218,11 @@ where
         let circuit_breaker_registry =
             crate::circuit_breaker::CircuitBreakerRegistry::new(&config.circuit_breaker);
 
+        let effective_config = match config.http.debug_config_route {
+            Some(_) => Some(config.render_effective()?),
+            None => None,
+        };
+
         // Extract base config, discarding app-specific fields
         let base_config = Config {
             http: config.http,
@@ -219,13 +233,19 @@ where
             circuit_breaker: config.circuit_breaker,
             app: (),
             is_production: config.is_production,
+            unknown_keys: config.unknown_keys,
+            strict_keys: config.strict_keys,
+            app_renderer: None,
         };
 
         // Create the base router and add public fallback files if configured
+        let admin = base_config.http.admin.as_ref().map(|_| Router::new());
         let me = FluentRouter {
             config: base_config,
             state,
             inner: Router::new(),
+            admin,
+            effective_config,
             #[cfg(any(
                 feature = "rate-limiting",
                 feature = "deduplication",
@@ -234,7 +254,11 @@ where
             task_guards: TaskGuards::default(),
             panic_channel: None,
             shutdown_notifier: ShutdownNotifier::default(),
+            shutdown_hooks: Vec::new(),
+            tasks: Vec::new(),
+            task_registry: Default::default(),
             readiness_check: None,
+            live: Default::default(),
             #[cfg(feature = "postgres")]
             db_pool,
             #[cfg(feature = "circuit-breaker")]
@@ -382,6 +406,136 @@ where
         self.shutdown_notifier.subscribe()
     }
 
+    /// Registers a cleanup step to run when the server shuts down.
+    ///
+    /// [`start`](Self::start) awaits the hooks once the HTTP connections have
+    /// drained, before it returns. They run one at a time, lowest `priority`
+    /// first; hooks of equal priority run in registration order. Each hook runs
+    /// for at most `timeout`. Together they get the sum of their timeouts,
+    /// within `[http] shutdown_timeout`: up to a quarter of it is reserved for
+    /// them and taken off the time connections have to drain, so they run even
+    /// when requests hold the drain to the end. A hook still running when the
+    /// time runs out is cancelled, and the hooks after it are skipped. The outcome and duration
+    /// of each hook are logged; a hook that fails, panics or times out does not
+    /// stop the others.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use axum_conf::{Config, FluentRouter};
+    /// use std::time::Duration;
+    ///
+    /// # async fn stop_consumers() {}
+    /// # async fn flush_outbox() -> axum_conf::Result<()> { Ok(()) }
+    /// # async fn example() -> axum_conf::Result<()> {
+    /// FluentRouter::without_state(Config::<()>::default())?
+    ///     .on_shutdown("stop consumers", 0, Duration::from_secs(5), || async {
+    ///         stop_consumers().await;
+    ///         Ok(())
+    ///     })
+    ///     .on_shutdown("flush outbox", 10, Duration::from_secs(10), flush_outbox)
+    ///     .setup_middleware()
+    ///     .await?
+    ///     .start()
+    ///     .await
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn on_shutdown<F, Fut>(
+        mut self,
+        name: impl Into<String>,
+        priority: i32,
+        timeout: std::time::Duration,
+        hook: F,
+    ) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        self.shutdown_hooks.push(super::shutdown::ShutdownHook {
+            name: name.into(),
+            priority,
+            timeout,
+            run: Box::new(move || Box::pin(hook())),
+        });
+        self
+    }
+
+    /// Runs a background task for as long as the server, restarting it as
+    /// `restart_policy` allows.
+    ///
+    /// `task` is called with a [`CancellationToken`] that is cancelled when
+    /// shutdown is initiated, and is called again for each restart. A run
+    /// that returns an error or panics is logged and, per the policy,
+    /// restarted after an exponential backoff; restarts are counted in the
+    /// `background_task_restarts_total{task}` metric (with the `metrics`
+    /// feature). During shutdown, [`start`](Self::start) waits for the tasks
+    /// to return once the connections have drained, and aborts those still
+    /// running `shutdown_timeout` after the grace period started. Dropping the
+    /// router without starting it aborts the tasks, unless it was handed off
+    /// with [`into_inner`](Self::into_inner).
+    ///
+    /// The task starts right away, so this must be called from within a
+    /// Tokio runtime. Its health is available through [`tasks`](Self::tasks).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use axum_conf::{Config, FluentRouter, RestartPolicy};
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # async fn next_message() -> axum_conf::Result<()> { Ok(()) }
+    /// async fn consume(token: CancellationToken) -> axum_conf::Result<()> {
+    ///     loop {
+    ///         tokio::select! {
+    ///             _ = token.cancelled() => return Ok(()),
+    ///             message = next_message() => message?,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> axum_conf::Result<()> {
+    /// FluentRouter::without_state(Config::<()>::default())?
+    ///     .spawn_task("consumer", RestartPolicy::on_failure(), consume)
+    ///     .setup_middleware()
+    ///     .await?
+    ///     .start()
+    ///     .await
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn spawn_task<F, Fut>(
+        mut self,
+        name: impl Into<String>,
+        restart_policy: super::tasks::RestartPolicy,
+        task: F,
+    ) -> Self
+    where
+        F: FnMut(CancellationToken) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        let task = super::tasks::spawn_supervised(
+            name.into(),
+            restart_policy,
+            self.cancellation_token(),
+            self.task_registry.clone(),
+            task,
+        );
+        self.tasks.push(task);
+        self
+    }
+
+    /// Returns the health of the tasks started with [`spawn_task`](Self::spawn_task).
+    ///
+    /// The registry stays up to date as tasks restart or fail; return its
+    /// [`readiness`](super::tasks::TaskRegistry::readiness) from
+    /// [`with_readiness_check`](Self::with_readiness_check) to report the
+    /// instance not ready while a task is down.
+    #[must_use]
+    pub fn tasks(&self) -> super::tasks::TaskRegistry {
+        self.task_registry.clone()
+    }
+
     /// Returns the configured PostgreSQL database pool.
     #[cfg(feature = "postgres")]
     pub fn db_pool(&self) -> sqlx_postgres::PgPool {
@@ -0,0 +1,536 @@
+//! The connection loop behind [`FluentRouter::start`](super::FluentRouter::start).
+//!
+//! [`Server`] accepts connections from a listener and serves each one on its
+//! own task with hyper's HTTP/1 and HTTP/2 implementations, configured from
+//! `[http.connection]`. Connections with no request in flight are closed once
+//! `keep_alive_timeout` elapses; on shutdown the listener is dropped and every
+//! connection finishes its in-flight requests before closing. When the grace
+//! period ends first, the remaining connections are closed and their requests
+//! dropped; those requests are logged and counted in the
+//! `http_requests_aborted_total` metric.
+
+use {
+    crate::config::HttpConnectionConfig,
+    axum::{
+        Router,
+        body::Body,
+        extract::{ConnectInfo, Request},
+        response::IntoResponse,
+        serve::Listener,
+    },
+    http::{Extensions, StatusCode},
+    http_body_util::BodyExt,
+    hyper::{body::Incoming, server::conn::http1},
+    hyper_util::{
+        rt::{TokioExecutor, TokioIo, TokioTimer},
+        server::conn::auto::Builder,
+    },
+    std::{
+        convert::Infallible,
+        future::Future,
+        io,
+        pin::Pin,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    },
+    tokio::{sync::watch, task::JoinSet},
+    tokio_util::sync::CancellationToken,
+    tower::ServiceExt,
+};
+
+/// A running server, resolving once it has shut down gracefully.
+pub(crate) type ServeFuture = Pin<Box<dyn Future<Output = io::Result<()>> + Send>>;
+
+/// How long aborted requests on HTTP/2 streams, which run on tasks of their
+/// own, get to wind down after their connection has been closed.
+const ABORT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Serves connections with the settings of `[http.connection]`.
+#[derive(Clone)]
+pub(crate) struct Server {
+    builder: Builder<TokioExecutor>,
+    /// Serves HTTP/1 only when set. hyper-util cannot restrict the protocol of
+    /// connections that may be upgraded, so those go through hyper directly.
+    http1_only: Option<http1::Builder>,
+    keep_alive_timeout: Duration,
+}
+
+impl Server {
+    pub(crate) fn new(config: &HttpConnectionConfig) -> Self {
+        let mut builder = Builder::new(TokioExecutor::new());
+
+        let mut http1 = builder.http1();
+        http1
+            .timer(TokioTimer::new())
+            .keep_alive(config.keep_alive)
+            .header_read_timeout(non_zero(config.header_read_timeout));
+        if let Some(max_headers) = config.max_headers {
+            http1.max_headers(max_headers);
+        }
+        if let Some(size) = config.max_header_size {
+            http1.max_buf_size(usize::try_from(size.as_u64()).unwrap_or(usize::MAX));
+        }
+
+        let mut http2 = builder.http2();
+        http2
+            .timer(TokioTimer::new())
+            // CONNECT protocol needed for HTTP/2 websockets
+            .enable_connect_protocol()
+            .max_concurrent_streams(config.http2_max_concurrent_streams)
+            .initial_stream_window_size(window_size(config.http2_initial_stream_window_size))
+            .initial_connection_window_size(window_size(
+                config.http2_initial_connection_window_size,
+            ))
+            .keep_alive_interval(non_zero(config.http2_keep_alive_interval))
+            .keep_alive_timeout(config.http2_keep_alive_timeout);
+        if let Some(size) = config.max_header_size {
+            http2.max_header_list_size(u32::try_from(size.as_u64()).unwrap_or(u32::MAX));
+        }
+
+        let http1_only = (!config.h2c).then(|| {
+            let mut http1 = http1::Builder::new();
+            http1
+                .timer(TokioTimer::new())
+                .keep_alive(config.keep_alive)
+                .header_read_timeout(non_zero(config.header_read_timeout));
+            if let Some(max_headers) = config.max_headers {
+                http1.max_headers(max_headers);
+            }
+            if let Some(size) = config.max_header_size {
+                http1.max_buf_size(usize::try_from(size.as_u64()).unwrap_or(usize::MAX));
+            }
+            http1
+        });
+
+        Self {
+            builder,
+            http1_only,
+            keep_alive_timeout: config.keep_alive_timeout,
+        }
+    }
+
+    /// Accepts HTTP/2 with prior knowledge again, for listeners where the
+    /// protocol is negotiated through TLS ALPN rather than `h2c`.
+    #[cfg(feature = "tls")]
+    pub(crate) fn with_http2(mut self) -> Self {
+        self.http1_only = None;
+        self
+    }
+
+    /// Serves `router` on `listener` until `signal` resolves and the open
+    /// connections have drained, or `abort` is cancelled and the remaining
+    /// connections are closed.
+    ///
+    /// `peer` returns the extensions every request of a connection carries,
+    /// such as `ConnectInfo<SocketAddr>`.
+    pub(crate) fn serve<L, P, F>(
+        self,
+        mut listener: L,
+        router: Router,
+        peer: P,
+        signal: F,
+        abort: CancellationToken,
+    ) -> ServeFuture
+    where
+        L: Listener,
+        P: Fn(&L::Io, &L::Addr) -> Extensions + Send + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        Box::pin(async move {
+            let stop = CancellationToken::new();
+            let requests = Arc::new(Requests::new(abort));
+            let mut connections = JoinSet::new();
+            tokio::pin!(signal);
+            loop {
+                tokio::select! {
+                    () = &mut signal => break,
+                    (io, addr) = listener.accept() => {
+                        let extensions = peer(&io, &addr);
+                        connections.spawn(self.clone().serve_connection(
+                            io,
+                            router.clone(),
+                            extensions,
+                            stop.clone(),
+                            requests.clone(),
+                        ));
+                    }
+                    Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                }
+            }
+
+            // Stop accepting, then let every connection finish its requests.
+            drop(listener);
+            stop.cancel();
+            while connections.join_next().await.is_some() {}
+            requests.report_aborted().await;
+            Ok(())
+        })
+    }
+
+    async fn serve_connection<I>(
+        self,
+        io: I,
+        router: Router,
+        extensions: Extensions,
+        stop: CancellationToken,
+        requests: Arc<Requests>,
+    ) where
+        I: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    {
+        let (in_flight, idle) = watch::channel(0usize);
+        let in_flight = Arc::new(in_flight);
+        let abort = requests.abort.clone();
+        let service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+            request.extensions_mut().extend(extensions.clone());
+            let guard = InFlight::start(&in_flight, &requests);
+            let router = router.clone();
+            let abort = requests.abort.clone();
+            async move {
+                // HTTP/2 requests run on tasks of their own, which closing the
+                // connection does not stop: drop the handler here instead.
+                let response = tokio::select! {
+                    response = router.oneshot(request) => response?,
+                    () = abort.cancelled() => {
+                        drop(guard);
+                        return Ok(StatusCode::SERVICE_UNAVAILABLE.into_response());
+                    }
+                };
+                // The request stays in flight until its response body is dropped.
+                Ok::<_, Infallible>(response.map(|body| {
+                    Body::new(body.map_frame(move |frame| {
+                        let _ = &guard;
+                        frame
+                    }))
+                }))
+            }
+        });
+
+        let io = TokioIo::new(io);
+        match &self.http1_only {
+            Some(http1) => {
+                let connection = http1.serve_connection(io, service).with_upgrades();
+                drive(
+                    connection,
+                    |c| c.graceful_shutdown(),
+                    idle,
+                    self.keep_alive_timeout,
+                    stop,
+                    abort,
+                )
+                .await;
+            }
+            None => {
+                let connection = self.builder.serve_connection_with_upgrades(io, service);
+                drive(
+                    connection,
+                    |c| c.graceful_shutdown(),
+                    idle,
+                    self.keep_alive_timeout,
+                    stop,
+                    abort,
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Runs `connection` to completion, shutting it down gracefully when `stop`
+/// is cancelled or it has been idle for `keep_alive_timeout`, and closing it
+/// right away when `abort` is cancelled.
+async fn drive<C, E>(
+    connection: C,
+    graceful_shutdown: fn(Pin<&mut C>),
+    mut idle: watch::Receiver<usize>,
+    keep_alive_timeout: Duration,
+    stop: CancellationToken,
+    abort: CancellationToken,
+) where
+    C: Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    tokio::pin!(connection);
+    let mut closing = false;
+    loop {
+        tokio::select! {
+            result = connection.as_mut() => {
+                if let Err(e) = result {
+                    tracing::trace!(error = %e, "Failed to serve connection");
+                }
+                break;
+            }
+            // Dropping the connection closes it, along with its requests.
+            () = abort.cancelled() => break,
+            () = stop.cancelled(), if !closing => {
+                closing = true;
+                graceful_shutdown(connection.as_mut());
+            }
+            () = idle_for(&mut idle, keep_alive_timeout),
+                if !closing && !keep_alive_timeout.is_zero() =>
+            {
+                tracing::trace!("Closing idle connection");
+                closing = true;
+                graceful_shutdown(connection.as_mut());
+            }
+        }
+    }
+}
+
+/// The requests in flight on a server, and those aborted at the end of the
+/// grace period.
+struct Requests {
+    /// Cancelled when the grace period ends.
+    abort: CancellationToken,
+    in_flight: watch::Sender<usize>,
+    aborted: AtomicUsize,
+}
+
+impl Requests {
+    fn new(abort: CancellationToken) -> Self {
+        Self {
+            abort,
+            in_flight: watch::Sender::new(0),
+            aborted: AtomicUsize::new(0),
+        }
+    }
+
+    /// Logs and counts the requests that were aborted, once they have all
+    /// been dropped.
+    async fn report_aborted(&self) {
+        if !self.abort.is_cancelled() {
+            return;
+        }
+        let mut in_flight = self.in_flight.subscribe();
+        let _ = tokio::time::timeout(ABORT_TIMEOUT, in_flight.wait_for(|count| *count == 0)).await;
+        let aborted = self.aborted.load(Ordering::Relaxed);
+        if aborted > 0 {
+            tracing::warn!(
+                aborted,
+                "Aborted {aborted} request(s) still in flight at the end of the grace period"
+            );
+            #[cfg(feature = "metrics")]
+            axum_prometheus::metrics::counter!("http_requests_aborted_total")
+                .increment(aborted as u64);
+        }
+    }
+}
+
+/// Marks a request in flight on its connection until dropped. A request
+/// dropped after the grace period has ended counts as aborted.
+struct InFlight {
+    connection: Arc<watch::Sender<usize>>,
+    requests: Arc<Requests>,
+}
+
+impl InFlight {
+    fn start(connection: &Arc<watch::Sender<usize>>, requests: &Arc<Requests>) -> Self {
+        connection.send_modify(|count| *count += 1);
+        requests.in_flight.send_modify(|count| *count += 1);
+        Self {
+            connection: connection.clone(),
+            requests: requests.clone(),
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.requests.abort.is_cancelled() {
+            self.requests.aborted.fetch_add(1, Ordering::Relaxed);
+        }
+        self.connection.send_modify(|count| *count -= 1);
+        self.requests.in_flight.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Resolves once no request has been in flight for `timeout`.
+async fn idle_for(in_flight: &mut watch::Receiver<usize>, timeout: Duration) {
+    loop {
+        if in_flight.wait_for(|count| *count == 0).await.is_err() {
+            return std::future::pending().await;
+        }
+        if tokio::time::timeout(timeout, in_flight.changed())
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// The extensions of a connection from `peer`: its `ConnectInfo<SocketAddr>`,
+/// or `ConnectInfo<UnixPeer>` on a Unix socket.
+pub(crate) fn connect_info<T: Clone + Send + Sync + 'static>(peer: T) -> Extensions {
+    let mut extensions = Extensions::new();
+    extensions.insert(ConnectInfo(peer));
+    extensions
+}
+
+fn non_zero(duration: Duration) -> Option<Duration> {
+    (!duration.is_zero()).then_some(duration)
+}
+
+fn window_size(size: Option<byte_unit::Byte>) -> Option<u32> {
+    size.map(|size| u32::try_from(size.as_u64()).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use std::net::SocketAddr;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    async fn serve(config: HttpConnectionConfig) -> (SocketAddr, CancellationToken) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/", get(|| async { "hello" }));
+        let stop = CancellationToken::new();
+        let server = Server::new(&config).serve(
+            listener,
+            app,
+            |_, _| Extensions::new(),
+            stop.clone().cancelled_owned(),
+            CancellationToken::new(),
+        );
+        tokio::spawn(server);
+        (addr, stop)
+    }
+
+    /// Reads until the server closes the connection.
+    async fn read_to_close(stream: &mut TcpStream) -> String {
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut response))
+            .await
+            .expect("the server kept the connection open")
+            .unwrap();
+        String::from_utf8(response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn idle_connections_are_closed_after_keep_alive_timeout() {
+        let config = HttpConnectionConfig {
+            keep_alive_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let (addr, _stop) = serve(config).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let response = read_to_close(&mut stream).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("hello"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn slow_headers_are_cut_off_by_header_read_timeout() {
+        let config = HttpConnectionConfig {
+            header_read_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let (addr, _stop) = serve(config).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n").await.unwrap();
+        let response = read_to_close(&mut stream).await;
+        assert!(!response.contains("hello"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn too_many_headers_are_rejected() {
+        let config = HttpConnectionConfig {
+            max_headers: Some(4),
+            ..Default::default()
+        };
+        let (addr, _stop) = serve(config).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let headers: String = (0..8).map(|i| format!("x-header-{i}: {i}\r\n")).collect();
+        let request = format!("GET / HTTP/1.1\r\nhost: localhost\r\n{headers}\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let response = read_to_close(&mut stream).await;
+        assert!(response.starts_with("HTTP/1.1 431"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn requests_in_flight_are_dropped_on_abort() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // The handler holds a sender until its future is dropped.
+        let (started, mut handlers) = tokio::sync::mpsc::channel::<()>(1);
+        let app = Router::new().route(
+            "/poll",
+            get(move || {
+                let started = started.clone();
+                async move {
+                    started.send(()).await.unwrap();
+                    std::future::pending::<()>().await;
+                }
+            }),
+        );
+        let (stop, abort) = (CancellationToken::new(), CancellationToken::new());
+        let server = tokio::spawn(Server::new(&HttpConnectionConfig::default()).serve(
+            listener,
+            app,
+            |_, _| Extensions::new(),
+            stop.clone().cancelled_owned(),
+            abort.clone(),
+        ));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /poll HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        handlers.recv().await.unwrap();
+
+        // The request keeps the server draining until it is aborted.
+        stop.cancel();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!server.is_finished());
+        abort.cancel();
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("the server kept waiting for the request")
+            .unwrap()
+            .unwrap();
+        assert!(handlers.recv().await.is_none());
+        read_to_close(&mut stream).await;
+    }
+
+    #[tokio::test]
+    async fn h2c_can_be_disabled() {
+        const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+        let (addr, _stop) = serve(HttpConnectionConfig::default()).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(PREFACE).await.unwrap();
+        // An HTTP/2 server answers the preface with its SETTINGS frame (type 4).
+        let mut frame_header = [0; 9];
+        stream.read_exact(&mut frame_header).await.unwrap();
+        assert_eq!(frame_header[3], 4);
+
+        let config = HttpConnectionConfig {
+            h2c: false,
+            ..Default::default()
+        };
+        let (addr, _stop) = serve(config).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(PREFACE).await.unwrap();
+        let response = read_to_close(&mut stream).await;
+        // An HTTP/1-only server rejects it, or just closes the connection.
+        assert!(
+            response.is_empty() || response.starts_with("HTTP/1.1"),
+            "{response:?}"
+        );
+    }
+}
@@ -104,8 +104,8 @@
 //! # }
 //! ```
 
-use std::time::Duration;
-use tokio::sync::broadcast;
+use std::{future::Future, pin::Pin, time::Duration};
+use tokio::{sync::broadcast, time::Instant};
 use tokio_util::sync::CancellationToken;
 
 /// The phases of a graceful shutdown sequence.
@@ -145,10 +145,12 @@ use tokio_util::sync::CancellationToken;
 /// ```
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ShutdownPhase {
-    /// Shutdown signal received (SIGTERM or SIGINT).
+    /// Shutdown signal received (SIGTERM or SIGINT), or
+    /// [`ServerHandle::shutdown`](crate::ServerHandle::shutdown) called.
     ///
     /// At this point:
-    /// - The server stops accepting new connections
+    /// - The readiness route starts answering 503; the server stops accepting
+    ///   new connections once `shutdown_pre_stop_delay` has passed
     /// - The [`CancellationToken`] is triggered
     /// - Components should stop accepting new work
     /// - Existing work should continue until completion or timeout
@@ -171,7 +173,8 @@ pub enum ShutdownPhase {
     /// Grace period has ended, shutdown will be forced.
     ///
     /// This phase indicates the timeout has expired and the process
-    /// will terminate shortly. Any remaining work will be abandoned.
+    /// will terminate shortly. The remaining connections are closed right
+    /// after, dropping the requests still in flight.
     ///
     /// At this point:
     /// - All cleanup should be complete
@@ -364,6 +367,80 @@ impl std::fmt::Debug for ShutdownNotifier {
     }
 }
 
+/// The boxed body of a [`ShutdownHook`], called once.
+pub(crate) type HookFn =
+    Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = crate::Result<()>> + Send + 'static>> + Send>;
+
+/// A cleanup step registered with [`FluentRouter::on_shutdown`](crate::FluentRouter::on_shutdown).
+pub(crate) struct ShutdownHook {
+    pub(crate) name: String,
+    pub(crate) priority: i32,
+    pub(crate) timeout: Duration,
+    pub(crate) run: HookFn,
+}
+
+/// Largest share of `shutdown_timeout` set aside for the shutdown hooks, as a
+/// divisor: at most a quarter of it.
+const HOOKS_SHARE: u32 = 4;
+
+/// The part of `shutdown_timeout` reserved for `hooks`, which the connection
+/// drain does not get: the sum of their timeouts, at most a quarter of it.
+pub(crate) fn hooks_reserve(hooks: &[ShutdownHook], shutdown_timeout: Duration) -> Duration {
+    hooks_budget(hooks).min(shutdown_timeout / HOOKS_SHARE)
+}
+
+/// The sum of the timeouts of `hooks`.
+fn hooks_budget(hooks: &[ShutdownHook]) -> Duration {
+    hooks
+        .iter()
+        .map(|hook| hook.timeout)
+        .fold(Duration::ZERO, Duration::saturating_add)
+}
+
+/// Runs `hooks` one at a time, lowest priority first, each for at most its own
+/// timeout. Together they get the sum of their timeouts, counted from now, and
+/// no time past `deadline`; a hook left with no time is skipped.
+///
+/// A hook that fails, panics or times out is logged and the next one runs.
+pub(crate) async fn run_shutdown_hooks(mut hooks: Vec<ShutdownHook>, deadline: Instant) {
+    let deadline = deadline.min(Instant::now() + hooks_budget(&hooks));
+    // The sort is stable, so hooks of equal priority keep their registration order.
+    hooks.sort_by_key(|hook| hook.priority);
+    for hook in hooks {
+        let started = Instant::now();
+        let timeout = hook
+            .timeout
+            .min(deadline.saturating_duration_since(started));
+        if timeout.is_zero() {
+            tracing::warn!(
+                hook = %hook.name,
+                "Skipping shutdown hook: shutdown_timeout has expired"
+            );
+            continue;
+        }
+
+        // A task of its own, so a panicking hook does not stop the others.
+        let mut task = tokio::spawn((hook.run)());
+        let outcome = tokio::time::timeout(timeout, &mut task).await;
+        let elapsed = started.elapsed();
+        match outcome {
+            Ok(Ok(Ok(()))) => {
+                tracing::info!(hook = %hook.name, ?elapsed, "Shutdown hook completed");
+            }
+            Ok(Ok(Err(e))) => {
+                tracing::warn!(hook = %hook.name, ?elapsed, error = %e, "Shutdown hook failed");
+            }
+            Ok(Err(e)) => {
+                tracing::error!(hook = %hook.name, ?elapsed, error = %e, "Shutdown hook panicked");
+            }
+            Err(_) => {
+                task.abort();
+                tracing::warn!(hook = %hook.name, ?timeout, "Shutdown hook timed out");
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -480,6 +557,82 @@ mod tests {
         assert_eq!(count, 2);
     }
 
+    fn hook(
+        name: &'static str,
+        priority: i32,
+        ran: &std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>,
+        work: impl Future<Output = crate::Result<()>> + Send + 'static,
+    ) -> ShutdownHook {
+        let ran = ran.clone();
+        ShutdownHook {
+            name: name.to_string(),
+            priority,
+            timeout: Duration::from_millis(200),
+            run: Box::new(move || {
+                Box::pin(async move {
+                    ran.lock().unwrap().push(name);
+                    work.await
+                })
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_in_priority_order_despite_failures() {
+        let ran = std::sync::Arc::default();
+        let hooks = vec![
+            hook("close pool", 20, &ran, async { Ok(()) }),
+            hook("flush outbox", 10, &ran, async {
+                Err(crate::Error::internal("broker unavailable"))
+            }),
+            hook("stop consumers", 0, &ran, async {
+                panic!("consumer panicked")
+            }),
+            hook("stuck", 10, &ran, std::future::pending()),
+        ];
+
+        let started = Instant::now();
+        run_shutdown_hooks(hooks, Instant::now() + Duration::from_secs(5)).await;
+
+        assert_eq!(
+            *ran.lock().unwrap(),
+            ["stop consumers", "flush outbox", "stuck", "close pool"]
+        );
+        // The stuck hook is cut off at its own timeout.
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_hooks_past_the_deadline_are_skipped() {
+        let ran = std::sync::Arc::default();
+        let hooks = vec![
+            hook("slow", 0, &ran, async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            }),
+            hook("late", 1, &ran, async { Ok(()) }),
+        ];
+
+        run_shutdown_hooks(hooks, Instant::now() + Duration::from_millis(50)).await;
+
+        assert_eq!(*ran.lock().unwrap(), ["slow"]);
+    }
+
+    #[test]
+    fn test_hooks_reserve_at_most_a_quarter_of_the_shutdown_timeout() {
+        let ran = std::sync::Arc::default();
+        let hooks = vec![
+            hook("flush outbox", 0, &ran, async { Ok(()) }),
+            hook("close pool", 1, &ran, async { Ok(()) }),
+        ];
+        let ms = Duration::from_millis;
+
+        // Two hooks of 200ms each.
+        assert_eq!(hooks_reserve(&hooks, ms(30_000)), ms(400));
+        assert_eq!(hooks_reserve(&hooks, ms(400)), ms(100));
+        assert_eq!(hooks_reserve(&[], ms(30_000)), Duration::ZERO);
+    }
+
     #[test]
     fn test_shutdown_phase_debug() {
         let phase = ShutdownPhase::GracePeriodStarted {
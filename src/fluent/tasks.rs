@@ -0,0 +1,460 @@
+//! Supervised background tasks bound to the router lifecycle.
+//!
+//! [`FluentRouter::spawn_task`](super::FluentRouter::spawn_task) runs an
+//! application task under a supervisor that restarts it with exponential
+//! backoff when it fails or panics, as its [`RestartPolicy`] allows. Every
+//! task receives the router's [`CancellationToken`], cancelled when shutdown is
+//! initiated, and [`FluentRouter::start`](super::FluentRouter::start) waits for
+//! the tasks to stop once the connections have drained. Each task's
+//! [`TaskHealth`] is tracked in the router's [`TaskRegistry`], which a
+//! readiness check can consult. Restarts are counted in the
+//! `background_task_restarts_total` metric.
+
+use {
+    crate::{Readiness, Result},
+    std::{
+        collections::BTreeMap,
+        fmt,
+        future::Future,
+        sync::{Arc, Mutex, PoisonError},
+        time::Duration,
+    },
+    tokio::time::Instant,
+    tokio_util::{sync::CancellationToken, task::AbortOnDropHandle},
+};
+
+/// When a supervised task is restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Restart {
+    Never,
+    OnFailure,
+    Always,
+}
+
+/// How [`FluentRouter::spawn_task`](super::FluentRouter::spawn_task) restarts a task.
+///
+/// The delay before a restart starts at one second and doubles after each
+/// consecutive failure, up to one minute; a run that lasted longer than the
+/// maximum delay resets it. Tasks are restarted without limit unless
+/// [`with_max_restarts`](Self::with_max_restarts) sets one. No task is
+/// restarted once shutdown has been initiated.
+///
+/// # Example
+///
+/// ```rust
+/// use axum_conf::RestartPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RestartPolicy::on_failure()
+///     .with_backoff(Duration::from_millis(500), Duration::from_secs(30))
+///     .with_max_restarts(10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestartPolicy {
+    restart: Restart,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: Option<u32>,
+}
+
+impl RestartPolicy {
+    fn new(restart: Restart) -> Self {
+        Self {
+            restart,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_restarts: None,
+        }
+    }
+
+    /// Runs the task once; if it fails, it stays [`TaskHealth::Failed`].
+    #[must_use]
+    pub fn never() -> Self {
+        Self::new(Restart::Never)
+    }
+
+    /// Restarts the task when it returns an error or panics.
+    #[must_use]
+    pub fn on_failure() -> Self {
+        Self::new(Restart::OnFailure)
+    }
+
+    /// Restarts the task whenever it stops before shutdown, even successfully.
+    #[must_use]
+    pub fn always() -> Self {
+        Self::new(Restart::Always)
+    }
+
+    /// Sets the delay before the first restart and the largest delay it
+    /// doubles up to.
+    #[must_use]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Gives up after `max_restarts` consecutive restarts, leaving the task
+    /// [`TaskHealth::Failed`].
+    #[must_use]
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+}
+
+/// The state of a supervised task, as reported by [`TaskRegistry::health`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskHealth {
+    /// The task is running.
+    Running,
+    /// The task failed and will be restarted after a delay.
+    Restarting {
+        /// Number of restarts since the task last ran long enough to reset
+        /// the backoff.
+        restarts: u32,
+        /// Why the last run failed.
+        error: String,
+    },
+    /// The task failed and its restart policy does not allow a restart.
+    Failed {
+        /// Why the last run failed.
+        error: String,
+    },
+    /// The task returned successfully and its restart policy does not
+    /// restart it.
+    Finished,
+    /// The task stopped because shutdown was initiated.
+    Stopped,
+}
+
+impl TaskHealth {
+    /// Returns `true` unless the task is restarting or has failed.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        !matches!(
+            self,
+            TaskHealth::Restarting { .. } | TaskHealth::Failed { .. }
+        )
+    }
+}
+
+impl fmt::Display for TaskHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskHealth::Running => write!(f, "running"),
+            TaskHealth::Restarting { restarts, error } => {
+                write!(f, "restarting after {restarts} failure(s): {error}")
+            }
+            TaskHealth::Failed { error } => write!(f, "failed: {error}"),
+            TaskHealth::Finished => write!(f, "finished"),
+            TaskHealth::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// The health of the tasks started with
+/// [`FluentRouter::spawn_task`](super::FluentRouter::spawn_task).
+///
+/// Obtained from [`FluentRouter::tasks`](super::FluentRouter::tasks); clones
+/// share the same state. To take the instance out of rotation while a task is
+/// down, return [`readiness`](Self::readiness) from a readiness check:
+///
+/// ```rust,no_run
+/// use axum_conf::{Config, FluentRouter, RestartPolicy};
+///
+/// # async fn consume(_: tokio_util::sync::CancellationToken) -> axum_conf::Result<()> { Ok(()) }
+/// # async fn example() -> axum_conf::Result<()> {
+/// let router = FluentRouter::without_state(Config::<()>::default())?
+///     .spawn_task("consumer", RestartPolicy::on_failure(), consume);
+/// let tasks = router.tasks();
+/// router
+///     .with_readiness_check(move |_| {
+///         let tasks = tasks.clone();
+///         async move { tasks.readiness() }
+///     })
+///     .setup_middleware()
+///     .await?
+///     .start()
+///     .await
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TaskRegistry {
+    health: Arc<Mutex<BTreeMap<String, TaskHealth>>>,
+}
+
+impl TaskRegistry {
+    /// The health of the task named `name`, if one was spawned.
+    #[must_use]
+    pub fn health(&self, name: &str) -> Option<TaskHealth> {
+        self.lock().get(name).cloned()
+    }
+
+    /// The name and health of every task, sorted by name.
+    #[must_use]
+    pub fn all(&self) -> Vec<(String, TaskHealth)> {
+        self.lock()
+            .iter()
+            .map(|(name, health)| (name.clone(), health.clone()))
+            .collect()
+    }
+
+    /// [`Readiness::NotReady`] naming the tasks that are restarting or have
+    /// failed, or [`Readiness::Ready`] when there are none.
+    #[must_use]
+    pub fn readiness(&self) -> Readiness {
+        let unhealthy: Vec<_> = self
+            .lock()
+            .iter()
+            .filter(|(_, health)| !health.is_healthy())
+            .map(|(name, health)| format!("task {name} is {health}"))
+            .collect();
+        if unhealthy.is_empty() {
+            Readiness::ready()
+        } else {
+            Readiness::not_ready(unhealthy.join("; "))
+        }
+    }
+
+    fn set(&self, name: &str, health: TaskHealth) {
+        self.lock().insert(name.to_string(), health);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, TaskHealth>> {
+        self.health.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A spawned supervisor, aborted when dropped.
+pub(crate) struct SupervisedTask {
+    name: String,
+    handle: AbortOnDropHandle<()>,
+}
+
+impl SupervisedTask {
+    /// Lets the task run on after its handle is gone.
+    pub(crate) fn detach(self) {
+        drop(self.handle.detach());
+    }
+}
+
+/// Starts one of the crate's own tasks, such as the config-update task. It is
+/// joined on shutdown like a supervised task, but neither restarted nor listed
+/// in the [`TaskRegistry`].
+pub(crate) fn spawn_internal<Fut>(name: &str, task: Fut) -> SupervisedTask
+where
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    SupervisedTask {
+        name: name.to_string(),
+        handle: AbortOnDropHandle::new(tokio::spawn(task)),
+    }
+}
+
+/// Starts `task` under a supervisor that restarts it as `policy` allows until
+/// `token` is cancelled.
+pub(crate) fn spawn_supervised<F, Fut>(
+    name: String,
+    policy: RestartPolicy,
+    token: CancellationToken,
+    registry: TaskRegistry,
+    task: F,
+) -> SupervisedTask
+where
+    F: FnMut(CancellationToken) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    if registry.health(&name).is_some() {
+        tracing::warn!(task = %name, "A background task with this name was already spawned");
+    }
+    registry.set(&name, TaskHealth::Running);
+    let handle = tokio::spawn(supervise(name.clone(), policy, token, registry, task));
+    SupervisedTask {
+        name,
+        handle: AbortOnDropHandle::new(handle),
+    }
+}
+
+async fn supervise<F, Fut>(
+    name: String,
+    policy: RestartPolicy,
+    token: CancellationToken,
+    registry: TaskRegistry,
+    mut task: F,
+) where
+    F: FnMut(CancellationToken) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let mut backoff = policy.initial_backoff;
+    let mut restarts = 0;
+    loop {
+        registry.set(&name, TaskHealth::Running);
+        let started = Instant::now();
+        // A task of its own, so a panic is reported instead of unwinding the
+        // supervisor; aborted with the supervisor.
+        let run = AbortOnDropHandle::new(tokio::spawn(task(token.clone())));
+        let error = match run.await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(e) => Some(format!("panicked: {e}")),
+        };
+
+        if token.is_cancelled() {
+            match &error {
+                Some(error) => {
+                    tracing::warn!(task = %name, %error, "Background task failed while stopping")
+                }
+                None => tracing::debug!(task = %name, "Background task stopped"),
+            }
+            registry.set(&name, TaskHealth::Stopped);
+            return;
+        }
+        // A long healthy run starts the backoff over.
+        if started.elapsed() >= policy.max_backoff {
+            backoff = policy.initial_backoff;
+            restarts = 0;
+        }
+        let health = match error {
+            None if policy.restart != Restart::Always => {
+                tracing::info!(task = %name, "Background task finished");
+                registry.set(&name, TaskHealth::Finished);
+                return;
+            }
+            None => {
+                tracing::info!(task = %name, ?backoff, "Background task finished; restarting it");
+                TaskHealth::Running
+            }
+            Some(error)
+                if policy.restart == Restart::Never
+                    || policy.max_restarts.is_some_and(|max| restarts >= max) =>
+            {
+                tracing::error!(task = %name, %error, restarts, "Background task failed; not restarting it");
+                registry.set(&name, TaskHealth::Failed { error });
+                return;
+            }
+            Some(error) => {
+                restarts += 1;
+                tracing::warn!(task = %name, %error, restarts, ?backoff, "Background task failed; restarting it");
+                TaskHealth::Restarting { restarts, error }
+            }
+        };
+
+        #[cfg(feature = "metrics")]
+        axum_prometheus::metrics::counter!("background_task_restarts_total", "task" => name.clone())
+            .increment(1);
+        registry.set(&name, health);
+        tokio::select! {
+            () = token.cancelled() => {
+                registry.set(&name, TaskHealth::Stopped);
+                return;
+            }
+            () = tokio::time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(policy.max_backoff);
+    }
+}
+
+/// Waits for `tasks`, already cancelled, to stop; those still running at
+/// `deadline` are aborted.
+pub(crate) async fn join_tasks(tasks: Vec<SupervisedTask>, deadline: Instant) {
+    for SupervisedTask { name, mut handle } in tasks {
+        if tokio::time::timeout_at(deadline, &mut handle)
+            .await
+            .is_err()
+        {
+            tracing::warn!(task = %name, "Background task did not stop in time; aborting it");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast(policy: RestartPolicy) -> RestartPolicy {
+        policy.with_backoff(Duration::from_millis(10), Duration::from_secs(5))
+    }
+
+    async fn wait_for(registry: &TaskRegistry, name: &str, health: impl Fn(&TaskHealth) -> bool) {
+        for _ in 0..400 {
+            if registry.health(name).as_ref().is_some_and(&health) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("task {name} is {:?}", registry.health(name));
+    }
+
+    #[tokio::test]
+    async fn failing_and_panicking_tasks_are_restarted_up_to_the_limit() {
+        let registry = TaskRegistry::default();
+        let runs = Arc::new(AtomicU32::new(0));
+        let counted = runs.clone();
+        let _task = spawn_supervised(
+            "flaky".into(),
+            fast(RestartPolicy::on_failure()).with_max_restarts(3),
+            CancellationToken::new(),
+            registry.clone(),
+            move |_| {
+                let run = counted.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if run.is_multiple_of(2) {
+                        Err(Error::internal("broker unavailable"))
+                    } else {
+                        panic!("consumer panicked")
+                    }
+                }
+            },
+        );
+
+        wait_for(&registry, "flaky", |health| {
+            matches!(health, TaskHealth::Failed { .. })
+        })
+        .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+        assert!(!registry.readiness().is_ready());
+    }
+
+    #[tokio::test]
+    async fn tasks_stop_when_cancelled_and_are_joined() {
+        let registry = TaskRegistry::default();
+        let token = CancellationToken::new();
+        let task = spawn_supervised(
+            "worker".into(),
+            fast(RestartPolicy::always()),
+            token.clone(),
+            registry.clone(),
+            |token: CancellationToken| async move {
+                token.cancelled().await;
+                Ok(())
+            },
+        );
+        assert_eq!(registry.health("worker"), Some(TaskHealth::Running));
+        assert!(registry.readiness().is_ready());
+
+        token.cancel();
+        join_tasks(vec![task], Instant::now() + Duration::from_secs(5)).await;
+        assert_eq!(registry.health("worker"), Some(TaskHealth::Stopped));
+    }
+
+    #[tokio::test]
+    async fn a_finished_task_is_not_restarted_on_failure_only() {
+        let registry = TaskRegistry::default();
+        let _task = spawn_supervised(
+            "migration".into(),
+            fast(RestartPolicy::on_failure()),
+            CancellationToken::new(),
+            registry.clone(),
+            |_| async { Ok(()) },
+        );
+
+        wait_for(&registry, "migration", |health| {
+            *health == TaskHealth::Finished
+        })
+        .await;
+        assert!(registry.readiness().is_ready());
+    }
+}
@@ -262,3 +262,329 @@ async fn test_fluent_router_route_service() {
         .unwrap();
     assert_eq!(&body[..], b"route service response");
 }
+
+#[tokio::test]
+async fn test_start_with_listener_serves_on_the_given_listener() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let fluent_router = FluentRouter::without_state(Config::new())
+        .unwrap()
+        .route("/hello", get(|| async { "hello" }));
+    let server = tokio::spawn(fluent_router.start_with_listener(listener));
+
+    let response = reqwest::get(format!("http://{addr}/hello")).await.unwrap();
+    let status = response.status();
+    let body = response.text().await.unwrap();
+    server.abort();
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "hello");
+}
+
+#[tokio::test]
+async fn test_spawn_serves_on_an_ephemeral_port_and_drains_on_shutdown() {
+    use crate::ShutdownPhase;
+    use std::time::Duration;
+
+    let mut config = Config::new();
+    config.http.bind_addr = "0.0.0.0".to_string();
+    config.http.bind_port = 0;
+    let server = FluentRouter::without_state(config)
+        .unwrap()
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                "done"
+            }),
+        )
+        .spawn()
+        .await
+        .unwrap();
+    let addr = server.local_addr();
+    assert!(addr.ip().is_loopback());
+    assert_ne!(addr.port(), 0);
+    assert_eq!(server.base_url(), format!("http://{addr}"));
+
+    let mut phases = server.shutdown_notifier().subscribe();
+    let in_flight = tokio::spawn(reqwest::get(format!("{}/slow", server.base_url())));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    server.shutdown().await.unwrap();
+
+    // The request in flight completes; new connections are refused.
+    let response = in_flight.await.unwrap().unwrap();
+    assert_eq!(response.text().await.unwrap(), "done");
+    assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    assert_eq!(phases.recv().await.unwrap(), ShutdownPhase::Initiated);
+    assert!(matches!(
+        phases.recv().await.unwrap(),
+        ShutdownPhase::GracePeriodStarted { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_server_keeps_serving_during_the_pre_stop_delay() {
+    use std::time::{Duration, Instant};
+
+    let mut config = Config::new();
+    config.http.bind_addr = "127.0.0.1".to_string();
+    config.http.bind_port = 0;
+    config.http.shutdown_pre_stop_delay = Duration::from_millis(500);
+    let server = FluentRouter::without_state(config)
+        .unwrap()
+        .with_readiness_check(|_| async { crate::Readiness::ready() })
+        .setup_readiness()
+        .route("/hello", get(|| async { "hello" }))
+        .spawn()
+        .await
+        .unwrap();
+    let base_url = server.base_url();
+    let addr = server.local_addr();
+    // A new client per request, so each one opens a new connection.
+    let get = |path: &str| reqwest::get(format!("{base_url}{path}"));
+
+    let started = Instant::now();
+    let shutdown = tokio::spawn(server.shutdown());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Readiness fails, but new connections are still served.
+    let ready = get("/ready").await.unwrap();
+    assert_eq!(ready.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let hello = get("/hello").await.unwrap();
+    assert_eq!(hello.text().await.unwrap(), "hello");
+
+    shutdown.await.unwrap().unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(500));
+    assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+}
+
+#[tokio::test]
+async fn test_requests_still_in_flight_are_aborted_when_the_grace_period_ends() {
+    use crate::ShutdownPhase;
+    use std::time::{Duration, Instant};
+
+    let mut config = Config::new();
+    config.http.bind_addr = "127.0.0.1".to_string();
+    config.http.bind_port = 0;
+    config.http.shutdown_timeout = Duration::from_millis(800);
+    let server = FluentRouter::without_state(config)
+        .unwrap()
+        .route("/poll", get(std::future::pending::<&str>))
+        .spawn()
+        .await
+        .unwrap();
+
+    let mut phases = server.shutdown_notifier().subscribe();
+    let long_poll = tokio::spawn(reqwest::get(format!("{}/poll", server.base_url())));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let started = Instant::now();
+    server.shutdown().await.unwrap();
+
+    // The long poll no longer holds the server past its grace period.
+    assert!(started.elapsed() < Duration::from_secs(2));
+    // The client sees the connection closed, or a 503 if it was flushed first.
+    if let Ok(response) = long_poll.await.unwrap() {
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+    assert_eq!(phases.recv().await.unwrap(), ShutdownPhase::Initiated);
+    assert!(matches!(
+        phases.recv().await.unwrap(),
+        ShutdownPhase::GracePeriodStarted { .. }
+    ));
+    assert_eq!(
+        phases.recv().await.unwrap(),
+        ShutdownPhase::GracePeriodEnded
+    );
+}
+
+#[tokio::test]
+async fn test_shutdown_waits_for_the_shutdown_hooks() {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+
+    let flushed = Arc::new(AtomicBool::new(false));
+    let mut config = Config::new();
+    config.http.bind_addr = "127.0.0.1".to_string();
+    config.http.bind_port = 0;
+    let server = FluentRouter::without_state(config)
+        .unwrap()
+        .on_shutdown("flush", 0, Duration::from_secs(5), {
+            let flushed = flushed.clone();
+            move || async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                flushed.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+        .spawn()
+        .await
+        .unwrap();
+
+    server.shutdown().await.unwrap();
+    assert!(flushed.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_shutdown_hooks_run_after_the_grace_period_is_used_up() {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+
+    let flushed = Arc::new(AtomicBool::new(false));
+    let mut config = Config::new();
+    config.http.bind_addr = "127.0.0.1".to_string();
+    config.http.bind_port = 0;
+    config.http.shutdown_timeout = Duration::from_millis(800);
+    let server = FluentRouter::without_state(config)
+        .unwrap()
+        .route("/poll", get(std::future::pending::<&str>))
+        .on_shutdown("flush outbox", 0, Duration::from_secs(5), {
+            let flushed = flushed.clone();
+            move || async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                flushed.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+        .spawn()
+        .await
+        .unwrap();
+
+    // The long poll outlives the drain, which ends with it aborted.
+    let long_poll = tokio::spawn(reqwest::get(format!("{}/poll", server.base_url())));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let started = std::time::Instant::now();
+    server.shutdown().await.unwrap();
+    let _ = long_poll.await;
+
+    assert!(
+        flushed.load(Ordering::SeqCst),
+        "the hook runs in the share of shutdown_timeout reserved for it"
+    );
+    assert!(
+        started.elapsed() < Duration::from_secs(2),
+        "shutdown as a whole stays close to shutdown_timeout"
+    );
+}
+
+#[tokio::test]
+async fn test_background_tasks_are_cancelled_and_joined_on_shutdown() {
+    use crate::{RestartPolicy, TaskHealth};
+    use tokio_util::sync::CancellationToken;
+
+    let mut config = Config::new();
+    config.http.bind_addr = "127.0.0.1".to_string();
+    config.http.bind_port = 0;
+    let router = FluentRouter::without_state(config).unwrap().spawn_task(
+        "worker",
+        RestartPolicy::on_failure(),
+        |token: CancellationToken| async move {
+            token.cancelled().await;
+            Ok(())
+        },
+    );
+    let tasks = router.tasks();
+    let server = router.spawn().await.unwrap();
+    assert_eq!(tasks.health("worker"), Some(TaskHealth::Running));
+
+    server.shutdown().await.unwrap();
+    assert_eq!(tasks.health("worker"), Some(TaskHealth::Stopped));
+}
+
+#[tokio::test]
+async fn test_proxy_protocol_header_becomes_connect_info() {
+    use axum::extract::ConnectInfo;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = Config::new();
+    config.http.proxy_protocol = Some(crate::ProxyProtocol::V1);
+    let fluent_router = FluentRouter::without_state(config).unwrap().route(
+        "/peer",
+        get(|ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>| async move { peer.to_string() }),
+    );
+    let server = tokio::spawn(fluent_router.start_with_listener(listener));
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 80\r\n\
+              GET /peer HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n",
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    // Without the header, the connection is closed unanswered.
+    let mut direct = tokio::net::TcpStream::connect(addr).await.unwrap();
+    direct
+        .write_all(b"GET /peer HTTP/1.1\r\nhost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+    let mut unanswered = String::new();
+    let _ = direct.read_to_string(&mut unanswered).await;
+    server.abort();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+    assert!(response.ends_with("203.0.113.7:51234"), "{response}");
+    assert_eq!(unanswered, "");
+}
+
+#[tokio::test]
+async fn test_admin_listener_serves_health_routes_apart_from_the_public_one() {
+    let admin_port = {
+        let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        probe.local_addr().unwrap().port()
+    };
+
+    let mut config = Config::new();
+    config.http.bind_addr = "127.0.0.1".into();
+    config.http.bind_port = 0;
+    config.http.admin = Some(crate::HttpAdminConfig::new(admin_port));
+    let server = FluentRouter::without_state(config)
+        .unwrap()
+        .route("/hello", get(|| async { "hello" }))
+        .setup_liveness()
+        .spawn()
+        .await
+        .unwrap();
+    let public = server.local_addr();
+
+    let admin_live = format!("http://127.0.0.1:{admin_port}/live");
+    let mut admin_status = None;
+    for _ in 0..50 {
+        if let Ok(response) = reqwest::get(&admin_live).await {
+            admin_status = Some(response.status());
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let public_live = reqwest::get(format!("http://{public}/live")).await.unwrap();
+    let public_hello = reqwest::get(format!("http://{public}/hello"))
+        .await
+        .unwrap();
+
+    assert_eq!(admin_status, Some(StatusCode::OK));
+    assert_eq!(public_live.status(), StatusCode::NOT_FOUND);
+    assert_eq!(public_hello.status(), StatusCode::OK);
+
+    // The admin listener shuts down together with the public one.
+    server.shutdown().await.unwrap();
+    assert!(
+        reqwest::get(&admin_live).await.is_err(),
+        "the admin listener closes with the public one"
+    );
+}
@@ -0,0 +1,283 @@
+//! Tests for the effective-config route (`http.debug_config_route`)
+
+use crate::fluent::tests::{create_config_with_toml, prepare_config_for_test};
+use crate::{Config, FluentRouter, HttpMiddleware, HttpMiddlewareConfig, Sensitive};
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header},
+};
+use tower::ServiceExt;
+
+async fn debug_config_app() -> Router {
+    let config = prepare_config_for_test(create_config_with_toml(
+        r#"
+debug_config_route = "/debug/config"
+debug_config_role = "config-reader"
+
+[http.basic_auth]
+mode = "either"
+
+[[http.basic_auth.users]]
+username = "admin"
+password = "hunter2-password"
+
+[[http.basic_auth.api_keys]]
+key = "hunter2-api-key"
+roles = ["config-reader"]
+
+[[http.basic_auth.api_keys]]
+key = "other-api-key"
+"#,
+    ));
+
+    FluentRouter::without_state(config)
+        .unwrap()
+        .setup_middleware()
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+fn get(uri: &str, api_key: Option<&str>) -> Request<Body> {
+    let mut request = Request::builder().uri(uri);
+    if let Some(key) = api_key {
+        request = request.header("X-API-Key", key);
+    }
+    request.body(Body::empty()).unwrap()
+}
+
+async fn body_text(response: axum::response::Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_debug_config_requires_authentication() {
+    let app = debug_config_app().await;
+
+    let response = app.oneshot(get("/debug/config", None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_debug_config_requires_the_configured_role() {
+    let app = debug_config_app().await;
+
+    let response = app
+        .oneshot(get("/debug/config", Some("other-api-key")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_debug_config_serves_redacted_json() {
+    let app = debug_config_app().await;
+
+    let response = app
+        .oneshot(get("/debug/config", Some("hunter2-api-key")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+    let body = body_text(response).await;
+    assert!(!body.contains("hunter2"), "secrets must be masked: {body}");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["http"]["bind_addr"], "127.0.0.1");
+    assert_eq!(json["http"]["basic_auth"]["users"][0]["username"], "admin");
+    assert_eq!(json["http"]["basic_auth"]["users"][0]["password"], "****");
+    assert_eq!(json["http"]["basic_auth"]["api_keys"][0]["key"], "****");
+    assert!(json["is_production"].is_boolean());
+}
+
+#[tokio::test]
+async fn test_debug_config_serves_toml_on_request() {
+    let app = debug_config_app().await;
+
+    let response = app
+        .clone()
+        .oneshot(get("/debug/config?format=toml", Some("hunter2-api-key")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/toml");
+    let table: toml::Table = body_text(response).await.parse().unwrap();
+    assert_eq!(table["http"]["bind_port"].as_integer(), Some(3000));
+
+    let response = app
+        .oneshot(get("/debug/config?format=yaml", Some("hunter2-api-key")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_debug_config_renders_app_settings_when_enabled() {
+    #[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+    struct AppSettings {
+        region: String,
+        api_token: Sensitive<String>,
+    }
+
+    impl crate::ValidateAppConfig for AppSettings {}
+
+    let toml = r#"
+region = "eu-west-1"
+api_token = "hunter2-token"
+
+[http]
+max_payload_size_bytes = "1KiB"
+debug_config_route = "/debug/config"
+debug_config_role = "config-reader"
+
+[http.basic_auth]
+mode = "api_key"
+
+[[http.basic_auth.api_keys]]
+key = "hunter2-api-key"
+groups = ["config-reader"]
+"#;
+    let load = || {
+        let mut config: Config<AppSettings> = toml.parse().unwrap();
+        config.http.with_metrics = false;
+        config.http.middleware = Some(HttpMiddlewareConfig::Exclude(vec![
+            HttpMiddleware::RateLimiting,
+        ]));
+        config
+    };
+
+    for (config, region) in [
+        (load().render_app(), serde_json::json!("eu-west-1")),
+        (load(), serde_json::Value::Null),
+    ] {
+        let app = FluentRouter::without_state(config)
+            .unwrap()
+            .setup_middleware()
+            .await
+            .unwrap()
+            .into_inner();
+        let response = app
+            .oneshot(get("/debug/config", Some("hunter2-api-key")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_text(response).await;
+        assert!(!body.contains("hunter2"), "secrets must be masked: {body}");
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["region"], region);
+    }
+}
+
+#[tokio::test]
+async fn test_debug_config_route_is_opt_in() {
+    let config = prepare_config_for_test(create_config_with_toml(
+        r#"
+[http.basic_auth]
+mode = "api_key"
+
+[[http.basic_auth.api_keys]]
+key = "hunter2-api-key"
+"#,
+    ));
+    let app = FluentRouter::without_state(config)
+        .unwrap()
+        .route("/", axum::routing::get(|| async { "ok" }))
+        .setup_middleware()
+        .await
+        .unwrap()
+        .into_inner();
+
+    let response = app
+        .oneshot(get("/debug/config", Some("hunter2-api-key")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_debug_config_is_not_served_on_the_admin_listener() {
+    let mut config = prepare_config_for_test(create_config_with_toml(
+        r#"
+debug_config_route = "/debug/config"
+debug_config_role = "config-reader"
+
+[http.basic_auth]
+mode = "api_key"
+
+[[http.basic_auth.api_keys]]
+key = "hunter2-api-key"
+roles = ["config-reader"]
+"#,
+    ));
+    let mut admin = crate::HttpAdminConfig::new(9090);
+    admin.bind_addr = Some("127.0.0.1".into());
+    config.http.admin = Some(admin);
+
+    let fluent_router = FluentRouter::without_state(config)
+        .unwrap()
+        .setup_middleware()
+        .await
+        .unwrap();
+    let admin = fluent_router.admin.clone().unwrap().with_state(());
+    let app = fluent_router.into_inner();
+
+    let response = admin.oneshot(get("/debug/config", None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .oneshot(get("/debug/config", Some("hunter2-api-key")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_debug_config_follows_config_updates() {
+    let load = |bind_port: u16| {
+        let mut config = prepare_config_for_test(create_config_with_toml(
+            r#"
+debug_config_route = "/debug/config"
+debug_config_role = "config-reader"
+
+[http.basic_auth]
+mode = "api_key"
+
+[[http.basic_auth.api_keys]]
+key = "hunter2-api-key"
+roles = ["config-reader"]
+"#,
+        ));
+        config.http.bind_port = bind_port;
+        config
+    };
+    let (tx, rx) = tokio::sync::watch::channel(std::sync::Arc::new(load(3000)));
+
+    let app = FluentRouter::without_state(load(3000))
+        .unwrap()
+        .setup_middleware()
+        .await
+        .unwrap()
+        .with_config_updates(rx)
+        .into_inner();
+
+    let bind_port = |app: Router| async move {
+        let response = app
+            .oneshot(get("/debug/config", Some("hunter2-api-key")))
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        json["http"]["bind_port"].clone()
+    };
+    assert_eq!(bind_port(app.clone()).await, 3000);
+
+    tx.send_replace(std::sync::Arc::new(load(4000)));
+    for _ in 0..100 {
+        if bind_port(app.clone()).await == 4000 {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("the reloaded configuration was not served");
+}
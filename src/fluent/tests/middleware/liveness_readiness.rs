@@ -27,3 +27,51 @@ async fn test_liveness_readiness_individual_control() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_health_routes_move_to_the_admin_router() {
+    let mut config = Config::new();
+    config.http.admin = Some(crate::HttpAdminConfig::new(9090));
+
+    let fluent_router = FluentRouter::without_state(config)
+        .unwrap()
+        .setup_liveness()
+        .setup_readiness();
+    let mut admin = fluent_router.admin.clone().unwrap().with_state(());
+    let mut app = fluent_router.into_inner();
+
+    for route in ["/live", "/ready"] {
+        let request = || Request::builder().uri(route).body(Body::empty()).unwrap();
+        let public = app.call(request()).await.unwrap();
+        assert_eq!(public.status(), StatusCode::NOT_FOUND, "{route}");
+        // Readiness may report 503 (e.g. no database with `postgres`), but it is routed.
+        let admin = admin.call(request()).await.unwrap();
+        assert_ne!(admin.status(), StatusCode::NOT_FOUND, "{route}");
+    }
+}
+
+#[tokio::test]
+async fn test_readiness_fails_once_shutdown_is_initiated() {
+    let fluent_router = FluentRouter::without_state(Config::new())
+        .unwrap()
+        .with_readiness_check(|_| async { crate::Readiness::ready() })
+        .setup_readiness();
+    let notifier = fluent_router.shutdown_notifier().clone();
+    let mut app = fluent_router.into_inner();
+
+    notifier.emit(crate::ShutdownPhase::Initiated);
+    let response = app
+        .call(
+            Request::builder()
+                .uri("/ready")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"Shutting down\n");
+}
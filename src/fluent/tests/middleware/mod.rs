@@ -10,6 +10,8 @@ mod api_versioning;
 mod concurrency_limit;
 #[cfg(feature = "cors")]
 mod cors;
+#[cfg(feature = "basic-auth")]
+mod debug_config;
 #[cfg(feature = "deduplication")]
 mod deduplication;
 #[cfg(feature = "security-headers")]
@@ -22,6 +24,8 @@ mod metrics;
 mod opentelemetry;
 #[cfg(feature = "path-normalization")]
 mod path_normalization;
+#[cfg(any(feature = "basic-auth", feature = "cors", feature = "rate-limiting"))]
+mod reload;
 #[cfg(feature = "sensitive-headers")]
 mod sensitive_headers;
 #[cfg(feature = "session")]
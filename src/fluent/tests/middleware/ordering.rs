@@ -36,156 +36,168 @@ const MIDDLEWARE_ORDER: &[Step] = &[
     },
     Step {
         pos: 2,
+        setup: "setup_debug_config",
+        role: "Effective-config route (added before auth so the auth `route_layer` covers it)",
+        feature: None,
+    },
+    Step {
+        pos: 3,
         setup: "setup_browser_login_redirect",
         role: "Redirect unauthenticated browsers to the login route",
         feature: Some("keycloak"),
     },
     Step {
-        pos: 3,
+        pos: 4,
         setup: "setup_oidc",
         role: "OIDC authentication (bearer JWT and/or auth-code identity)",
         feature: Some("keycloak"),
     },
     Step {
-        pos: 4,
+        pos: 5,
         setup: "setup_basic_auth",
         role: "HTTP Basic Auth and API-key authentication",
         feature: Some("basic-auth"),
     },
     Step {
-        pos: 5,
+        pos: 6,
         setup: "setup_proxy_oidc",
         role: "Reverse-proxy header authentication (fail-closed in production)",
         feature: None,
     },
     Step {
-        pos: 6,
+        pos: 7,
+        setup: "setup_client_cert_auth",
+        role: "Client-certificate (mutual TLS) authentication",
+        feature: Some("tls"),
+    },
+    Step {
+        pos: 8,
         setup: "setup_public_files",
         role: "Public static files (added after auth so they need no credentials)",
         feature: None,
     },
     Step {
-        pos: 7,
+        pos: 9,
         setup: "setup_oidc_routes",
         role: "OIDC login / callback / logout routes",
         feature: Some("keycloak"),
     },
     Step {
-        pos: 8,
+        pos: 10,
         setup: "setup_user_span",
         role: "Record the authenticated username on the tracing span",
         feature: None,
     },
     Step {
-        pos: 9,
+        pos: 11,
         setup: "setup_session_handling",
         role: "Session cookie store (wraps the auth layers)",
         feature: Some("session"),
     },
     Step {
-        pos: 10,
+        pos: 12,
         setup: "setup_deduplication",
         role: "Request deduplication by request id",
         feature: Some("deduplication"),
     },
     Step {
-        pos: 11,
+        pos: 13,
         setup: "setup_concurrency_limit",
         role: "Max concurrent in-flight requests",
         feature: Some("concurrency-limit"),
     },
     Step {
-        pos: 12,
+        pos: 14,
         setup: "setup_max_payload_size",
         role: "Request body size limit",
         feature: Some("payload-limit"),
     },
     Step {
-        pos: 13,
+        pos: 15,
         setup: "setup_compression",
         role: "Response compression / request decompression",
         feature: Some("compression"),
     },
     Step {
-        pos: 14,
+        pos: 16,
         setup: "setup_path_normalization",
         role: "Trailing-slash path normalization",
         feature: Some("path-normalization"),
     },
     Step {
-        pos: 15,
+        pos: 17,
         setup: "setup_sensitive_headers",
         role: "Mark sensitive headers for redaction in logs",
         feature: Some("sensitive-headers"),
     },
     Step {
-        pos: 16,
+        pos: 18,
         setup: "setup_api_versioning",
         role: "Extract the API version from path / header / query",
         feature: Some("api-versioning"),
     },
     Step {
-        pos: 17,
+        pos: 19,
         setup: "setup_cors",
         role: "CORS preflight handling and response headers",
         feature: Some("cors"),
     },
     Step {
-        pos: 18,
+        pos: 20,
         setup: "setup_helmet",
         role: "Security headers (Helmet)",
         feature: Some("security-headers"),
     },
     Step {
-        pos: 19,
+        pos: 21,
         setup: "setup_logging",
         role: "Request / response logging",
         feature: None,
     },
     Step {
-        pos: 20,
+        pos: 22,
         setup: "setup_metrics",
         role: "Prometheus metrics layer and the `/metrics` endpoint",
         feature: Some("metrics"),
     },
     Step {
-        pos: 21,
+        pos: 23,
         setup: "setup_readiness",
         role: "Readiness probe endpoint (benefits from timeout / rate limiting)",
         feature: None,
     },
     Step {
-        pos: 22,
+        pos: 24,
         setup: "setup_timeout",
         role: "Request timeout boundary",
         feature: None,
     },
     Step {
-        pos: 23,
+        pos: 25,
         setup: "setup_rate_limiting",
         role: "Per-IP rate limiting (rejects excess load early)",
         feature: Some("rate-limiting"),
     },
     Step {
-        pos: 24,
+        pos: 26,
         setup: "setup_request_id",
         role: "Generate / propagate the `x-request-id` header (early, for tracing)",
         feature: None,
     },
     Step {
-        pos: 25,
+        pos: 27,
         setup: "setup_liveness",
         role: "Liveness probe endpoint (always reachable, very early)",
         feature: None,
     },
     Step {
-        pos: 26,
+        pos: 28,
         setup: "setup_catch_panic",
         role: "Panic recovery — catches panics from every inner layer (outermost)",
         feature: None,
     },
     Step {
-        pos: 27,
+        pos: 29,
         setup: "setup_fallback_files",
         role: "Fallback static files (must be installed last)",
         feature: None,
@@ -274,8 +286,8 @@ mod tests {
 
     /// Catch-panic is outer to the request pipeline (not merely wrapping the leaf
     /// handler). A handler panic is converted to a `500` by the outermost layer;
-    /// and because the request-id layer (position 24) is *inner* to catch-panic
-    /// (26), the panic unwinds past it and the synthesized 500 carries **no**
+    /// and because the request-id layer (position 26) is *inner* to catch-panic
+    /// (28), the panic unwinds past it and the synthesized 500 carries **no**
     /// `x-request-id` — whereas a normal response does (see
     /// `application_route_traverses_request_id_and_security_layers`). That
     /// asymmetry distinguishes "catch-panic is outermost" from "catch-panic merely
@@ -310,15 +322,15 @@ mod tests {
         );
         assert!(
             !resp.headers().contains_key("x-request-id"),
-            "the panic-recovery 500 must not carry a request id — the request-id layer (24) is \
-             inner to catch-panic (26), so it cannot stamp the outermost-synthesized response"
+            "the panic-recovery 500 must not carry a request id — the request-id layer (26) is \
+             inner to catch-panic (28), so it cannot stamp the outermost-synthesized response"
         );
     }
 
     /// The liveness and readiness probes are wired as endpoints by
-    /// `setup_middleware` (validates positions 21 and 25 are reachable). Note the
+    /// `setup_middleware` (validates positions 23 and 27 are reachable). Note the
     /// liveness endpoint is added *outer* to the request-id and Helmet layers
-    /// (positions 24 and 18) precisely so it short-circuits as cheaply as
+    /// (positions 26 and 20) precisely so it short-circuits as cheaply as
     /// possible — which is why those response headers are asserted on a normal
     /// application route below, not on `/live`.
     #[tokio::test]
@@ -349,8 +361,8 @@ mod tests {
     }
 
     /// A normal application route traverses the full stack, so the request-id
-    /// layer (position 24) stamps the response and, when enabled, Helmet
-    /// (position 18) adds security headers (validates those layers wrap ordinary
+    /// layer (position 26) stamps the response and, when enabled, Helmet
+    /// (position 20) adds security headers (validates those layers wrap ordinary
     /// routes, unlike the short-circuiting health endpoints above).
     #[tokio::test]
     async fn application_route_traverses_request_id_and_security_layers() {
@@ -378,7 +390,7 @@ mod tests {
         );
         assert!(
             resp.headers().contains_key("x-request-id"),
-            "request-id layer (24) must stamp a generated id on every application response"
+            "request-id layer (26) must stamp a generated id on every application response"
         );
 
         #[cfg(feature = "security-headers")]
@@ -388,7 +400,7 @@ mod tests {
                 headers.contains_key("x-content-type-options")
                     || headers.contains_key("content-security-policy")
                     || headers.contains_key("x-frame-options"),
-                "Helmet (18) must apply a security header to application responses"
+                "Helmet (20) must apply a security header to application responses"
             );
         }
 
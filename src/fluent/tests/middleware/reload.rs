@@ -0,0 +1,181 @@
+//! Tests for live configuration updates (`FluentRouter::with_config_updates`)
+
+use crate::{Config, FluentRouter};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    response::Response,
+    routing::get,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::watch;
+use tower::ServiceExt;
+
+/// Sends requests built by `request` until `done` accepts a response, giving
+/// the config-update task time to apply the published configuration.
+async fn eventually(
+    app: &Router,
+    request: impl Fn() -> Request<Body>,
+    done: impl Fn(&Response) -> bool,
+) -> Response {
+    for _ in 0..100 {
+        let response = app.clone().oneshot(request()).await.unwrap();
+        if done(&response) {
+            return response;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the reloaded configuration was not applied");
+}
+
+#[cfg(feature = "basic-auth")]
+#[tokio::test]
+async fn test_reloaded_api_keys_apply_without_restart() {
+    let toml = |key: &str| {
+        format!(
+            r#"
+            [http]
+            max_payload_size_bytes = "1KiB"
+            [http.basic_auth]
+            mode = "api_key"
+            [[http.basic_auth.api_keys]]
+            key = "{key}"
+            "#
+        )
+    };
+    let config: Config = toml("old-key").parse().unwrap();
+    let (tx, rx) = watch::channel(Arc::new(config.clone()));
+
+    let app = FluentRouter::without_state(config)
+        .unwrap()
+        .route("/", get(|| async { "ok" }))
+        .setup_basic_auth()
+        .unwrap()
+        .with_config_updates(rx)
+        .into_inner();
+
+    let with_key = |key: &'static str| {
+        move || {
+            Request::builder()
+                .uri("/")
+                .header("X-API-Key", key)
+                .body(Body::empty())
+                .unwrap()
+        }
+    };
+    let response = app.clone().oneshot(with_key("old-key")()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    tx.send_replace(Arc::new(toml("new-key").parse().unwrap()));
+
+    eventually(&app, with_key("new-key"), |r| r.status() == StatusCode::OK).await;
+    let response = app.clone().oneshot(with_key("old-key")()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[cfg(feature = "rate-limiting")]
+#[tokio::test]
+async fn test_reloaded_rate_limit_applies_without_restart() {
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+
+    let mut config = Config::<()>::from_toml("[http]\nmax_payload_size_bytes = \"1KiB\"").unwrap();
+    config.http.max_requests_per_sec = 1;
+    let (tx, rx) = watch::channel(Arc::new(config.clone()));
+
+    let app = FluentRouter::without_state(config.clone())
+        .unwrap()
+        .route("/", get(|| async { "ok" }))
+        .setup_rate_limiting()
+        .with_config_updates(rx)
+        .into_inner();
+
+    let request = || {
+        let mut request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+        request
+    };
+    assert_eq!(
+        app.clone().oneshot(request()).await.unwrap().status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.clone().oneshot(request()).await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    config.http.max_requests_per_sec = 1000;
+    tx.send_replace(Arc::new(config));
+
+    eventually(&app, request, |r| r.status() == StatusCode::OK).await;
+    for _ in 0..10 {
+        assert_eq!(
+            app.clone().oneshot(request()).await.unwrap().status(),
+            StatusCode::OK
+        );
+    }
+}
+
+#[cfg(feature = "cors")]
+#[tokio::test]
+async fn test_reloaded_cors_origins_apply_without_restart() {
+    use crate::HttpCorsConfig;
+
+    let mut config = Config::<()>::from_toml("[http]\nmax_payload_size_bytes = \"1KiB\"").unwrap();
+    config.http.cors = Some(HttpCorsConfig::default().with_allowed_origins(vec![
+        "https://a.example".to_string(),
+        "https://b.example".to_string(),
+    ]));
+    let (tx, rx) = watch::channel(Arc::new(config.clone()));
+
+    let app = FluentRouter::without_state(config.clone())
+        .unwrap()
+        .route("/", get(|| async { "ok" }))
+        .setup_cors()
+        .with_config_updates(rx)
+        .into_inner();
+
+    let from = |origin: &'static str| {
+        move || {
+            Request::builder()
+                .uri("/")
+                .header("Origin", origin)
+                .body(Body::empty())
+                .unwrap()
+        }
+    };
+    let allowed = |r: &Response| r.headers().contains_key("access-control-allow-origin");
+
+    // Every configured origin is allowed, not only the last one.
+    for origin in ["https://a.example", "https://b.example"] {
+        let response = app.clone().oneshot(from(origin)()).await.unwrap();
+        assert_eq!(
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .unwrap(),
+            origin
+        );
+    }
+    let response = app
+        .clone()
+        .oneshot(from("https://c.example")())
+        .await
+        .unwrap();
+    assert!(!allowed(&response));
+
+    config.http.cors =
+        Some(HttpCorsConfig::default().with_allowed_origins(vec!["https://c.example".to_string()]));
+    tx.send_replace(Arc::new(config));
+
+    eventually(&app, from("https://c.example"), allowed).await;
+    let response = app
+        .clone()
+        .oneshot(from("https://a.example")())
+        .await
+        .unwrap();
+    assert!(!allowed(&response));
+}
@@ -0,0 +1,548 @@
+//! HTTPS termination for [`FluentRouter::start`](super::FluentRouter::start).
+//!
+//! [`TlsListener`] accepts TCP connections and completes their TLS handshakes
+//! off the accept loop, so a slow or stalled client cannot hold up others. The
+//! certificate is served by a [`CertificateResolver`], which a background task
+//! reloads from disk when the files change: new handshakes pick up the new
+//! certificate while established connections carry on undisturbed.
+//!
+//! [`tls_extensions`] attaches the verified client certificate (if any) of a
+//! connection to each of its requests for `[http.client_cert_auth]`.
+
+use {
+    super::{client_cert::ClientCertificate, server::connect_info},
+    crate::{
+        Error, Result,
+        config::{HttpTlsConfig, TlsVersion},
+    },
+    axum::serve::Listener,
+    http::Extensions,
+    rustls::{
+        RootCertStore, ServerConfig, SupportedProtocolVersion,
+        crypto::CryptoProvider,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+        server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier},
+        sign::CertifiedKey,
+    },
+    std::{
+        fs, io,
+        net::SocketAddr,
+        path::{Path, PathBuf},
+        sync::{Arc, PoisonError, RwLock},
+        time::{Duration, SystemTime},
+    },
+    tokio::task::JoinSet,
+    tokio_rustls::{TlsAcceptor, server::TlsStream},
+    tokio_util::task::AbortOnDropHandle,
+};
+
+/// Modification time and length of a file, used to detect changes.
+type FileStamp = Option<(Option<SystemTime>, u64)>;
+
+/// A TCP listener that yields connections once their TLS handshake completes.
+pub(crate) struct TlsListener<L: Listener> {
+    tcp: L,
+    acceptor: TlsAcceptor,
+    handshake_timeout: Duration,
+    handshakes: JoinSet<Option<(TlsStream<L::Io>, SocketAddr)>>,
+    /// Certificate reload task, stopped when the listener is dropped.
+    _reload: Option<AbortOnDropHandle<()>>,
+}
+
+impl<L: Listener<Addr = SocketAddr>> TlsListener<L> {
+    /// Serves TLS on `tcp` with the certificate, key and client CAs of `config`.
+    pub(crate) fn new(tcp: L, config: &HttpTlsConfig) -> Result<Self> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let resolver = Arc::new(CertificateResolver::load(
+            Path::new(&config.cert_file),
+            Path::new(&config.key_file),
+            provider.clone(),
+        )?);
+        let server_config = server_config(config, provider, resolver.clone())?;
+
+        let reload = (!config.reload_interval.is_zero()).then(|| {
+            AbortOnDropHandle::new(tokio::spawn(reload_certificates(
+                resolver,
+                config.reload_interval,
+            )))
+        });
+
+        Ok(Self {
+            tcp,
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            handshake_timeout: config.handshake_timeout,
+            handshakes: JoinSet::new(),
+            _reload: reload,
+        })
+    }
+}
+
+impl<L: Listener<Addr = SocketAddr>> Listener for TlsListener<L> {
+    type Io = TlsStream<L::Io>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let Self {
+            tcp,
+            acceptor,
+            handshake_timeout,
+            handshakes,
+            ..
+        } = self;
+        loop {
+            tokio::select! {
+                Some(done) = handshakes.join_next(), if !handshakes.is_empty() => {
+                    if let Ok(Some(connection)) = done {
+                        return connection;
+                    }
+                }
+                (stream, addr) = Listener::accept(tcp) => {
+                    let acceptor = acceptor.clone();
+                    let timeout = *handshake_timeout;
+                    handshakes.spawn(async move {
+                        match tokio::time::timeout(timeout, acceptor.accept(stream)).await {
+                            Ok(Ok(stream)) => Some((stream, addr)),
+                            Ok(Err(e)) => {
+                                tracing::debug!(peer = %addr, error = %e, "TLS handshake failed");
+                                None
+                            }
+                            Err(_) => {
+                                tracing::debug!(peer = %addr, "TLS handshake timed out");
+                                None
+                            }
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.tcp.local_addr()
+    }
+}
+
+/// The extensions of a [`TlsListener`] connection.
+///
+/// Every request carries `ConnectInfo<SocketAddr>`. When the client presented
+/// a certificate, its leaf is parsed once per connection and attached to every
+/// request as well.
+pub(crate) fn tls_extensions<I>(stream: &TlsStream<I>, addr: &SocketAddr) -> Extensions {
+    let mut extensions = connect_info(*addr);
+    let (_, session) = stream.get_ref();
+    if let Some(certificate) = session
+        .peer_certificates()
+        .and_then(|chain| chain.first())
+        .and_then(|leaf| ClientCertificate::from_der(leaf))
+    {
+        extensions.insert(Arc::new(certificate));
+    }
+    extensions
+}
+
+/// Builds the rustls server configuration for `config`.
+fn server_config(
+    config: &HttpTlsConfig,
+    provider: Arc<CryptoProvider>,
+    resolver: Arc<CertificateResolver>,
+) -> Result<ServerConfig> {
+    let versions: &[&SupportedProtocolVersion] = match config.min_version {
+        TlsVersion::Tls12 => &[&rustls::version::TLS13, &rustls::version::TLS12],
+        TlsVersion::Tls13 => &[&rustls::version::TLS13],
+    };
+    let builder =
+        ServerConfig::builder_with_provider(provider.clone()).with_protocol_versions(versions)?;
+    let builder = match &config.client_ca_file {
+        Some(path) => {
+            let roots = Arc::new(load_client_roots(Path::new(path))?);
+            let verifier = WebPkiClientVerifier::builder_with_provider(roots, provider)
+                .build()
+                .map_err(|e| Error::tls(format!("Invalid client CA bundle {path}: {e}")))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder.with_cert_resolver(resolver);
+    server_config.alpn_protocols = config
+        .alpn
+        .iter()
+        .map(|protocol| protocol.as_bytes().to_vec())
+        .collect();
+    Ok(server_config)
+}
+
+fn load_client_roots(path: &Path) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certificates(path)? {
+        roots.add(cert).map_err(|e| {
+            Error::tls(format!("Invalid CA certificate in {}: {e}", path.display()))
+        })?;
+    }
+    Ok(roots)
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<std::result::Result<Vec<_>, _>>())
+        .map_err(|e| {
+            Error::tls(format!(
+                "Cannot read certificates from {}: {e}",
+                path.display()
+            ))
+        })?;
+    if certs.is_empty() {
+        return Err(Error::tls(format!(
+            "No PEM certificate found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Serves the current certificate and swaps in a new one when the files change.
+#[derive(Debug)]
+pub(crate) struct CertificateResolver {
+    cert_file: PathBuf,
+    key_file: PathBuf,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<LoadedCertificate>,
+}
+
+#[derive(Debug)]
+struct LoadedCertificate {
+    key: Arc<CertifiedKey>,
+    stamps: [FileStamp; 2],
+}
+
+impl CertificateResolver {
+    fn load(cert_file: &Path, key_file: &Path, provider: Arc<CryptoProvider>) -> Result<Self> {
+        let stamps = [stamp(cert_file), stamp(key_file)];
+        let key = load_certified_key(cert_file, key_file, &provider)?;
+        Ok(Self {
+            cert_file: cert_file.to_path_buf(),
+            key_file: key_file.to_path_buf(),
+            provider,
+            current: RwLock::new(LoadedCertificate {
+                key: Arc::new(key),
+                stamps,
+            }),
+        })
+    }
+
+    /// Reloads the certificate and key if either file changed since the last
+    /// attempt. Returns whether a new certificate is now served.
+    ///
+    /// A pair that fails to load leaves the previous certificate in place; it
+    /// is retried once the files change again.
+    pub(crate) fn reload_if_changed(&self) -> Result<bool> {
+        // Stamp before reading, so a change made while reading is seen next time.
+        let stamps = [stamp(&self.cert_file), stamp(&self.key_file)];
+        if stamps == self.lock_read().stamps {
+            return Ok(false);
+        }
+        let loaded = load_certified_key(&self.cert_file, &self.key_file, &self.provider);
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        current.stamps = stamps;
+        current.key = Arc::new(loaded?);
+        Ok(true)
+    }
+
+    fn lock_read(&self) -> std::sync::RwLockReadGuard<'_, LoadedCertificate> {
+        self.current.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.lock_read().key.clone())
+    }
+}
+
+fn load_certified_key(
+    cert_file: &Path,
+    key_file: &Path,
+    provider: &CryptoProvider,
+) -> Result<CertifiedKey> {
+    let certs = load_certificates(cert_file)?;
+    let key = PrivateKeyDer::from_pem_file(key_file).map_err(|e| {
+        Error::tls(format!(
+            "Cannot read the private key from {}: {e}",
+            key_file.display()
+        ))
+    })?;
+    CertifiedKey::from_der(certs, key, provider).map_err(|e| {
+        Error::tls(format!(
+            "Invalid TLS certificate {} or key {}: {e}",
+            cert_file.display(),
+            key_file.display()
+        ))
+    })
+}
+
+fn stamp(path: &Path) -> FileStamp {
+    // `metadata` follows symlinks, so a swapped Kubernetes secret shows up as
+    // a change even though the link itself is untouched.
+    fs::metadata(path)
+        .ok()
+        .map(|meta| (meta.modified().ok(), meta.len()))
+}
+
+/// Checks the certificate files every `interval`, until the task is aborted.
+async fn reload_certificates(resolver: Arc<CertificateResolver>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match resolver.reload_if_changed() {
+            Ok(true) => tracing::info!(
+                cert_file = %resolver.cert_file.display(),
+                "Reloaded the TLS certificate"
+            ),
+            Ok(false) => {}
+            Err(e) => tracing::warn!(
+                error = %e,
+                "Failed to reload the TLS certificate; still serving the previous one"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::get};
+    use rustls::{ClientConfig, pki_types::ServerName};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+    use tokio_rustls::{TlsConnector, client::TlsStream as ClientTlsStream};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls");
+
+    fn fixture(name: &str) -> String {
+        format!("{FIXTURES}/{name}")
+    }
+
+    /// Copies the server certificate and key into a fresh temporary directory.
+    fn temp_certificate() -> (PathBuf, HttpTlsConfig) {
+        let dir = std::env::temp_dir().join(format!("axum-conf-tls-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::copy(fixture("server.pem"), dir.join("tls.crt")).unwrap();
+        fs::copy(fixture("server.key"), dir.join("tls.key")).unwrap();
+        let config = HttpTlsConfig::new(
+            dir.join("tls.crt").display().to_string(),
+            dir.join("tls.key").display().to_string(),
+        );
+        (dir, config)
+    }
+
+    async fn bind(config: &HttpTlsConfig) -> Result<TlsListener<TcpListener>> {
+        TlsListener::new(TcpListener::bind("127.0.0.1:0").await.unwrap(), config)
+    }
+
+    async fn serve(config: &HttpTlsConfig) -> SocketAddr {
+        let listener = bind(config).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/", get(|| async { "hello" }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    async fn connect(addr: SocketAddr, alpn: &[&str]) -> ClientTlsStream<TcpStream> {
+        let roots = load_client_roots(Path::new(&fixture("ca.pem"))).unwrap();
+        let mut config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        config.alpn_protocols = alpn.iter().map(|p| p.as_bytes().to_vec()).collect();
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap()
+    }
+
+    async fn get_root(stream: &mut ClientTlsStream<TcpStream>) -> String {
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        let mut buf = [0; 1024];
+        while !response.ends_with(b"hello") {
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(n > 0, "connection closed");
+            response.extend_from_slice(&buf[..n]);
+        }
+        String::from_utf8(response).unwrap()
+    }
+
+    /// Connects presenting the `client.pem` certificate and sends one request.
+    async fn request_as_client(addr: SocketAddr, path: &str) -> String {
+        let roots = load_client_roots(Path::new(&fixture("ca.pem"))).unwrap();
+        let client_cert = load_certificates(Path::new(&fixture("client.pem"))).unwrap();
+        let client_key = PrivateKeyDer::from_pem_file(fixture("client.key")).unwrap();
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_root_certificates(roots)
+                .with_client_auth_cert(client_cert, client_key)
+                .unwrap();
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let mut stream = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        let request =
+            format!("GET {path} HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    fn leaf(stream: &ClientTlsStream<TcpStream>) -> CertificateDer<'static> {
+        stream.get_ref().1.peer_certificates().unwrap()[0].clone()
+    }
+
+    #[tokio::test]
+    async fn serves_https_and_negotiates_alpn() {
+        let config = HttpTlsConfig::new(fixture("server.pem"), fixture("server.key"));
+        let addr = serve(&config).await;
+
+        let h2 = connect(addr, &["h2", "http/1.1"]).await;
+        assert_eq!(h2.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+
+        let mut http1 = connect(addr, &["http/1.1"]).await;
+        assert_eq!(http1.get_ref().1.alpn_protocol(), Some(&b"http/1.1"[..]));
+        let response = get_root(&mut http1).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn changed_certificate_is_served_without_dropping_connections() {
+        let (dir, mut config) = temp_certificate();
+        config.reload_interval = Duration::from_millis(20);
+        let addr = serve(&config).await;
+
+        let mut established = connect(addr, &["http/1.1"]).await;
+        let original = load_certificates(Path::new(&fixture("server.pem"))).unwrap();
+        assert_eq!(leaf(&established), original[0]);
+
+        // A trailing newline changes the length, so the change is seen even on
+        // filesystems with coarse modification times.
+        let renewed = load_certificates(Path::new(&fixture("server2.pem"))).unwrap();
+        let mut pem = fs::read(fixture("server2.pem")).unwrap();
+        pem.push(b'\n');
+        fs::write(&config.cert_file, pem).unwrap();
+        fs::copy(fixture("server2.key"), &config.key_file).unwrap();
+
+        let mut served = original[0].clone();
+        for _ in 0..250 {
+            served = leaf(&connect(addr, &["http/1.1"]).await);
+            if served == renewed[0] {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(served, renewed[0], "the renewed certificate was not served");
+
+        let response = get_root(&mut established).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn client_certificate_identity_reaches_role_extractors() {
+        crate::role!(OrdersWriter => "orders-writer");
+        crate::role!(Admin => "admin");
+
+        let mut config = crate::Config::<()>::default();
+        let mut tls = HttpTlsConfig::new(fixture("server.pem"), fixture("server.key"));
+        tls.client_ca_file = Some(fixture("ca.pem"));
+        let mut client_cert_auth = crate::HttpClientCertAuthConfig::default();
+        client_cert_auth
+            .role_mappings
+            .push(crate::config::ClientCertRoleMapping {
+                attribute: crate::CertAttribute::SanUri,
+                value: "spiffe://cluster.local/ns/shop/sa/orders".into(),
+                roles: vec!["orders-writer".into()],
+            });
+        config.http.tls = Some(tls.clone());
+        config.http.client_cert_auth = Some(client_cert_auth);
+
+        let router = crate::FluentRouter::without_state(config)
+            .unwrap()
+            .route(
+                "/orders",
+                get(|identity: crate::WithRole<OrdersWriter>| async move {
+                    format!(
+                        "{} {:?} {:?}",
+                        identity.user, identity.method, identity.groups
+                    )
+                }),
+            )
+            .route("/admin", get(|_: crate::WithRole<Admin>| async { "admin" }))
+            .setup_client_cert_auth()
+            .into_inner();
+
+        let listener = bind(&tls).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = crate::fluent::server::Server::new(&Default::default());
+        tokio::spawn(server.serve(
+            listener,
+            router,
+            tls_extensions,
+            std::future::pending(),
+            tokio_util::sync::CancellationToken::new(),
+        ));
+
+        let response = request_as_client(addr, "/orders").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(
+            response.ends_with(r#"orders-service ClientCertificate ["payments", "billing"]"#),
+            "{response}"
+        );
+
+        let response = request_as_client(addr, "/admin").await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn invalid_files_are_rejected_and_keep_the_previous_certificate() {
+        let mismatched = HttpTlsConfig::new(fixture("server.pem"), fixture("server2.key"));
+        let err = bind(&mismatched).await.err().unwrap();
+        assert_eq!(err.kind(), crate::ErrorKind::Tls);
+
+        let missing = HttpTlsConfig::new(fixture("missing.pem"), fixture("server.key"));
+        let err = bind(&missing).await.err().unwrap();
+        assert!(err.to_string().contains("missing.pem"), "{err}");
+
+        let (dir, config) = temp_certificate();
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let resolver = CertificateResolver::load(
+            Path::new(&config.cert_file),
+            Path::new(&config.key_file),
+            provider,
+        )
+        .unwrap();
+        let before = resolver.lock_read().key.clone();
+        fs::write(&config.key_file, "not a key").unwrap();
+        let reloaded = resolver.reload_if_changed();
+        let unchanged = resolver.reload_if_changed();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(reloaded.is_err());
+        assert!(
+            !unchanged.unwrap(),
+            "a broken pair is not retried until it changes"
+        );
+        assert!(Arc::ptr_eq(&before, &resolver.lock_read().key));
+    }
+}
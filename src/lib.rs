@@ -70,6 +70,7 @@
 //! | `opentelemetry` | Distributed tracing with OTLP export |
 //! | `basic-auth` | HTTP Basic Auth and API key authentication |
 //! | `rustls` | TLS support |
+//! | `tls` | HTTPS termination with certificate hot-reload and client-certificate auth (`[http.tls]`, enables `rustls`) |
 //!
 //! # Authentication
 //!
@@ -288,20 +289,38 @@ pub use config::HttpOidcConfig;
 #[cfg(feature = "opentelemetry")]
 pub use config::OpenTelemetryConfig;
 pub use config::{
-    AllRoles, AnyRole, ApplicationRole, ApplicationRoles, AuthMethod, AuthenticatedIdentity, Byte,
-    Config, CorsHeader, CorsMethod, HttpConfig, HttpCorsConfig, HttpDeduplicationConfig,
-    HttpMiddleware, HttpMiddlewareConfig, HttpProxyOidcConfig, HttpXFrameConfig, LogFormat,
-    LoggingConfig, MetricBucketsConfig, MetricMatch, SharedIdentity, StaticDirConfig, StaticDirRoute,
-    TracingBase, WithRole, XFrameOptions,
+    AllRoles, AnyRole, ApplicationRole, ApplicationRoles, AuthMethod, AuthenticatedIdentity,
+    BindTarget, Byte, Config, CorsHeader, CorsMethod, HttpAdminConfig, HttpConfig,
+    HttpConnectionConfig, HttpCorsConfig, HttpDeduplicationConfig, HttpMiddleware,
+    HttpMiddlewareConfig, HttpProxyOidcConfig, HttpXFrameConfig, LogFormat, LoggingConfig,
+    MetricBucketsConfig, MetricMatch, ProxyProtocol, SharedIdentity, StaticDirConfig,
+    StaticDirRoute, TracingBase, WithRole, XFrameOptions,
 };
 #[cfg(feature = "basic-auth")]
 pub use config::{BasicAuthApiKey, BasicAuthMode, BasicAuthUser, HttpBasicAuthConfig};
+#[cfg(feature = "tls")]
+pub use config::{
+    CertAttribute, ClientCertRoleMapping, HttpClientCertAuthConfig, HttpTlsConfig, TlsVersion,
+};
 #[cfg(feature = "circuit-breaker")]
 pub use config::{CircuitBreakerConfig, CircuitBreakerTargetConfig};
+pub use config::{ConfigReload, ConfigWatcher};
+pub use config::{
+    DirectorySecretProvider, EnvSecretProvider, FileSecretProvider, SecretProvider,
+    register_secret_provider,
+};
+#[cfg(feature = "encryption")]
+pub use config::{ENCRYPTION_KEY_FILE_VAR, ENCRYPTION_KEY_VAR, EncryptionKey};
 #[cfg(feature = "session")]
 pub use config::{SameSiteConfig, SessionStoreConfig};
+pub use config::{Severity, ValidateAppConfig, ValidationIssue, ValidationReport};
 pub use error::{Error, ErrorKind, ErrorResponse};
-pub use fluent::{FluentRouter, Readiness, ShutdownNotifier, ShutdownPhase};
+#[cfg(unix)]
+pub use fluent::UnixPeer;
+pub use fluent::{
+    FluentRouter, Readiness, RestartPolicy, ServerHandle, ServerListener, ShutdownNotifier,
+    ShutdownPhase, TaskHealth, TaskRegistry,
+};
 pub use utils::{ApiVersion, Sensitive};
 
 /// Convenience alias for results returned by this crate, fixing the error type
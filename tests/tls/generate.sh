@@ -0,0 +1,31 @@
+#!/usr/bin/env sh
+# Regenerates the TLS test fixtures: a CA, two server certificates for
+# `localhost` (the second one exercises hot reloading) and a client
+# certificate for mutual TLS. Everything is valid for 100 years.
+set -eu
+cd "$(dirname "$0")"
+DAYS=36500
+
+openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:P-256 -nodes \
+    -keyout ca.key -out ca.pem -days "$DAYS" -subj "/CN=axum-conf test CA"
+
+issue() {
+    name=$1 subj=$2 ext=$3
+    openssl req -newkey ec -pkeyopt ec_paramgen_curve:P-256 -nodes \
+        -keyout "$name.key" -out "$name.csr" -subj "$subj"
+    printf '%s\n' "$ext" > "$name.ext"
+    openssl x509 -req -in "$name.csr" -CA ca.pem -CAkey ca.key -CAcreateserial \
+        -out "$name.pem" -days "$DAYS" -extfile "$name.ext"
+    rm "$name.csr" "$name.ext"
+}
+
+issue server "/CN=localhost" \
+    "subjectAltName=DNS:localhost,IP:127.0.0.1
+extendedKeyUsage=serverAuth"
+issue server2 "/CN=localhost" \
+    "subjectAltName=DNS:localhost,IP:127.0.0.1
+extendedKeyUsage=serverAuth"
+issue client "/CN=orders-service/OU=payments/OU=billing" \
+    "subjectAltName=DNS:orders.internal,URI:spiffe://cluster.local/ns/shop/sa/orders
+extendedKeyUsage=clientAuth"
+rm -f ca.srl